@@ -0,0 +1,101 @@
+//! # rocket_csrf_token_derive
+//!
+//! Derive macros for [rocket_csrf_token](https://crates.io/crates/rocket_csrf_token). Enable the
+//! `derive` feature of that crate rather than depending on this one directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Ident, LitStr};
+
+/// The field holding the authenticity token unless `#[csrf(field = "...")]` says otherwise.
+const DEFAULT_FIELD: &str = "authenticity_token";
+
+/// Implements `rocket_csrf_token::CsrfProtected` for a struct with named fields, typically a
+/// `FromForm` one, reading the authenticity token from its `authenticity_token` field.
+///
+/// The field can be of any type implementing `AsRef<str>`, e.g. `String` or `&str`. Another field
+/// is picked with the `csrf` attribute:
+///
+/// ```rust,ignore
+/// #[derive(FromForm, CsrfProtected)]
+/// #[csrf(field = "csrf")]
+/// struct Comment<'r> {
+///     csrf: &'r str,
+///     body: &'r str,
+/// }
+/// ```
+#[proc_macro_derive(CsrfProtected, attributes(csrf))]
+pub fn derive_csrf_protected(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Generates the `CsrfProtected` implementation for `input`.
+fn expand(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let field = token_field(&input)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "CsrfProtected requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "CsrfProtected can only be derived for structs",
+            ))
+        }
+    };
+    if !fields
+        .iter()
+        .any(|named| named.ident.as_ref() == Some(&field))
+    {
+        return Err(Error::new_spanned(
+            &input.ident,
+            format!("CsrfProtected requires a `{}` field", field),
+        ));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::rocket_csrf_token::CsrfProtected for #name #ty_generics #where_clause {
+            fn authenticity_token(&self) -> &str {
+                ::core::convert::AsRef::<str>::as_ref(&self.#field)
+            }
+        }
+    })
+}
+
+/// Returns the field named by `#[csrf(field = "...")]`, or `authenticity_token`.
+fn token_field(input: &DeriveInput) -> Result<Ident, Error> {
+    let mut field = Ident::new(DEFAULT_FIELD, Span::call_site());
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("csrf"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("field") {
+                let name: LitStr = meta.value()?.parse()?;
+                field = name.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported csrf attribute, expected `field = \"...\"`"))
+            }
+        })?;
+    }
+
+    Ok(field)
+}
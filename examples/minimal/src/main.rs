@@ -9,7 +9,7 @@ use dotenv;
 use rocket::form::Form;
 use rocket::request::{FlashMessage, FromRequest};
 use rocket::response::{Flash, Redirect};
-use rocket_csrf_token::CsrfToken;
+use rocket_csrf_token::{AuthenticityToken, CsrfToken};
 use rocket_dyn_templates::Template;
 
 #[derive(Serialize)]
@@ -61,12 +61,12 @@ fn index() -> Redirect {
 
 #[get("/comments/new")]
 fn new(
-    csrf_token: CsrfToken,
+    authenticity_token: AuthenticityToken,
     flash: Option<FlashMessage>,
     _authenticated: Authenticated,
 ) -> Template {
     let template_context = TemplateContext {
-        authenticity_token: csrf_token.authenticity_token().unwrap().to_string(),
+        authenticity_token: authenticity_token.into_inner(),
         flash: flash.map(|flash| flash.message().to_string()),
     };
 
@@ -121,33 +121,72 @@
 
 use base64::{engine::general_purpose, Engine as _};
 use bcrypt::{hash, verify, BcryptError};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
 use rand::{distributions::Standard, Rng};
+#[cfg(feature = "json")]
+use rocket::data::{self, FromData};
 use rocket::{
-    async_trait, error,
+    async_trait,
+    config::SecretKey,
+    error,
     fairing::{self, Fairing as RocketFairing, Info, Kind},
+    form::{self, name::Name, Contextual, Form, FromForm},
     http::{
-        // ContentType,
-        Cookie,
-        Status,
+        uri::Origin, ContentType, Cookie, CookieJar, Method, RawStr, SameSite, Status, StatusClass,
     },
     info,
-    request::{FromRequest, Outcome},
-    response::{Responder, Response},
+    request::{FlashMessage, FromRequest, Outcome},
+    response::{Flash, Responder, Response},
+    route::{self, Handler, Route},
+    serde::{Deserialize, Serialize},
     time::{Duration, OffsetDateTime},
-    Data, Request, Rocket, State,
+    warn, Data, Request, Rocket, State,
 };
+use sha2::{Digest, Sha256, Sha512};
 use std::{
     borrow::Cow,
-    fmt,
-    //io::Cursor
+    collections::{hash_map::Entry, HashMap},
+    env, fmt,
+    io::Cursor,
+    net::IpAddr,
+    ops::{Deref, RangeInclusive},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, OnceLock,
+    },
+    time::Instant,
 };
+use subtle::ConstantTimeEq;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+#[cfg(feature = "derive")]
+pub use rocket_csrf_token_derive::CsrfProtected;
+pub use subtle::Choice;
 
 // Constants for CSRF handling
 const BCRYPT_COST: u32 = 8;
+const BCRYPT_COSTS: RangeInclusive<u32> = 4..=31;
+const BCRYPT_HASH_LEN: usize = 60;
+const BCRYPT_MAX_INPUT_LEN: usize = 72;
 const HEADER_NAME: &str = "X-CSRF-Token";
-const _PARAM_NAME: &str = "authenticity_token";
-const _PARAM_META_NAME: &str = "csrf-param";
-const _TOKEN_META_NAME: &str = "csrf-token";
+const MAX_HEADER_TOKENS: usize = 4;
+const REFRESH_HEADER_NAME: &str = "X-CSRF-Refresh";
+const TOKEN_ID_HEADER_NAME: &str = "X-CSRF-Token-Id";
+const PREVIOUS_TOKEN_ID_HEADER_NAME: &str = "X-CSRF-Previous-Token-Id";
+const LATENCY_BUCKETS: usize = 32;
+const PARAM_NAME: &str = "authenticity_token";
+const PARAM_META_NAME: &str = "csrf-param";
+const TOKEN_META_NAME: &str = "csrf-token";
+const MESSAGE_SCOPE: &str = "websocket-message";
+const STEP_UP_SCOPE: &str = "step-up";
+#[cfg(feature = "json")]
+const CLAIMS_LIFETIME: Duration = Duration::hours(1);
+const BODY_PEEK_LIMIT: usize = 512;
+const FLASH_KIND: &str = "csrf-token";
+const PRUNE_THRESHOLD: usize = 1024;
 
 /// Configuration for Cross-Site Request Forgery (CSRF) protection. It allows you to customize
 /// settings related to CSRF token management, including token lifespan, cookie name, and token length.
@@ -157,8 +196,127 @@ pub struct CsrfConfig {
     lifespan: Option<Duration>,
     /// The name of the CSRF cookie that stores the token.
     cookie_name: Cow<'static, str>,
-    /// The length of the CSRF token in bytes.
-    cookie_len: usize,
+    /// The environment marker appended to the cookie name.
+    env_suffix: Option<Cow<'static, str>>,
+    /// The length of the CSRF token in bytes, if set regardless of the strategy.
+    cookie_len: Option<usize>,
+    /// The lengths of the CSRF token in bytes set for individual strategies, by strategy name.
+    strategy_token_lens: Vec<(&'static str, usize)>,
+    /// The `SameSite` policy of the CSRF cookie.
+    same_site: SameSite,
+    /// Whether the `SameSite=None` attribute is left out for browsers that mishandle it.
+    same_site_none_fallback: bool,
+    /// Whether the CSRF cookie carries the `Secure` flag.
+    secure: bool,
+    /// Whether the CSRF cookie carries the `HttpOnly` flag.
+    http_only: bool,
+    /// The `Domain` attribute of the CSRF cookie, None for a host-only cookie.
+    cookie_domain: Option<Cow<'static, str>>,
+    /// The `Path` attribute of the CSRF cookie.
+    cookie_path: Cow<'static, str>,
+    /// Whether this is the development preset, which enables verbose logging.
+    development: bool,
+    /// Previous configurations whose outstanding tokens remain verifiable.
+    legacy_configs: Vec<CsrfConfig>,
+    /// The strategies tokens are verified with against every session token during a migration.
+    migration_set: Vec<TokenStrategy>,
+    /// How long the session token replaced by a rotation remains valid.
+    rotation_grace: Option<Duration>,
+    /// Whether the `CsrfQuery` guard may accept tokens from the query string.
+    query_tokens: bool,
+    /// Whether authenticity tokens are bound to the path of the request they are minted on.
+    route_scoping: bool,
+    /// Whether CSRF meta tags are injected into the `<head>` of HTML responses to GET requests.
+    meta_tag_injection: bool,
+    /// Whether verification rejects session tokens whose length differs from `cookie_len`.
+    strict_length: bool,
+    /// The maximum number of session tokens a submitted token is verified against.
+    max_verify_attempts: usize,
+    /// The schemes used to verify authenticity tokens, the first of which also mints them.
+    strategies: Vec<TokenStrategy>,
+    /// Where clients can fetch a fresh token, advertised when a stale token is rejected.
+    refresh_hint: Option<String>,
+    /// The status of responses rejecting requests that came without a CSRF session.
+    no_session_status: Status,
+    /// Whether the CSRF cookie is looked up regardless of the case of its name.
+    case_insensitive_cookie_lookup: bool,
+    /// The transform applied to freshly generated token bytes before encoding.
+    token_transform: Option<TokenTransformer>,
+    /// The predicate selecting requests for which no CSRF cookie is issued.
+    skip_issue_when: Option<SkipIssue>,
+    /// Whether whitespace embedded in session and submitted tokens is ignored.
+    lenient_whitespace: bool,
+    /// Whether the CSRF cookie is only set on 2xx and 3xx responses.
+    issue_on_success_only: bool,
+    /// The counters behind `Fairing::metrics`, shared by every clone of this configuration.
+    metrics: Arc<MetricsCounters>,
+    /// The name of the request header carrying the authenticity token.
+    header_name: Cow<'static, str>,
+    /// The `Authorization` scheme under which clients may send the token.
+    authorization_scheme: Option<Cow<'static, str>>,
+    /// The path at which the fairing mounts an endpoint returning a fresh token.
+    token_endpoint: Option<String>,
+    /// How long step-up tokens remain valid, None when step-up tokens are disabled.
+    step_up: Option<Duration>,
+    /// Whether the current or the legacy CSRF cookies are preferred when several are present.
+    cookie_preference: CookiePreference,
+    /// Whether the CSRF cookies that lost to the preferred one are removed.
+    clear_stale_cookies: bool,
+    /// The key signing CSRF cookies in place of Rocket's private cookies, if migrated.
+    shared_key: Option<SharedKey>,
+    /// The media types of request bodies carrying form fields.
+    form_content_types: Vec<String>,
+    /// Whether the fairing only issues CSRF cookies over HTTPS.
+    https_only: bool,
+    /// The name of the form field, query parameter and JSON field carrying the authenticity token.
+    param_name: Cow<'static, str>,
+    /// The path of the form field carrying the authenticity token, e.g. `form[_csrf]`, if it
+    /// differs from the parameter name.
+    form_field: Option<Cow<'static, str>>,
+    /// Whether only the issuance fairing runs, leaving verification to handlers.
+    manual_only: bool,
+    /// Whether the `VerifyAllRequests` check rejects mutating requests without a valid token.
+    reject_invalid: bool,
+    /// The digest pre-hashing secrets too long for bcrypt.
+    prehash: PrehashAlgo,
+    /// The cost factor of the bcrypt hashes minted as authenticity tokens.
+    bcrypt_cost: u32,
+    /// The path on which the fairing removes the CSRF cookie.
+    logout_path: Option<String>,
+    /// The session cookie the session token is derived from, instead of a CSRF cookie.
+    derive_from_session: Option<String>,
+    /// The SSO cookie the session token is extracted from, along with the extractor.
+    secret_from_cookie: Option<CookieSecret>,
+    /// Whether authenticity tokens are prefixed with a checksum.
+    token_checksum: bool,
+    /// Whether authenticity tokens are bound to the HTTP method of the request.
+    method_binding: bool,
+    /// A token accepted unconditionally outside of production, for end-to-end test suites.
+    test_token: Option<String>,
+    /// Whether responses carry an authenticity token in a header as well.
+    cdn_safe_token: bool,
+    /// The paths on which GET requests must carry a valid authenticity token.
+    protected_get_paths: Vec<String>,
+    /// The methods of the requests that must carry a valid authenticity token.
+    protected_methods: Vec<Method>,
+    /// Whether the durations of verifications are recorded in the metrics.
+    latency_recording: bool,
+    /// Whether responses announce the replaced and new session tokens after a rotation.
+    rotation_headers: bool,
+    /// Whether HEAD requests are issued a CSRF cookie like GET requests.
+    issue_on_head: bool,
+    /// Whether authenticity tokens verify only once.
+    single_use: bool,
+    /// Whether verification is skipped for requests a browser marks as same-origin.
+    same_origin_shortcut: bool,
+    /// The store recording the tokens already used under single use.
+    nonce_store: NonceStoreHandle,
+    /// The key signing CSRF cookie values underneath Rocket's protection, if any.
+    extra_signing_key: Option<SharedKey>,
+    /// The limit on CSRF cookies issued to a client IP address within a window, if any.
+    issue_rate_limit: Option<IssueRateLimit>,
+    /// The sink recording the outcome of every verification.
+    audit_sink: AuditSinkHandle,
 }
 
 impl Default for CsrfConfig {
@@ -166,18 +324,161 @@ impl Default for CsrfConfig {
     /// - Lifespan: 1 day
     /// - Cookie Name: "csrf_token"
     /// - Token Length: 32 bytes
+    /// - SameSite: Strict
+    /// - Maximum verification attempts: 4
+    /// - Token strategy: bcrypt
     ///
     /// This function returns a new CsrfConfig instance with the default settings.
     fn default() -> Self {
         Self {
             lifespan: Some(Duration::days(1)),
             cookie_name: "csrf_token".into(),
-            cookie_len: 32,
+            env_suffix: None,
+            cookie_len: None,
+            strategy_token_lens: Vec::new(),
+            same_site: SameSite::Strict,
+            same_site_none_fallback: false,
+            secure: true,
+            http_only: true,
+            cookie_domain: None,
+            cookie_path: Cow::Borrowed("/"),
+            development: false,
+            legacy_configs: Vec::new(),
+            migration_set: Vec::new(),
+            rotation_grace: None,
+            query_tokens: false,
+            route_scoping: false,
+            meta_tag_injection: false,
+            strict_length: false,
+            max_verify_attempts: 4,
+            strategies: vec![TokenStrategy::Bcrypt],
+            refresh_hint: None,
+            no_session_status: Status::Forbidden,
+            case_insensitive_cookie_lookup: false,
+            token_transform: None,
+            skip_issue_when: None,
+            lenient_whitespace: false,
+            issue_on_success_only: false,
+            metrics: Arc::new(MetricsCounters::default()),
+            authorization_scheme: None,
+            token_endpoint: None,
+            step_up: None,
+            cookie_preference: CookiePreference::default(),
+            clear_stale_cookies: false,
+            shared_key: None,
+            form_content_types: vec![
+                "application/x-www-form-urlencoded".to_string(),
+                "multipart/form-data".to_string(),
+            ],
+            https_only: false,
+            param_name: Cow::Borrowed(PARAM_NAME),
+            form_field: None,
+            header_name: Cow::Borrowed(HEADER_NAME),
+            manual_only: false,
+            reject_invalid: false,
+            prehash: PrehashAlgo::default(),
+            bcrypt_cost: BCRYPT_COST,
+            logout_path: None,
+            derive_from_session: None,
+            secret_from_cookie: None,
+            token_checksum: false,
+            method_binding: false,
+            test_token: None,
+            cdn_safe_token: false,
+            protected_get_paths: Vec::new(),
+            protected_methods: vec![
+                Method::Post,
+                Method::Put,
+                Method::Patch,
+                Method::Delete,
+                Method::Connect,
+            ],
+            latency_recording: false,
+            rotation_headers: false,
+            issue_on_head: true,
+            single_use: false,
+            same_origin_shortcut: false,
+            nonce_store: NonceStoreHandle(Arc::new(MemoryNonceStore::default())),
+            extra_signing_key: None,
+            issue_rate_limit: None,
+            audit_sink: AuditSinkHandle(Arc::new(NoopAuditSink)),
         }
     }
 }
 
 impl CsrfConfig {
+    /// Creates a CsrfConfig suited for local development with the following settings:
+    /// - Lifespan: 1 hour
+    /// - Secure: false, so the cookie is accepted over plain HTTP
+    /// - SameSite: Lax
+    /// - Verbose logging of issued cookies
+    ///
+    /// This preset must not be used in production. The fairing logs a warning at ignition
+    /// whenever it is active.
+    pub fn development() -> Self {
+        Self {
+            lifespan: Some(Duration::hours(1)),
+            same_site: SameSite::Lax,
+            secure: false,
+            development: true,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a CsrfConfig from `ROCKET_CSRF_*` environment variables, for containerized
+    /// deployments. The following variables are read:
+    /// - `ROCKET_CSRF_COOKIE_NAME`: the name of the CSRF cookie
+    /// - `ROCKET_CSRF_LIFESPAN`: the lifespan of the CSRF cookie in seconds
+    /// - `ROCKET_CSRF_COOKIE_LEN`: the length of the CSRF token in bytes
+    /// - `ROCKET_CSRF_SECRET`: a key shared between instances, signing the CSRF cookies as with
+    ///   `migrate_to_shared_key` under its SHA-256 digest
+    /// - `ROCKET_CSRF_STRATEGY`: `time-window` to mint `TokenStrategy::TimeWindow` tokens under
+    ///   `ROCKET_CSRF_SECRET` instead of session-bound bcrypt tokens
+    /// - `ROCKET_CSRF_STEP`: the time step of `TokenStrategy::TimeWindow` in seconds, 300 by default
+    ///
+    /// Time-window tokens aren't bound to a session, so they are only used when explicitly
+    /// requested. Unset variables fall back to the defaults of `CsrfConfig::default`. Values that
+    /// fail to parse are ignored with a warning. The key encrypting the CSRF cookie itself is Rocket's
+    /// `secret_key`, which Rocket reads from `ROCKET_SECRET_KEY`.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(name) = env::var("ROCKET_CSRF_COOKIE_NAME") {
+            config.cookie_name = name.into();
+        }
+        if let Some(seconds) = env_number::<i64>("ROCKET_CSRF_LIFESPAN") {
+            config.lifespan = Some(Duration::seconds(seconds));
+        }
+        if let Some(len) = env_number("ROCKET_CSRF_COOKIE_LEN") {
+            config.cookie_len = Some(len);
+        }
+        let secret = env::var("ROCKET_CSRF_SECRET").ok();
+        if let Some(secret) = &secret {
+            config.shared_key = Some(SharedKey(Sha256::digest(secret.as_bytes()).into()));
+        }
+        match (env::var("ROCKET_CSRF_STRATEGY").ok().as_deref(), secret) {
+            (None, _) => {}
+            (Some("time-window"), Some(secret)) => {
+                let step = env_number("ROCKET_CSRF_STEP").unwrap_or(300);
+                config.strategies = vec![TokenStrategy::TimeWindow {
+                    step: Duration::seconds(step),
+                    secret: secret.into_bytes(),
+                }];
+            }
+            (Some("time-window"), None) => {
+                warn!("Ignoring ROCKET_CSRF_STRATEGY: time-window requires ROCKET_CSRF_SECRET.");
+            }
+            (Some(strategy), _) => {
+                warn!(
+                    "Ignoring ROCKET_CSRF_STRATEGY: unknown strategy {:?}.",
+                    strategy
+                );
+            }
+        }
+
+        config
+    }
+
     /// Sets the lifespan of the CSRF token cookie.
     /// # Arguments
     /// * `Option<rocket::Duration>` - The duration for which the CSRF token remains valid.
@@ -199,249 +500,5285 @@ impl CsrfConfig {
         self
     }
 
+    /// Sets the `SameSite` policy of the CSRF cookie.
+    /// # Arguments
+    /// * `policy` - The policy, `SameSite::Strict` by default.
+    ///
+    /// `Strict` keeps browsers from sending the cookie along with any cross-site request, which
+    /// hardens the double-submit pattern. Single-page applications navigated to from other sites
+    /// may relax it to `SameSite::Lax`. Applications embedded cross-site, e.g. in an iframe, need
+    /// `SameSite::None` for the cookie to reach them. Browsers only accept such cookies with the
+    /// `Secure` flag, which Rocket sets when TLS is enabled. See `with_same_site_none_fallback` for browsers mishandling `None`.
+    pub fn with_same_site(mut self, policy: SameSite) -> Self {
+        self.same_site = policy;
+        self
+    }
+
+    /// Sets whether the CSRF cookie carries the `Secure` flag.
+    /// # Arguments
+    /// * `secure` - Whether browsers only send the cookie over HTTPS, true by default.
+    ///
+    /// Browsers drop `Secure` cookies set over plain HTTP, except on `localhost`, so only disable
+    /// it for applications served over plain HTTP, as `CsrfConfig::development` does.
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Sets whether the CSRF cookie carries the `HttpOnly` flag.
+    /// # Arguments
+    /// * `http_only` - Whether scripts are kept from reading the cookie, true by default.
+    ///
+    /// Scripts never need the session token itself, as they send the authenticity token, so the
+    /// flag should only be disabled for clients that insist on reading the cookie.
+    pub fn with_http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Sets the `Domain` attribute of the CSRF cookie.
+    /// # Arguments
+    /// * `domain` - The domain browsers send the cookie to, along with its subdomains, e.g.
+    ///   `"example.com"` to share it between `app.example.com` and `api.example.com`.
+    ///
+    /// Without a domain, the cookie is host-only, i.e. only sent to the host that set it. Any
+    /// subdomain can then overwrite the cookie, so only share it with trusted subdomains.
+    pub fn with_cookie_domain(mut self, domain: impl Into<Cow<'static, str>>) -> Self {
+        self.cookie_domain = Some(domain.into());
+        self
+    }
+
+    /// Sets the `Path` attribute of the CSRF cookie.
+    /// # Arguments
+    /// * `path` - The path prefix browsers send the cookie to, `"/"` by default, e.g. `"/admin"`
+    ///   for an application mounted there behind a reverse proxy.
+    ///
+    /// Requests outside of the path don't carry the cookie, so the fairing issues them another
+    /// one. The path only scopes the cookie, as the session token is read the same way.
+    pub fn with_cookie_path(mut self, path: impl Into<Cow<'static, str>>) -> Self {
+        self.cookie_path = path.into();
+        self
+    }
+
+    /// Leaves out the `SameSite=None` attribute for browsers known to mishandle it.
+    /// # Arguments
+    /// * `enabled` - Whether such browsers get the cookie without the attribute.
+    ///
+    /// Some older browsers reject cookies with `SameSite=None` (Chrome 51 to 66, UC Browser
+    /// before 12.13.2) or treat them as `SameSite=Strict` (Safari on iOS 12 and macOS 10.14).
+    /// When enabled and the policy set with `with_same_site` is `SameSite::None`, the fairing
+    /// recognizes these browsers by their `User-Agent` header and sends them the CSRF cookie
+    /// without a `SameSite` attribute, which they treat as `None`. Other browsers get the standard
+    /// cookie. User-agent sniffing is brittle, so only enable this for audiences still on such
+    /// browsers.
+    pub fn with_same_site_none_fallback(mut self, enabled: bool) -> Self {
+        self.same_site_none_fallback = enabled;
+        self
+    }
+
+    /// Appends an environment marker to the name of the CSRF cookie.
+    /// # Arguments
+    /// * `suffix` - The environment marker, e.g. `"staging"`.
+    ///
+    /// This function modifies the CsrfConfig instance so that the cookie is named
+    /// `<cookie_name>_<suffix>` (e.g. `csrf_token_staging`). It prevents cookie collisions between
+    /// environments served from the same parent domain, such as staging and production.
+    pub fn with_env_suffix(mut self, suffix: impl Into<Cow<'static, str>>) -> Self {
+        self.env_suffix = Some(suffix.into());
+        self
+    }
+
     /// Sets the length of the CSRF token.
     /// # Arguments
     /// * `length` - The desired length of the CSRF token in bytes.
     ///
     /// This function modifies the CsrfConfig instance by setting the token length to the specified value.
     /// It is important to ensure that the token length is 16 bytes or larger.
+    ///
+    /// Without it, the length follows the primary token strategy (see `with_token_len_for`).
     pub fn with_cookie_len(mut self, length: usize) -> Self {
-        self.cookie_len = length;
+        self.cookie_len = Some(length);
         self
     }
-}
 
-/// Rocket fairing for CSRF protection. This fairing is responsible for handling and managing CSRF tokens
-/// during Rocket application runtime.
-pub struct Fairing {
-    config: CsrfConfig,
-}
+    /// Sets the length of the CSRF token used while a given strategy is the primary one.
+    /// # Arguments
+    /// * `strategy` - The strategy the length applies to. Only its variant matters, not its
+    ///   secret or other settings.
+    /// * `length` - The desired length of the CSRF token in bytes.
+    ///
+    /// Each strategy has a natural token length: 32 bytes for `TokenStrategy::Bcrypt`, whose
+    /// base64 encoding fits bcrypt's 72-byte input, and for the HMAC-based strategies, matching
+    /// the key size of HMAC-SHA256, but 16 bytes for `TokenStrategy::TimeWindow`, whose tokens
+    /// don't depend on the session token. The length of the primary strategy applies, so
+    /// switching strategies doesn't require retuning it. A length set through this function
+    /// takes precedence over `with_cookie_len`, which takes precedence over the defaults.
+    pub fn with_token_len_for(mut self, strategy: &TokenStrategy, length: usize) -> Self {
+        let name = strategy.name();
+        self.strategy_token_lens.retain(|(other, _)| *other != name);
+        self.strategy_token_lens.push((name, length));
+        self
+    }
 
-impl Default for Fairing {
-    /// Creates a default Fairing with the default CsrfConfig settings. This fairing can be used to
-    /// automatically manage CSRF tokens in a Rocket application.
+    /// Sets the previous configurations whose tokens should remain verifiable.
+    /// # Arguments
+    /// * `configs` - The configurations that were in use before the current one.
     ///
-    /// This function returns a new Fairing instance with the default CsrfConfig settings.
-    fn default() -> Self {
-        Self::new(CsrfConfig::default())
+    /// This function modifies the CsrfConfig instance so that `CsrfToken::verify` also attempts
+    /// verification against the session token read under each legacy configuration, in order.
+    /// It allows configuration changes (e.g. renaming the cookie) to roll out without
+    /// invalidating the authenticity tokens that were already handed out.
+    pub fn with_legacy_configs(mut self, configs: Vec<CsrfConfig>) -> Self {
+        self.legacy_configs = configs;
+        self
+    }
+
+    /// Sets the strategies tokens are verified with regardless of the cookie they are checked
+    /// against, for zero-downtime strategy changes.
+    /// # Arguments
+    /// * `strategies` - The strategies involved in the migration, typically the old and new ones.
+    ///
+    /// Normally the session token read under each configuration is only checked with the
+    /// strategies of that configuration. While a strategy change rolls out along with
+    /// `with_legacy_configs`, a client may hold a cookie of the old configuration and submit a
+    /// token minted under the new strategy, or the other way around. Every session token,
+    /// including the legacy ones, is additionally checked with each strategy of the migration
+    /// set, so all such combinations verify. Clear the set once the migration is complete.
+    pub fn with_migration_set(mut self, strategies: Vec<TokenStrategy>) -> Self {
+        self.migration_set = strategies;
+        self
+    }
+
+    /// Sets the grace window during which the session token replaced by `CsrfToken::rotate`
+    /// remains valid.
+    /// # Arguments
+    /// * `grace` - How long the previous session token is still accepted after a rotation.
+    ///
+    /// This function modifies the CsrfConfig instance so that authenticity tokens minted before a
+    /// rotation keep verifying until the window elapses. With `None`, rotation invalidates them
+    /// immediately.
+    pub fn with_rotation_grace(mut self, grace: Option<Duration>) -> Self {
+        self.rotation_grace = grace;
+        self
+    }
+
+    /// Allows the `CsrfQuery` guard to accept authenticity tokens from the query string.
+    /// # Arguments
+    /// * `enabled` - Whether query-string tokens are accepted.
+    ///
+    /// Tokens placed in URLs leak through browser history, server logs and the `Referer` header,
+    /// so this is disabled by default and the fairing logs a warning at ignition when enabled.
+    /// Only turn it on for GET-triggered actions that can't be changed to use a form or header.
+    pub fn with_query_tokens(mut self, enabled: bool) -> Self {
+        self.query_tokens = enabled;
+        self
+    }
+
+    /// Binds authenticity tokens to the route they are minted on.
+    /// # Arguments
+    /// * `enabled` - Whether route scoping is enabled.
+    ///
+    /// When enabled, the `CsrfToken` guard uses `request.uri().path()` as the token scope, so a
+    /// token minted on `/transfer` fails verification on `/delete`. Forms must then be rendered
+    /// and submitted on the same path, or minted explicitly with `CsrfToken::authenticity_token_for`.
+    pub fn with_route_scoping(mut self, enabled: bool) -> Self {
+        self.route_scoping = enabled;
+        self
+    }
+
+    /// Binds authenticity tokens to the HTTP method of the request they are minted in.
+    /// # Arguments
+    /// * `enabled` - Whether method binding is enabled.
+    ///
+    /// When enabled, the method of the current request is hashed into authenticity tokens along
+    /// with their scope and checked on verification, so a token minted for a GET-able preview
+    /// fails verification on a DELETE. Tokens for another method must be minted explicitly with
+    /// `CsrfToken::authenticity_token_for_method`.
+    pub fn with_method_binding(mut self, enabled: bool) -> Self {
+        self.method_binding = enabled;
+        self
+    }
+
+    /// Sets a pre-shared token that verification accepts unconditionally, for end-to-end test
+    /// suites that can't read cookies.
+    /// # Arguments
+    /// * `token` - The pre-shared token, or `None` to disable it.
+    ///
+    /// The token is only honored in debug builds or under `CsrfConfig::development`, and ignored
+    /// otherwise. Since it disables CSRF protection for anyone who knows it, the fairing logs a
+    /// warning at ignition and on every request it lets through.
+    pub fn with_test_token(mut self, token: Option<String>) -> Self {
+        self.test_token = token;
+        self
+    }
+
+    /// Delivers an authenticity token in an `X-CSRF-Token` response header as well, for
+    /// deployments behind a CDN that strips `Set-Cookie` from cached responses.
+    /// # Arguments
+    /// * `enabled` - Whether responses carry the token header.
+    ///
+    /// When enabled, the fairing adds an authenticity token minted from the session (including
+    /// one issued during the request) to every response, so clients that already hold a CSRF
+    /// cookie can pick up a valid token even when the page itself, or the `Set-Cookie` header of
+    /// a refresh, was lost in the CDN. The header is per-user: the CDN must be configured not to
+    /// cache it, or to vary on the cookie, otherwise one user's token would be served to others.
+    /// Tokens obtained this way still only verify with the matching CSRF cookie.
+    pub fn with_cdn_safe_token(mut self, enabled: bool) -> Self {
+        self.cdn_safe_token = enabled;
+        self
+    }
+
+    /// Announces rotations of the session token in response headers.
+    /// # Arguments
+    /// * `enabled` - Whether rotations are announced.
+    ///
+    /// When a handler rotates the session token, e.g. through `CsrfToken::verify_and_renew`, SPAs
+    /// holding authenticity tokens in memory need to learn which of their in-flight requests
+    /// used the replaced token. When enabled, the response to a rotating request carries the
+    /// identifier of the replaced session token in `X-CSRF-Previous-Token-Id` and the one of the
+    /// new session token in `X-CSRF-Token-Id`, as returned by `CsrfToken::token_id`. Identifiers
+    /// are digests of the session tokens and don't reveal them.
+    pub fn with_rotation_headers(mut self, enabled: bool) -> Self {
+        self.rotation_headers = enabled;
+        self
+    }
+
+    /// Makes authenticity tokens verify only once.
+    /// # Arguments
+    /// * `enabled` - Whether replayed tokens are rejected.
+    ///
+    /// When enabled, every token that passes verification is recorded in the nonce store (see
+    /// `with_nonce_store`) for the lifespan of the CSRF cookie, or a day for session cookies, and
+    /// submitting it again yields `CsrfError::Replayed`. Each token must therefore be verified
+    /// once per request, and clients need a fresh token for every submission. Tokens of
+    /// deterministic strategies such as `TokenStrategy::TimeWindow` verify once per time step.
+    pub fn with_single_use(mut self, enabled: bool) -> Self {
+        self.single_use = enabled;
+        self
+    }
+
+    /// Skips token verification for requests a browser marks as same-origin.
+    /// # Arguments
+    /// * `enabled` - Whether same-origin requests verify without a token.
+    ///
+    /// Browsers send the `Sec-Fetch-Site` header with every request made in a secure context, and
+    /// pages can't set it themselves. When enabled, a GET, HEAD or POST request carrying exactly
+    /// one `Sec-Fetch-Site: same-origin` header passes `CsrfToken::verify_request` and the global
+    /// `VerifyAllRequests` check without the bcrypt check, whatever token it carries, and
+    /// `CsrfToken::verify_request` reports `TokenSource::FetchMetadata`. Explicit checks of a
+    /// given token, i.e. `verify`, `verify_for`, `verify_step_up` and `verify_and_scope`, always
+    /// verify it, so scopes, step-up tokens and single use still apply. Any other value,
+    /// including `same-site`, which sibling subdomains also get, as well as other methods and
+    /// requests without the header, e.g. over plain HTTP or from older browsers, go through full
+    /// verification.
+    ///
+    /// Only enable this if every page of the origin is trusted: an XSS flaw or an upload served
+    /// from the same origin can then submit requests without a token. Non-browser clients can
+    /// send the header freely, so it proves nothing about them; they are no CSRF vector either.
+    pub fn with_same_origin_shortcut(mut self, enabled: bool) -> Self {
+        self.same_origin_shortcut = enabled;
+        self
+    }
+
+    /// Sets the store recording the tokens already used under `with_single_use`.
+    /// # Arguments
+    /// * `store` - The nonce store, an in-memory `MemoryNonceStore` by default.
+    ///
+    /// The in-memory store only protects a single instance of the application. Deployments with
+    /// several nodes behind a load balancer should plug in a store shared between them, e.g.
+    /// one backed by Redis.
+    pub fn with_nonce_store(mut self, store: Arc<dyn NonceStore>) -> Self {
+        self.nonce_store = NonceStoreHandle(store);
+        self
+    }
+
+    /// Limits how many CSRF cookies a client may be issued within a window.
+    /// # Arguments
+    /// * `limit` - The number of cookies issued to a client IP address per window.
+    /// * `window` - The length of a window.
+    ///
+    /// A client that keeps discarding its CSRF cookie makes the fairing generate a new session
+    /// token on every request. With a limit, a client IP address is issued no more than `limit`
+    /// cookies until its window elapses, bounding the RNG and hashing work it can trigger.
+    /// Throttled requests get no cookie, so their submissions fail with `CsrfError::NoSession`.
+    /// Requests whose client IP address is unknown are not limited. The counters live in the
+    /// memory of the current process.
+    pub fn with_issue_rate_limit(mut self, limit: u32, window: Duration) -> Self {
+        self.issue_rate_limit = Some(IssueRateLimit {
+            limit,
+            window,
+            issued: Arc::new(Mutex::default()),
+        });
+        self
+    }
+
+    /// Sets the sink recording the outcome of every verification, e.g. for compliance.
+    /// # Arguments
+    /// * `sink` - The audit sink, a no-op `NoopAuditSink` by default.
+    ///
+    /// Every verification passes an `AuditEntry` to the sink, whether it succeeded or failed,
+    /// carrying the time, the request path and client IP address, and the source of the token
+    /// when `CsrfToken::verify_request` found it. The sink is called on the request's thread, so
+    /// implementations writing to slow storage should hand the entry off, e.g. to a channel.
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = AuditSinkHandle(sink);
+        self
+    }
+
+    /// Controls whether HEAD requests are issued a CSRF cookie.
+    /// # Arguments
+    /// * `enabled` - Whether HEAD requests trigger issuance, `true` by default.
+    ///
+    /// Rocket answers HEAD requests with the response of the matching GET route, so by default the
+    /// fairing issues the cookie on HEAD exactly as on GET, and an SPA probing a page ends up with
+    /// the same token state as one fetching it. When disabled, HEAD requests never trigger
+    /// issuance, e.g. for health checks and link checkers that shouldn't create sessions; their
+    /// responses carry no `Set-Cookie` header for the CSRF cookie.
+    pub fn with_issue_on_head(mut self, enabled: bool) -> Self {
+        self.issue_on_head = enabled;
+        self
+    }
+
+    /// Sets paths on which GET requests must carry a valid authenticity token.
+    /// # Arguments
+    /// * `paths` - The request paths of state-changing GET endpoints.
+    ///
+    /// GET requests are exempt from verification by default, as they should not change state.
+    /// Legacy apps that do have state-changing GET endpoints can list their paths here, so that
+    /// `CsrfJson` and `CsrfRequestExt::csrf_token_required` treat them like POST requests.
+    /// Tokens in URLs leak easily, so clients should send them in the `X-CSRF-Token` header.
+    pub fn with_protected_get_paths(mut self, paths: Vec<String>) -> Self {
+        self.protected_get_paths = paths;
+        self
+    }
+
+    /// Sets the methods of the requests that must carry a valid authenticity token.
+    /// # Arguments
+    /// * `methods` - The protected methods, by default all but the safe ones of RFC 7231 (GET,
+    ///   HEAD, OPTIONS and TRACE).
+    ///
+    /// Requests with other methods skip the global `VerifyAllRequests` check, `CsrfJson` and
+    /// `with_reject_invalid`, and `CsrfRequestExt::csrf_token_required` is false for them, except
+    /// for GET requests to the paths set with `with_protected_get_paths`.
+    pub fn with_protected_methods(mut self, methods: Vec<Method>) -> Self {
+        self.protected_methods = methods;
+        self
+    }
+
+    /// Records how long verifications take, for tuning the bcrypt cost.
+    /// # Arguments
+    /// * `enabled` - Whether verification durations are recorded.
+    ///
+    /// When enabled, the duration of every verification is counted in a histogram with
+    /// power-of-two buckets of microseconds, whose percentiles are reported by
+    /// `CsrfMetrics::verify_latency_percentiles`. When disabled, verifications don't read the
+    /// clock at all.
+    pub fn with_latency_recording(mut self, enabled: bool) -> Self {
+        self.latency_recording = enabled;
+        self
+    }
+
+    /// Injects CSRF meta tags into HTML responses.
+    /// # Arguments
+    /// * `enabled` - Whether meta tag injection is enabled.
+    ///
+    /// When enabled, every HTML response to a GET request carries both the CSRF cookie and
+    /// `<meta name="csrf-token">` / `<meta name="csrf-param">` tags spliced before `</head>`,
+    /// ready for both form and AJAX use. Documents without a `</head>` are left untouched.
+    pub fn with_meta_tag_injection(mut self, enabled: bool) -> Self {
+        self.meta_tag_injection = enabled;
+        self
+    }
+
+    /// Rejects session tokens generated under a different token length.
+    /// # Arguments
+    /// * `strict` - Whether the session token length must match `cookie_len` exactly.
+    ///
+    /// By default a session token longer than `cookie_len` (e.g. issued before the length was
+    /// lowered) is still accepted. When strict, `CsrfToken::verify` returns
+    /// `CsrfError::LengthMismatch` for such sessions instead of verifying against them.
+    pub fn with_strict_length(mut self, strict: bool) -> Self {
+        self.strict_length = strict;
+        self
+    }
+
+    /// Sets the maximum number of verification attempts made for a single submitted token.
+    /// # Arguments
+    /// * `attempts` - The maximum number of session tokens to verify against.
+    ///
+    /// Every candidate (the current session token, the one replaced by the last rotation, and
+    /// those of each legacy configuration) costs one bcrypt verification. Capping the attempts
+    /// bounds the work an attacker can force per request, at the cost of rejecting tokens that
+    /// only match a candidate past the cap. Defaults to 4.
+    pub fn with_max_verify_attempts(mut self, attempts: usize) -> Self {
+        self.max_verify_attempts = attempts;
+        self
+    }
+
+    /// Sets the scheme used to mint and verify authenticity tokens.
+    /// # Arguments
+    /// * `strategy` - The token strategy, `TokenStrategy::Bcrypt` by default.
+    ///
+    /// This function modifies the CsrfConfig instance so that `CsrfToken::authenticity_token` and
+    /// `CsrfToken::verify` use the given strategy. See `TokenStrategy` for the trade-offs.
+    pub fn with_strategy(mut self, strategy: TokenStrategy) -> Self {
+        self.strategies = vec![strategy];
+        self
+    }
+
+    /// Sets the algorithm signing authenticity tokens.
+    /// # Arguments
+    /// * `algorithm` - The signing algorithm, `Algorithm::Bcrypt` by default.
+    ///
+    /// This is a shorthand for `with_strategy` with the strategy of the algorithm. bcrypt is
+    /// deliberately slow, which CSRF tokens don't need, so `Algorithm::Hmac` saves the cost of a
+    /// bcrypt hash on every minted and verified token while keeping tokens tamper-proof.
+    pub fn with_signing_algorithm(self, algorithm: Algorithm) -> Self {
+        self.with_strategy(algorithm.into())
+    }
+
+    /// Verifies authenticity tokens with a custom function.
+    /// # Arguments
+    /// * `verify` - The function receiving the session token and the submitted token.
+    ///
+    /// This is a shorthand for `with_strategy(TokenStrategy::Custom(verify))`, for schemes the
+    /// built-in strategies don't cover. Combine `TokenStrategy::Custom` with other strategies
+    /// through `with_strategies` to keep minting tokens.
+    pub fn with_verify_fn(self, verify: VerifyFn) -> Self {
+        self.with_strategy(TokenStrategy::Custom(verify))
+    }
+
+    /// Sets several schemes to verify authenticity tokens with, falling back from one to the next.
+    /// # Arguments
+    /// * `strategies` - The token strategies, the first of which mints new tokens.
+    ///
+    /// This function modifies the CsrfConfig instance so that `CsrfToken::verify` succeeds if any
+    /// of the strategies validates the submitted token, while `CsrfToken::authenticity_token` uses
+    /// the first one. It allows migrating e.g. from bcrypt to `TokenStrategy::TimeWindow` without
+    /// breaking tokens that are already in flight. An empty list resets to `TokenStrategy::Bcrypt`.
+    pub fn with_strategies(mut self, strategies: Vec<TokenStrategy>) -> Self {
+        self.strategies = if strategies.is_empty() {
+            vec![TokenStrategy::Bcrypt]
+        } else {
+            strategies
+        };
+        self
+    }
+
+    /// Advertises where clients can fetch a fresh token after a stale one is rejected.
+    /// # Arguments
+    /// * `hint` - The location of the token endpoint, e.g. `"/csrf"`.
+    ///
+    /// This function modifies the CsrfConfig instance so that a `CsrfError` responding with a
+    /// `Mismatch` or `LengthMismatch` rejection, which typically means the token expired or was
+    /// rotated, carries an `X-CSRF-Refresh` header with the hint. SPAs can use it to re-fetch a
+    /// token and retry automatically.
+    pub fn with_refresh_hint(mut self, hint: Option<String>) -> Self {
+        self.refresh_hint = hint;
+        self
+    }
+
+    /// Sets the status of responses rejecting requests that came without a CSRF session.
+    /// # Arguments
+    /// * `status` - The status responded with, e.g. `Status::PreconditionRequired`.
+    ///
+    /// A brand-new client posting before any GET has no CSRF cookie yet, so its request fails
+    /// with `CsrfError::NoSession` rather than `CsrfError::Mismatch`. Responding with a distinct
+    /// status, along with the `X-CSRF-Refresh` header when `CsrfConfig::with_refresh_hint` is set,
+    /// tells such clients to fetch a token first instead of reporting a forged request. Defaults
+    /// to 403 Forbidden.
+    pub fn with_no_session_status(mut self, status: Status) -> Self {
+        self.no_session_status = status;
+        self
+    }
+
+    /// Looks up the CSRF cookie regardless of the case of its name.
+    /// # Arguments
+    /// * `enabled` - Whether the cookie name is matched case-insensitively.
+    ///
+    /// Cookie names are case-sensitive, but some proxies and upstream services normalize their
+    /// case. When enabled, a cookie named e.g. `CSRF_TOKEN` is read if no `csrf_token` cookie is
+    /// present. Since private cookies are encrypted under their name, the cookie must have been
+    /// issued under the casing it arrives with, using the same secret key.
+    pub fn with_case_insensitive_cookie_lookup(mut self, enabled: bool) -> Self {
+        self.case_insensitive_cookie_lookup = enabled;
+        self
+    }
+
+    /// Sets a transform applied to freshly generated session token bytes before they are encoded.
+    /// # Arguments
+    /// * `transform` - The callback modifying the random bytes in place.
+    ///
+    /// This function modifies the CsrfConfig instance so that every session token issued by the
+    /// fairing or by `CsrfToken::rotate` goes through the transform, e.g. to prepend a version tag
+    /// or mix in additional entropy. Tokens shorter than `cookie_len` after the transform are
+    /// rejected as invalid, as are tokens of a different length under strict length checking.
+    pub fn with_token_transform(mut self, transform: TokenTransform) -> Self {
+        self.token_transform = Some(TokenTransformer(transform));
+        self
+    }
+
+    /// Skips issuing the CSRF cookie for requests matching a predicate.
+    /// # Arguments
+    /// * `predicate` - The callback deciding whether a request goes without a CSRF cookie.
+    ///
+    /// Requests that authenticate statelessly, e.g. with a bearer API token, are not exposed to
+    /// CSRF, so setting a cookie on them only adds a `Set-Cookie` header to every API response.
+    /// The fairing doesn't issue a cookie to requests for which the predicate returns `true`.
+    /// Verification is unaffected.
+    pub fn with_skip_issue_when(mut self, predicate: IssuePredicate) -> Self {
+        self.skip_issue_when = Some(SkipIssue(predicate));
+        self
+    }
+
+    /// Ignores whitespace embedded in tokens.
+    /// # Arguments
+    /// * `lenient` - Whether whitespace is stripped before tokens are decoded and verified.
+    ///
+    /// Tokens copied through certain UIs acquire stray spaces or newlines. When lenient, such
+    /// whitespace is removed from the session token and the submitted token, which never contain
+    /// any legitimately, instead of failing verification.
+    pub fn with_lenient_whitespace(mut self, lenient: bool) -> Self {
+        self.lenient_whitespace = lenient;
+        self
+    }
+
+    /// Only sets the CSRF cookie on successful responses.
+    /// # Arguments
+    /// * `enabled` - Whether the cookie is withheld from error responses.
+    ///
+    /// When enabled, a token issued during a request is still available to its guards, but the
+    /// cookie is only set once the response turns out to be 2xx or 3xx, so 404 and other error
+    /// pages don't hand out tokens. The `Fairing::with_on_issue` hook is likewise only invoked
+    /// for successful responses.
+    pub fn with_issue_on_success_only(mut self, enabled: bool) -> Self {
+        self.issue_on_success_only = enabled;
+        self
+    }
+
+    /// Accepts tokens sent in the `Authorization` header under a custom scheme.
+    /// # Arguments
+    /// * `scheme` - The scheme name, e.g. `"CSRF"` for `Authorization: CSRF <token>`.
+    ///
+    /// This function modifies the CsrfConfig instance so that `csrf_header_token` falls back to
+    /// the `Authorization` header when no `X-CSRF-Token` header is present. The scheme is matched
+    /// case-insensitively, and credentials of other schemes (e.g. a `Bearer` token sent in the same
+    /// header) are skipped.
+    pub fn with_authorization_scheme(
+        mut self,
+        scheme: Option<impl Into<Cow<'static, str>>>,
+    ) -> Self {
+        self.authorization_scheme = scheme.map(Into::into);
+        self
+    }
+
+    /// Mounts an endpoint returning a fresh authenticity token as JSON.
+    /// # Arguments
+    /// * `path` - The path of the endpoint, e.g. `"/csrf-token"`, or None to not mount it.
+    ///
+    /// This function modifies the CsrfConfig instance so that the fairing mounts a `GET` route at
+    /// `path` at ignition, standardizing token fetching for SPAs. The route responds with
+    /// `{"token": "...", "param": "authenticity_token", "header": "X-CSRF-Token"}` and forbids
+    /// caching of the response.
+    pub fn with_token_endpoint(mut self, path: Option<String>) -> Self {
+        self.token_endpoint = path;
+        self
+    }
+
+    /// Enables step-up tokens for sensitive operations.
+    /// # Arguments
+    /// * `window` - How long a step-up token remains valid after it is minted, e.g. 5 minutes.
+    ///
+    /// Step-up tokens are minted with `CsrfToken::step_up_token`, which applications should only
+    /// call once the user re-authenticated, and verified with `CsrfToken::verify_step_up`. They
+    /// are scoped apart from regular authenticity tokens, so neither satisfies the other's check.
+    pub fn with_step_up(mut self, window: Duration) -> Self {
+        self.step_up = Some(window);
+        self
+    }
+
+    /// Sets which CSRF cookie is used when the current and a legacy cookie are both present.
+    /// # Arguments
+    /// * `preference` - Whether the current or the legacy cookies come first.
+    ///
+    /// During a migration set up with `with_legacy_configs`, a client may carry cookies under both
+    /// the current and a legacy name. The preferred cookie holds the session token authenticity
+    /// tokens are minted from and is tried first during verification; the others are fallbacks.
+    /// Under `CookiePreference::Legacy`, no current cookie is issued while a legacy one is valid.
+    pub fn with_cookie_preference(mut self, preference: CookiePreference) -> Self {
+        self.cookie_preference = preference;
+        self
+    }
+
+    /// Removes the CSRF cookies that are not preferred when several are present.
+    /// # Arguments
+    /// * `clear` - Whether the stale cookies are removed.
+    ///
+    /// When enabled, the fairing removes every current or legacy CSRF cookie but the one picked
+    /// according to `with_cookie_preference`, so clients converge on a single cookie. Tokens minted
+    /// from a removed cookie still verify during the request that removes it.
+    pub fn with_clear_stale_cookies(mut self, clear: bool) -> Self {
+        self.clear_stale_cookies = clear;
+        self
+    }
+
+    /// Migrates CSRF cookies from Rocket's private cookies to cookies signed with a shared key.
+    /// # Arguments
+    /// * `key` - The 32-byte key shared by every instance of the application.
+    ///
+    /// Private cookies are encrypted with the secret key of each Rocket instance, which ties
+    /// sessions to the instance that issued them when the key isn't configured consistently.
+    /// Once migrated, CSRF cookies are issued with an HMAC-SHA256 signature under `key` instead,
+    /// which any instance holding the key can check. Private cookies issued before the migration
+    /// are still read, and re-issued in signed form with the same session token, so authenticity
+    /// tokens that were already handed out keep verifying throughout the transition.
+    ///
+    /// Signed cookies are `HttpOnly` but, unlike private cookies, not encrypted.
+    pub fn migrate_to_shared_key(mut self, key: [u8; 32]) -> Self {
+        self.shared_key = Some(SharedKey(key));
+        self
+    }
+
+    /// Signs CSRF cookie values with an additional server-side key.
+    /// # Arguments
+    /// * `key` - The 32-byte key, kept apart from Rocket's secret key.
+    ///
+    /// For defense in depth, the session token is signed with an HMAC-SHA256 under `key` before
+    /// the cookie is encrypted as a private cookie (or signed with the key set through
+    /// `migrate_to_shared_key`). Even if Rocket's secret key leaks, cookies can't be forged
+    /// without `key`. Cookies lacking a valid signature are rejected like undecryptable ones, so
+    /// setting or changing the key replaces the CSRF cookies of existing sessions.
+    pub fn with_extra_signing_key(mut self, key: [u8; 32]) -> Self {
+        self.extra_signing_key = Some(SharedKey(key));
+        self
+    }
+
+    /// Sets the media types of request bodies carrying form fields.
+    /// # Arguments
+    /// * `content_types` - Media types such as `"application/x-www-form-urlencoded"`.
+    ///
+    /// Only bodies of these types are searched for an `authenticity_token` form field, so that
+    /// e.g. a JSON body is never parsed as a form. The default lists the two form media types;
+    /// unusual clients sending URL-encoded fields under another type can be added here. Matching
+    /// ignores case and media type parameters. `multipart/form-data` bodies are parsed as
+    /// multipart, every other type as URL-encoded.
+    pub fn with_form_content_types(mut self, content_types: Vec<String>) -> Self {
+        self.form_content_types = content_types;
+        self
+    }
+
+    /// Only issues CSRF cookies to requests made over HTTPS.
+    /// # Arguments
+    /// * `enabled` - Whether the fairing refuses to issue cookies over plain HTTP.
+    ///
+    /// When enabled, the fairing logs an error instead of issuing a CSRF cookie to a plain-HTTP
+    /// request, so that tokens are never established in the clear and a misconfigured deployment
+    /// shows up right away. A request counts as HTTPS when Rocket serves TLS itself, or when a
+    /// TLS-terminating proxy marks it with `X-Forwarded-Proto: https` or `Forwarded: proto=https`;
+    /// the proxy must then overwrite these headers rather than pass on those sent by clients.
+    pub fn with_https_only(mut self, enabled: bool) -> Self {
+        self.https_only = enabled;
+        self
+    }
+
+    /// Sets the path of the form field carrying the authenticity token.
+    /// # Arguments
+    /// * `path` - The field path, which may use bracket notation, e.g. `"form[_csrf]"`.
+    ///
+    /// Some frameworks nest the token under the name of the form. Paths are compared key by key
+    /// like Rocket compares form field names, so `form[_csrf]` also matches `form._csrf`. A plain
+    /// field named after `CsrfConfig::param_name` keeps being accepted as well.
+    pub fn with_form_field(mut self, path: impl Into<Cow<'static, str>>) -> Self {
+        self.form_field = Some(path.into());
+        self
+    }
+
+    /// Sets the name of the parameter carrying the authenticity token.
+    /// # Arguments
+    /// * `name` - The parameter name, `authenticity_token` by default, e.g. `"_csrf"`.
+    ///
+    /// The name applies to form fields, query parameters and JSON bodies read by the fairing and
+    /// the guards, and to the `csrf-param` meta tag and the token endpoint, so scripts follow it.
+    /// Templates should render hidden inputs under `CsrfConfig::param_name`.
+    pub fn with_param_name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.param_name = name.into();
+        self
+    }
+
+    /// Returns the name of the parameter carrying the authenticity token.
+    ///
+    /// # Returns
+    /// (`&str`): The name set with `with_param_name`, `authenticity_token` by default.
+    pub fn param_name(&self) -> &str {
+        &self.param_name
+    }
+
+    /// Sets the name of the request header carrying the authenticity token.
+    /// # Arguments
+    /// * `name` - The header name, `X-CSRF-Token` by default, e.g. `X-XSRF-TOKEN` for Angular.
+    ///
+    /// Header names are matched case-insensitively. Tokens sent under any other header, including
+    /// `X-CSRF-Token`, are ignored. The name is also used for the header set under
+    /// `CsrfConfig::with_cdn_safe_token` and returned by the token endpoint.
+    pub fn with_header_name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.header_name = name.into();
+        self
+    }
+
+    /// Leaves all verification to handlers, disabling the global `VerifyAllRequests` check.
+    ///
+    /// By default, the fairing also runs the `VerifyAllRequests` check on requests it issues a
+    /// CSRF cookie to. Unless `with_reject_invalid` is enabled, that check doesn't reject anything
+    /// and only logs errors for requests without a valid `X-CSRF-Token` header. In manual-only mode, the fairing just issues
+    /// cookies, and requests are verified solely by the guards and `CsrfToken::verify` calls in
+    /// handlers.
+    pub fn manual_only(mut self) -> Self {
+        self.manual_only = true;
+        self
+    }
+
+    /// Makes the global `VerifyAllRequests` check reject mutating requests without a valid token.
+    /// # Arguments
+    /// * `enabled` - Whether requests failing verification get 403, for the methods set with
+    ///   `with_protected_methods` (and GET requests to the paths set with
+    ///   `with_protected_get_paths`).
+    ///
+    /// Fairings can't answer requests themselves, so the check records its verdict in the request
+    /// and the fairing mounts a catch-all route for those methods that outranks every other route.
+    /// The route fails requests the check rejected, sending them to the `403` catcher (or the
+    /// status set with `with_no_session_status` for `CsrfError::NoSession`), and forwards all
+    /// others to the application's routes, so handlers never run for forged requests. Tokens are
+    /// read from every source accepted by `CsrfToken::verify_request`. The wiring is just:
+    ///
+    /// ```rust
+    /// use rocket::{catch, catchers, Request};
+    /// use rocket_csrf_token::{CsrfConfig, CsrfRequestExt, Fairing};
+    ///
+    /// #[catch(403)]
+    /// fn forbidden(request: &Request) -> String {
+    ///     match request.csrf_error() {
+    ///         Some(err) => format!("Request rejected: {}", err),
+    ///         None => "Forbidden".to_string(),
+    ///     }
+    /// }
+    ///
+    /// let rocket = rocket::build()
+    ///     .attach(Fairing::new(CsrfConfig::default().with_reject_invalid(true)))
+    ///     .register("/", catchers![forbidden]);
+    /// ```
+    ///
+    /// The catcher is optional, Rocket's default one answers with a plain 403 page. Nothing is
+    /// rejected in manual-only mode, as the check doesn't run.
+    pub fn with_reject_invalid(mut self, enabled: bool) -> Self {
+        self.reject_invalid = enabled;
+        self
+    }
+
+    /// Sets the digest pre-hashing secrets that exceed bcrypt's 72-byte input limit.
+    /// # Arguments
+    /// * `algo` - The digest, `PrehashAlgo::Sha256` by default.
+    ///
+    /// Under `TokenStrategy::Bcrypt`, a secret longer than 72 bytes (e.g. from a large
+    /// `cookie_len`) is replaced by the base64 encoding of its digest before being hashed, so
+    /// that its whole length contributes to authenticity tokens. Choosing the digest lets tokens
+    /// interoperate with other stacks using a specific pre-hash scheme.
+    pub fn with_prehash(mut self, algo: PrehashAlgo) -> Self {
+        self.prehash = algo;
+        self
+    }
+
+    /// Sets the cost factor of the bcrypt hashes minted as authenticity tokens.
+    /// # Arguments
+    /// * `cost` - The base-2 logarithm of the number of bcrypt rounds, 8 by default.
+    ///
+    /// Every increment doubles the time it takes to mint and verify a token, so raise it as far as
+    /// the latency budget allows (see `with_latency_recording`). Tokens minted under any cost keep
+    /// verifying, as bcrypt hashes embed their cost. Costs outside of bcrypt's range of 4 to 31
+    /// are clamped into it, with a warning.
+    pub fn with_bcrypt_cost(mut self, cost: u32) -> Self {
+        let clamped = cost.clamp(*BCRYPT_COSTS.start(), *BCRYPT_COSTS.end());
+        if clamped != cost {
+            warn!(
+                "bcrypt cost {} is outside of {:?}, using {} instead.",
+                cost, BCRYPT_COSTS, clamped
+            );
+        }
+        self.bcrypt_cost = clamped;
+        self
+    }
+
+    /// Sets the path on which the fairing removes the CSRF cookie, e.g. the logout route.
+    /// # Arguments
+    /// * `path` - The request path, or `None` to keep the cookie on every path.
+    ///
+    /// Requests to this path get the CSRF cookie (and any legacy one) removed instead of a new one
+    /// issued, so the token ends with the user's session without any handler code. The removal is
+    /// enforced again on the response, overriding a cookie issued by the handler.
+    pub fn with_logout_path(mut self, path: Option<String>) -> Self {
+        self.logout_path = path;
+        self
+    }
+
+    /// Derives the session token from the application's session cookie instead of storing a
+    /// random one in a CSRF cookie.
+    /// # Arguments
+    /// * `cookie_name` - The name of the cookie holding the session id.
+    ///
+    /// The session token is expanded from the raw value of the session cookie with HKDF-SHA256,
+    /// so authenticity tokens stay valid for as long as the session id does and no CSRF cookie is
+    /// issued. Requests without the session cookie have no session token. Since the derivation
+    /// is keyed by the session id alone, it must be unguessable and must not be readable by
+    /// scripts, as a random CSRF cookie would have to be. `CsrfToken::rotate` has no effect.
+    pub fn derive_from_session(mut self, cookie_name: impl Into<String>) -> Self {
+        self.derive_from_session = Some(cookie_name.into());
+        self
+    }
+
+    /// Extracts the session token from a cookie set by an SSO system instead of storing a random
+    /// one in a CSRF cookie.
+    /// # Arguments
+    /// * `cookie_name` - The name of the SSO cookie, e.g. the one carrying its authorization.
+    /// * `extractor` - The callback returning the CSRF secret embedded in the cookie's value.
+    ///
+    /// The extractor receives the raw value of the SSO cookie and returns the secret bytes, or
+    /// None when the cookie doesn't carry one. Secrets shorter than `cookie_len` are rejected.
+    /// Authenticity tokens are then minted and verified against the secret as against a random
+    /// session token, and no CSRF cookie is issued. Requests without the SSO cookie have no
+    /// session token. `CsrfToken::rotate` has no effect, as the secret is the SSO system's.
+    pub fn with_secret_from_cookie(
+        mut self,
+        cookie_name: impl Into<String>,
+        extractor: SecretExtractor,
+    ) -> Self {
+        self.secret_from_cookie = Some(CookieSecret {
+            cookie_name: cookie_name.into(),
+            extract: extractor,
+        });
+        self
+    }
+
+    /// Prefixes authenticity tokens with a checksum of themselves.
+    /// # Arguments
+    /// * `enabled` - Whether tokens carry a checksum.
+    ///
+    /// When enabled, minted tokens start with the CRC32 of the rest of the token as 8 hex digits.
+    /// `CsrfToken::verify` checks it before any cryptographic work and rejects corrupted or forged
+    /// junk as `CsrfError::Malformed`, which keeps floods of garbage tokens cheap to turn away.
+    /// The checksum carries no secret, so it only filters input and doesn't replace verification.
+    pub fn with_token_checksum(mut self, enabled: bool) -> Self {
+        self.token_checksum = enabled;
+        self
+    }
+
+    /// Returns a JSON Schema describing the options of `CsrfConfig`, as reported by
+    /// `ConfigSummary::to_json`.
+    ///
+    /// Each property carries its JSON type, a description and the default of
+    /// `CsrfConfig::default`, so tooling and admin UIs can render configuration forms and
+    /// validate input. Durations are expressed in seconds, and options holding secrets or
+    /// callbacks are only reported as whether they are set.
+    #[cfg(feature = "json")]
+    pub fn schema() -> serde_json::Value {
+        use serde_json::{json, Map, Value};
+
+        let nullable = |kind: &str| json!([kind, "null"]);
+        let string_enum = |values: &[&str]| json!({ "type": "string", "enum": values });
+        // The defaults are read from the summary, so this table only holds types and descriptions.
+        let options: Vec<(&str, Value, &str)> = vec![
+            (
+                "lifespan",
+                json!({ "type": nullable("integer") }),
+                "The duration for which the CSRF token remains valid, in seconds.",
+            ),
+            (
+                "cookie_name",
+                json!({ "type": "string" }),
+                "The name of the CSRF cookie that stores the token.",
+            ),
+            (
+                "cookie_len",
+                json!({ "type": "integer" }),
+                "The length of the CSRF token in bytes.",
+            ),
+            (
+                "same_site",
+                string_enum(&["Strict", "Lax", "None"]),
+                "The SameSite policy of the CSRF cookie.",
+            ),
+            (
+                "same_site_none_fallback",
+                json!({ "type": "boolean" }),
+                "Whether the SameSite=None attribute is left out for browsers that mishandle it.",
+            ),
+            (
+                "secure",
+                json!({ "type": "boolean" }),
+                "Whether the CSRF cookie carries the Secure flag.",
+            ),
+            (
+                "http_only",
+                json!({ "type": "boolean" }),
+                "Whether the CSRF cookie carries the HttpOnly flag.",
+            ),
+            (
+                "cookie_domain",
+                json!({ "type": nullable("string") }),
+                "The Domain attribute of the CSRF cookie, null for a host-only cookie.",
+            ),
+            (
+                "cookie_path",
+                json!({ "type": "string" }),
+                "The Path attribute of the CSRF cookie.",
+            ),
+            (
+                "development",
+                json!({ "type": "boolean" }),
+                "Whether the development preset is active.",
+            ),
+            (
+                "legacy_configs",
+                json!({ "type": "integer" }),
+                "The number of legacy configurations whose tokens remain verifiable.",
+            ),
+            (
+                "rotation_grace",
+                json!({ "type": nullable("integer") }),
+                "How long the session token replaced by a rotation remains valid, in seconds.",
+            ),
+            (
+                "query_tokens",
+                json!({ "type": "boolean" }),
+                "Whether tokens are accepted from the query string.",
+            ),
+            (
+                "route_scoping",
+                json!({ "type": "boolean" }),
+                "Whether authenticity tokens are bound to the request path.",
+            ),
+            (
+                "meta_tag_injection",
+                json!({ "type": "boolean" }),
+                "Whether CSRF meta tags are injected into HTML responses.",
+            ),
+            (
+                "strict_length",
+                json!({ "type": "boolean" }),
+                "Whether session tokens must match cookie_len exactly.",
+            ),
+            (
+                "max_verify_attempts",
+                json!({ "type": "integer" }),
+                "The maximum number of verification attempts per submitted token.",
+            ),
+            (
+                "strategy",
+                json!({ "type": "string" }),
+                "The name of the token strategy minting new tokens.",
+            ),
+            (
+                "fallback_strategies",
+                json!({ "type": "array", "items": { "type": "string" } }),
+                "The names of the further strategies tokens are verified with.",
+            ),
+            (
+                "migration_set",
+                json!({ "type": "array", "items": { "type": "string" } }),
+                "The names of the strategies tokens are verified with against every session token.",
+            ),
+            (
+                "refresh_hint",
+                json!({ "type": nullable("string") }),
+                "Where clients can fetch a fresh token after a stale one is rejected.",
+            ),
+            (
+                "issue_rate_limit",
+                json!({
+                    "type": nullable("object"),
+                    "properties": {
+                        "limit": { "type": "integer" },
+                        "window": { "type": "integer" },
+                    },
+                }),
+                "How many CSRF cookies a client IP address may be issued per window of seconds.",
+            ),
+            (
+                "no_session_status",
+                json!({ "type": "integer" }),
+                "The status of responses rejecting requests that came without a CSRF session.",
+            ),
+            (
+                "case_insensitive_cookie_lookup",
+                json!({ "type": "boolean" }),
+                "Whether the CSRF cookie is looked up regardless of the case of its name.",
+            ),
+            (
+                "lenient_whitespace",
+                json!({ "type": "boolean" }),
+                "Whether whitespace embedded in tokens is ignored.",
+            ),
+            (
+                "issue_on_success_only",
+                json!({ "type": "boolean" }),
+                "Whether the CSRF cookie is only set on successful responses.",
+            ),
+            (
+                "authorization_scheme",
+                json!({ "type": nullable("string") }),
+                "The Authorization scheme under which tokens are accepted.",
+            ),
+            (
+                "token_endpoint",
+                json!({ "type": nullable("string") }),
+                "The path of the endpoint returning a fresh token.",
+            ),
+            (
+                "step_up",
+                json!({ "type": nullable("integer") }),
+                "How long step-up tokens remain valid, in seconds.",
+            ),
+            (
+                "cookie_preference",
+                string_enum(&["Current", "Legacy"]),
+                "Whether the current or the legacy CSRF cookies are preferred.",
+            ),
+            (
+                "clear_stale_cookies",
+                json!({ "type": "boolean" }),
+                "Whether the CSRF cookies that are not preferred are removed.",
+            ),
+            (
+                "shared_key",
+                json!({ "type": "boolean" }),
+                "Whether CSRF cookies are signed with a shared key.",
+            ),
+            (
+                "extra_signing_key",
+                json!({ "type": "boolean" }),
+                "Whether CSRF cookie values are signed with an additional server-side key.",
+            ),
+            (
+                "form_content_types",
+                json!({ "type": "array", "items": { "type": "string" } }),
+                "The media types of request bodies carrying form fields.",
+            ),
+            (
+                "https_only",
+                json!({ "type": "boolean" }),
+                "Whether CSRF cookies are only issued over HTTPS.",
+            ),
+            (
+                "param_name",
+                json!({ "type": "string" }),
+                "The name of the parameter carrying the authenticity token.",
+            ),
+            (
+                "form_field",
+                json!({ "type": "string" }),
+                "The path of the form field carrying the authenticity token.",
+            ),
+            (
+                "header_name",
+                json!({ "type": "string" }),
+                "The name of the request header carrying the authenticity token.",
+            ),
+            (
+                "manual_only",
+                json!({ "type": "boolean" }),
+                "Whether verification is left to handlers.",
+            ),
+            (
+                "reject_invalid",
+                json!({ "type": "boolean" }),
+                "Whether mutating requests without a valid token are rejected by the fairing.",
+            ),
+            (
+                "prehash",
+                string_enum(&["Sha256", "Sha512", "None"]),
+                "The digest pre-hashing secrets too long for bcrypt.",
+            ),
+            (
+                "bcrypt_cost",
+                json!({ "type": "integer", "minimum": 4, "maximum": 31 }),
+                "The cost factor of the bcrypt hashes minted as authenticity tokens.",
+            ),
+            (
+                "logout_path",
+                json!({ "type": nullable("string") }),
+                "The path on which the CSRF cookie is removed.",
+            ),
+            (
+                "derive_from_session",
+                json!({ "type": nullable("string") }),
+                "The session cookie the session token is derived from.",
+            ),
+            (
+                "secret_from_cookie",
+                json!({ "type": nullable("string") }),
+                "The SSO cookie the session token is extracted from.",
+            ),
+            (
+                "token_checksum",
+                json!({ "type": "boolean" }),
+                "Whether authenticity tokens are prefixed with a checksum.",
+            ),
+            (
+                "method_binding",
+                json!({ "type": "boolean" }),
+                "Whether authenticity tokens are bound to the HTTP method.",
+            ),
+            (
+                "test_token",
+                json!({ "type": "boolean" }),
+                "Whether a pre-shared test token is accepted.",
+            ),
+            (
+                "cdn_safe_token",
+                json!({ "type": "boolean" }),
+                "Whether responses carry an authenticity token in a header.",
+            ),
+            (
+                "protected_get_paths",
+                json!({ "type": "array", "items": { "type": "string" } }),
+                "The paths on which GET requests must carry a valid authenticity token.",
+            ),
+            (
+                "protected_methods",
+                json!({
+                    "type": "array",
+                    "items": string_enum(&[
+                        "GET", "PUT", "POST", "DELETE", "OPTIONS", "HEAD", "TRACE", "CONNECT",
+                        "PATCH",
+                    ]),
+                }),
+                "The methods of the requests that must carry a valid authenticity token.",
+            ),
+            (
+                "latency_recording",
+                json!({ "type": "boolean" }),
+                "Whether the durations of verifications are recorded in the metrics.",
+            ),
+            (
+                "rotation_headers",
+                json!({ "type": "boolean" }),
+                "Whether responses announce the replaced and new session tokens after a rotation.",
+            ),
+            (
+                "issue_on_head",
+                json!({ "type": "boolean" }),
+                "Whether HEAD requests are issued a CSRF cookie like GET requests.",
+            ),
+            (
+                "single_use",
+                json!({ "type": "boolean" }),
+                "Whether authenticity tokens verify only once.",
+            ),
+            (
+                "same_origin_shortcut",
+                json!({ "type": "boolean" }),
+                "Whether verification is skipped for requests a browser marks as same-origin.",
+            ),
+        ];
+
+        let defaults = Self::default().summary().to_json();
+        let properties: Map<String, Value> = options
+            .into_iter()
+            .map(|(name, mut property, description)| {
+                property["description"] = json!(description);
+                property["default"] = defaults[name].clone();
+                (name.to_string(), property)
+            })
+            .collect();
+
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "CsrfConfig",
+            "type": "object",
+            "properties": properties,
+            "additionalProperties": false,
+        })
+    }
+
+    /// Checks that a Rocket instance provides what this configuration relies on at runtime.
+    /// # Arguments
+    /// * `rocket` - The Rocket instance the fairing is attached to, before it is ignited.
+    ///
+    /// Private CSRF cookies are encrypted with Rocket's secret key, which Rocket generates anew
+    /// at every launch in debug builds when none is configured, so cookies don't survive a
+    /// restart and aren't shared between instances. Stateless strategies such as
+    /// `TokenStrategy::TimeWindow` rely on their server secret instead, which must not be empty.
+    /// Calling this function while building the application, e.g. before `launch`, catches such
+    /// deployment mistakes before any request is served.
+    ///
+    /// # Returns
+    /// (`Result<(), Vec<String>>`): Ok if every prerequisite is met, or a description of each
+    /// missing one.
+    pub fn check_runtime(&self, rocket: &Rocket<rocket::Build>) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        let private_cookies = self.shared_key.is_none() && !self.has_external_session_token();
+        let key_provided = rocket
+            .figment()
+            .extract_inner::<SecretKey>("secret_key")
+            .is_ok_and(|key| key.is_provided());
+        if private_cookies && !key_provided {
+            errors.push(format!(
+                "CSRF cookie `{}` is a private cookie, but Rocket has no `secret_key` configured.",
+                self.full_cookie_name()
+            ));
+        }
+
+        for strategy in &self.strategies {
+            let secret = match strategy {
+                TokenStrategy::TimeWindow { secret, .. }
+                | TokenStrategy::Split { secret }
+                | TokenStrategy::SignedDoubleSubmit { secret } => secret,
+                #[cfg(feature = "json")]
+                TokenStrategy::Claims { secret } => secret,
+                _ => continue,
+            };
+            if secret.is_empty() {
+                errors.push(format!(
+                    "The `{}` token strategy has an empty server secret.",
+                    strategy.name()
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Returns a structured summary of this configuration.
+    pub fn summary(&self) -> ConfigSummary {
+        ConfigSummary {
+            lifespan: self.lifespan,
+            cookie_name: self.full_cookie_name().into_owned(),
+            cookie_len: self.token_len(),
+            same_site: self.same_site,
+            same_site_none_fallback: self.same_site_none_fallback,
+            secure: self.secure,
+            http_only: self.http_only,
+            cookie_domain: self.cookie_domain.as_deref().map(String::from),
+            cookie_path: self.cookie_path.to_string(),
+            development: self.development,
+            legacy_configs: self.legacy_configs.len(),
+            rotation_grace: self.rotation_grace,
+            query_tokens: self.query_tokens,
+            route_scoping: self.route_scoping,
+            meta_tag_injection: self.meta_tag_injection,
+            strict_length: self.strict_length,
+            max_verify_attempts: self.max_verify_attempts,
+            strategy: self.strategy().name(),
+            fallback_strategies: self.strategies[1..]
+                .iter()
+                .map(TokenStrategy::name)
+                .collect(),
+            migration_set: self.migration_set.iter().map(TokenStrategy::name).collect(),
+            refresh_hint: self.refresh_hint.clone(),
+            no_session_status: self.no_session_status.code,
+            issue_rate_limit: self
+                .issue_rate_limit
+                .as_ref()
+                .map(|rate_limit| (rate_limit.limit, rate_limit.window)),
+            case_insensitive_cookie_lookup: self.case_insensitive_cookie_lookup,
+            lenient_whitespace: self.lenient_whitespace,
+            issue_on_success_only: self.issue_on_success_only,
+            authorization_scheme: self.authorization_scheme.as_deref().map(String::from),
+            token_endpoint: self.token_endpoint.clone(),
+            step_up: self.step_up,
+            cookie_preference: self.cookie_preference,
+            clear_stale_cookies: self.clear_stale_cookies,
+            shared_key: self.shared_key.is_some(),
+            extra_signing_key: self.extra_signing_key.is_some(),
+            form_content_types: self.form_content_types.clone(),
+            https_only: self.https_only,
+            param_name: self.param_name.to_string(),
+            form_field: self.form_field().to_string(),
+            header_name: self.header_name.to_string(),
+            manual_only: self.manual_only,
+            reject_invalid: self.reject_invalid,
+            prehash: self.prehash,
+            bcrypt_cost: self.bcrypt_cost,
+            logout_path: self.logout_path.clone(),
+            derive_from_session: self.derive_from_session.clone(),
+            secret_from_cookie: self
+                .secret_from_cookie
+                .as_ref()
+                .map(|source| source.cookie_name.clone()),
+            token_checksum: self.token_checksum,
+            method_binding: self.method_binding,
+            test_token: self.active_test_token().is_some(),
+            cdn_safe_token: self.cdn_safe_token,
+            protected_get_paths: self.protected_get_paths.clone(),
+            protected_methods: self.protected_methods.clone(),
+            latency_recording: self.latency_recording,
+            rotation_headers: self.rotation_headers,
+            issue_on_head: self.issue_on_head,
+            single_use: self.single_use,
+            same_origin_shortcut: self.same_origin_shortcut,
+        }
+    }
+
+    /// Computes the `Set-Cookie` header value of the CSRF cookie carrying the given session token.
+    /// # Arguments
+    /// * `token_bytes` - The raw session token, e.g. `cookie_len` random bytes.
+    ///
+    /// This function is meant for deployments that set cookies outside of Rocket's cookie jar,
+    /// e.g. in an edge function. The returned value carries the configured name, lifespan, domain,
+    /// path, `SameSite` policy, `Secure` and `HttpOnly` flags, like the cookie issued by the
+    /// fairing. The token is signed with the key set through `migrate_to_shared_key`, so the
+    /// fairing accepts the cookie, and with the key set through `with_extra_signing_key`
+    /// beforehand, if any.
+    ///
+    /// Without a shared key, the fairing only accepts private cookies, which are encrypted with
+    /// Rocket's secret key, unknown to the configuration, so no header is built.
+    ///
+    /// # Returns
+    /// (`Option<String>`): The value of the `Set-Cookie` header, without the header name, or
+    /// None if `migrate_to_shared_key` isn't set.
+    pub fn build_cookie_header(&self, token_bytes: &[u8]) -> Option<String> {
+        let shared_key = self.shared_key.as_ref()?;
+        let mut cookie = self.session_cookie(general_purpose::STANDARD.encode(token_bytes));
+        if let Some(key) = &self.extra_signing_key {
+            cookie.set_value(key.sign(cookie.name(), cookie.value()));
+        }
+        cookie.set_value(shared_key.sign(cookie.name(), cookie.value()));
+
+        Some(cookie.to_string())
+    }
+
+    /// Adds a CSRF cookie to the jar, signed with the shared key if any, as a private cookie otherwise.
+    ///
+    /// The value is signed with the extra signing key first, if any.
+    fn add_cookie(&self, cookies: &CookieJar<'_>, mut cookie: Cookie<'static>) {
+        if let Some(key) = &self.extra_signing_key {
+            cookie.set_value(key.sign(cookie.name(), cookie.value()));
+        }
+        match &self.shared_key {
+            Some(key) => {
+                cookie.set_value(key.sign(cookie.name(), cookie.value()));
+                cookies.add(cookie);
+            }
+            None => cookies.add_private(cookie),
+        }
+    }
+
+    /// Reads the value of the CSRF cookie named `name`, accepting cookies signed with the shared
+    /// key as well as private cookies.
+    fn read_cookie(&self, cookies: &CookieJar<'_>, name: &str) -> Option<String> {
+        let signed = self
+            .shared_key
+            .as_ref()
+            .and_then(|key| key.verify(name, cookies.get(name)?.value()));
+        let value = signed.or_else(|| Some(cookies.get_private(name)?.value().to_string()))?;
+
+        self.verify_extra_signature(name, value)
+    }
+
+    /// Checks the signature of a decrypted cookie value under the extra signing key, if any,
+    /// returning the value without it.
+    fn verify_extra_signature(&self, name: &str, value: String) -> Option<String> {
+        match &self.extra_signing_key {
+            Some(key) => key.verify(name, &value),
+            None => Some(value),
+        }
+    }
+
+    /// Returns the length in bytes of the session tokens issued under the primary strategy.
+    fn token_len(&self) -> usize {
+        let name = self.strategy().name();
+        self.strategy_token_lens
+            .iter()
+            .find(|(strategy, _)| *strategy == name)
+            .map(|(_, len)| *len)
+            .or(self.cookie_len)
+            .unwrap_or_else(|| self.strategy().default_token_len())
+    }
+
+    /// Counts an issuance to the client of `request`, returning `false` if it is throttled under
+    /// `with_issue_rate_limit`.
+    fn allow_issue(&self, request: &Request<'_>) -> bool {
+        match (&self.issue_rate_limit, request.client_ip()) {
+            (Some(rate_limit), Some(ip)) if !rate_limit.allow(ip) => {
+                warn!("Throttling CSRF cookie issuance to {}.", ip);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Checks whether the session token comes from a cookie of the application or of an SSO
+    /// system rather than from a CSRF cookie.
+    fn has_external_session_token(&self) -> bool {
+        self.derive_from_session.is_some() || self.secret_from_cookie.is_some()
+    }
+
+    /// Derives the session token from the session cookie set with `derive_from_session`.
+    fn derive_session_token(&self, cookies: &CookieJar<'_>) -> Option<Vec<u8>> {
+        let session_id = cookies.get(self.derive_from_session.as_deref()?)?.value();
+        let info = format!("rocket_csrf_token:{}", self.full_cookie_name());
+
+        let mut derived = vec![0; self.token_len()];
+        match Hkdf::<Sha256>::new(None, session_id.as_bytes()).expand(info.as_bytes(), &mut derived)
+        {
+            Ok(()) => Some(derived),
+            Err(_) => {
+                error!(
+                    "Cannot derive a {}-byte CSRF token from the session.",
+                    self.token_len()
+                );
+                None
+            }
+        }
+    }
+
+    /// Strips the whitespace from `token` when lenient whitespace handling is enabled.
+    fn clean<'a>(&self, token: &'a str) -> Cow<'a, str> {
+        if self.lenient_whitespace && token.contains(|c: char| c.is_ascii_whitespace()) {
+            Cow::Owned(token.chars().filter(|c| !c.is_ascii_whitespace()).collect())
+        } else {
+            Cow::Borrowed(token)
+        }
+    }
+
+    /// Checks whether bodies of the given content type carry form fields.
+    fn is_form_content_type(&self, content_type: &ContentType) -> bool {
+        self.form_content_types.iter().any(|form_type| {
+            let form_type = form_type.split(';').next().unwrap_or_default().trim();
+            form_type
+                .split_once('/')
+                .is_some_and(|(top, sub)| content_type.top() == top && content_type.sub() == sub)
+        })
+    }
+
+    /// Checks whether a form field, named as submitted, carries the authenticity token.
+    fn is_token_field(&self, name: &str) -> bool {
+        let name = RawStr::new(name).url_decode_lossy();
+        [self.form_field(), self.param_name()]
+            .iter()
+            .any(|field| Name::new(&*name) == Name::new(field))
+    }
+
+    /// Returns the path of the form field carrying the authenticity token.
+    fn form_field(&self) -> &str {
+        self.form_field.as_deref().unwrap_or(&self.param_name)
+    }
+
+    /// Returns this configuration and its legacy configurations in the order of preference.
+    fn preferred_configs(&self) -> Vec<&CsrfConfig> {
+        let current = std::iter::once(self);
+        match self.cookie_preference {
+            CookiePreference::Current => current.chain(&self.legacy_configs).collect(),
+            CookiePreference::Legacy => self.legacy_configs.iter().chain(current).collect(),
+        }
+    }
+
+    /// Returns the strategy that mints authenticity tokens.
+    fn strategy(&self) -> &TokenStrategy {
+        &self.strategies[0]
+    }
+
+    /// Returns the pre-shared test token, if set and honored by this build.
+    fn active_test_token(&self) -> Option<&str> {
+        self.test_token
+            .as_deref()
+            .filter(|_| cfg!(debug_assertions) || self.development)
+    }
+
+    /// Returns whether `request` must carry a valid authenticity token: requests with a protected
+    /// method, and GET requests to a protected path.
+    fn requires_token(&self, request: &Request<'_>) -> bool {
+        let path = request.uri().path();
+        self.protected_methods.contains(&request.method())
+            || (request.method() == Method::Get
+                && self
+                    .protected_get_paths
+                    .iter()
+                    .any(|protected| path == protected.as_str()))
+    }
+
+    /// Returns whether the request targets the configured logout path.
+    fn is_logout_path(&self, request: &Request<'_>) -> bool {
+        self.logout_path
+            .as_deref()
+            .is_some_and(|path| request.uri().path() == path)
+    }
+
+    /// Mints an authenticity token from the encoded session token with the minting strategy,
+    /// prefixing it with its checksum when enabled.
+    fn mint(&self, value: &str, scope: Option<&str>) -> Result<String, BcryptError> {
+        let token = self
+            .strategy()
+            .mint(value, scope, self.prehash, self.bcrypt_cost)?;
+        if self.token_checksum {
+            Ok(format!("{:08x}{}", crc32(token.as_bytes()), token))
+        } else {
+            Ok(token)
+        }
+    }
+
+    /// Returns the name of the CSRF cookie, including the environment suffix if any.
+    fn full_cookie_name(&self) -> Cow<'static, str> {
+        match &self.env_suffix {
+            Some(suffix) => format!("{}_{}", self.cookie_name, suffix).into(),
+            None => self.cookie_name.clone(),
+        }
+    }
+
+    /// Returns the name of the cookie holding the session token replaced by the last rotation.
+    fn previous_cookie_name(&self) -> String {
+        format!("{}_previous", self.full_cookie_name())
+    }
+
+    /// Builds the CSRF cookie carrying the given encoded session token, applying the configured
+    /// attributes.
+    fn session_cookie(&self, encoded: String) -> Cookie<'static> {
+        // Expiration of None means a session cookie
+        let expires = self
+            .lifespan
+            .map(|duration| OffsetDateTime::now_utc() + duration);
+
+        let cookie_builder = Cookie::build((self.full_cookie_name(), encoded))
+            .path(self.cookie_path.clone())
+            .same_site(self.same_site)
+            .secure(self.secure)
+            .http_only(self.http_only);
+
+        let cookie_builder = match &self.cookie_domain {
+            Some(domain) => cookie_builder.domain(domain.clone()),
+            None => cookie_builder,
+        };
+
+        let cookie_builder = match expires {
+            Some(expiration) => cookie_builder.expires(expiration),
+            None => cookie_builder.expires(None), // Expiration of None means duration of session
+                                                  // Reference: https://api.rocket.rs/master/rocket/http/struct.Cookie.html#method.set_expires
+        };
+
+        cookie_builder.build()
+    }
+
+    /// Builds a cookie named `name` with the path and domain of the CSRF cookie, which browsers
+    /// require to match for the removal of the cookie.
+    fn removal_cookie(&self, name: impl Into<Cow<'static, str>>) -> Cookie<'static> {
+        let cookie_builder = Cookie::build(name.into()).path(self.cookie_path.clone());
+        match &self.cookie_domain {
+            Some(domain) => cookie_builder.domain(domain.clone()).build(),
+            None => cookie_builder.build(),
+        }
+    }
+}
+
+/// Which CSRF cookie is used when a request carries both the current and a legacy cookie.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CookiePreference {
+    /// The cookie of the current configuration is used, falling back to the legacy ones.
+    #[default]
+    Current,
+    /// The cookies of the legacy configurations are used in order, falling back to the current one.
+    Legacy,
+}
+
+/// The digest applied to secrets exceeding bcrypt's 72-byte input limit before hashing them,
+/// see `CsrfConfig::with_prehash`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+#[non_exhaustive]
+pub enum PrehashAlgo {
+    /// The secret is replaced by the base64-encoded SHA-256 digest of it.
+    #[default]
+    Sha256,
+    /// The secret is replaced by the base64-encoded SHA-512 digest of it. The encoded digest
+    /// exceeds 72 bytes as well, so bcrypt only uses its first 432 bits.
+    Sha512,
+    /// The secret is hashed as it is, so bcrypt ignores everything past its first 72 bytes.
+    None,
+}
+
+/// The scheme used to mint and verify authenticity tokens.
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub enum TokenStrategy {
+    /// Authenticity tokens are bcrypt hashes of the session token stored in the CSRF cookie.
+    #[default]
+    Bcrypt,
+    /// Authenticity tokens are an HMAC-SHA256 over the current time step, keyed with a server
+    /// secret, in the manner of TOTP. They verify during the step they were minted in and the
+    /// adjacent ones, without consulting the session, which suits clients that can't hold cookies.
+    ///
+    /// Such tokens are not bound to a session: any client that obtains one can replay it until
+    /// it expires. Keep `step` short.
+    TimeWindow {
+        /// The length of a time step.
+        step: Duration,
+        /// The server secret keying the HMAC.
+        secret: Vec<u8>,
+    },
+    /// Authenticity tokens are split into a selector and a validator, `<selector>.<validator>` in
+    /// unpadded URL-safe base64, both derived from the session token with an HMAC-SHA256 keyed by
+    /// a server secret. The selector identifies the session's verifier and the validator is
+    /// checked against it, so a leaked CSRF cookie alone can't be used to forge tokens.
+    Split {
+        /// The server secret keying the HMAC.
+        secret: Vec<u8>,
+    },
+    /// Authenticity tokens follow the signed double-submit cookie pattern: `<message>.<mac>` in
+    /// unpadded URL-safe base64, where the message is random and the MAC is an HMAC-SHA256 over
+    /// the session token and the message, keyed by a server secret. Verification recomputes the
+    /// MAC over the submitted message, so it is stateless, and a token injected by a sibling
+    /// subdomain doesn't verify without the secret. The token may be handed to clients in a
+    /// readable cookie, which they echo in a header.
+    SignedDoubleSubmit {
+        /// The server secret keying the HMAC.
+        secret: Vec<u8>,
+    },
+    /// Authenticity tokens carry a JSON claims object with the `iat` and `exp` unix timestamps
+    /// and the optional `scope`, in the manner of a JWT: `<claims>.<mac>` in unpadded URL-safe
+    /// base64, where the MAC is an HMAC-SHA256 over the session token and the encoded claims,
+    /// keyed by a server secret. Tokens minted here expire after an hour; tokens minted by
+    /// other services may pick their own expiry. An expired token yields `CsrfError::Expired`.
+    #[cfg(feature = "json")]
+    Claims {
+        /// The server secret keying the HMAC.
+        secret: Vec<u8>,
+    },
+    /// Authenticity tokens are minted and signed by an external auth service, and only verified
+    /// here against its Ed25519 public key. A token has the form `<payload>.<signature>`, both in
+    /// unpadded URL-safe base64, where the signature covers the payload bytes, followed by a zero
+    /// byte and the scope when one applies.
+    ///
+    /// Tokens can't be minted under this strategy, so `CsrfToken::authenticity_token` fails.
+    #[cfg(feature = "ed25519")]
+    Ed25519 {
+        /// The public key of the auth service.
+        public_key: [u8; 32],
+    },
+    /// Authenticity tokens are verified by a user-supplied function, e.g. one consulting an
+    /// external service. It receives the session token (derived with the scope, when one
+    /// applies) and the submitted token.
+    ///
+    /// Tokens can't be minted under this strategy, so `CsrfToken::authenticity_token` fails.
+    Custom(VerifyFn),
+}
+
+/// Function deciding whether a submitted token is valid, see `TokenStrategy::Custom`.
+pub type VerifyFn = Arc<dyn Fn(&str, &str) -> bool + Send + Sync>;
+
+impl fmt::Debug for TokenStrategy {
+    /// Formats the strategy without revealing the server secret.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenStrategy::Bcrypt => f.write_str("Bcrypt"),
+            TokenStrategy::TimeWindow { step, .. } => f
+                .debug_struct("TimeWindow")
+                .field("step", step)
+                .finish_non_exhaustive(),
+            TokenStrategy::Split { .. } => f.debug_struct("Split").finish_non_exhaustive(),
+            TokenStrategy::SignedDoubleSubmit { .. } => {
+                f.debug_struct("SignedDoubleSubmit").finish_non_exhaustive()
+            }
+            #[cfg(feature = "json")]
+            TokenStrategy::Claims { .. } => f.debug_struct("Claims").finish_non_exhaustive(),
+            #[cfg(feature = "ed25519")]
+            TokenStrategy::Ed25519 { public_key } => f
+                .debug_struct("Ed25519")
+                .field("public_key", public_key)
+                .finish(),
+            TokenStrategy::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+/// The algorithm signing authenticity tokens, see `CsrfConfig::with_signing_algorithm`.
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub enum Algorithm {
+    /// Authenticity tokens are bcrypt hashes of the session token, as with
+    /// `TokenStrategy::Bcrypt`.
+    #[default]
+    Bcrypt,
+    /// Authenticity tokens are an HMAC-SHA256 over the session token and a random message, keyed
+    /// with a server secret, as with `TokenStrategy::SignedDoubleSubmit`. Tokens signed with
+    /// another key don't verify.
+    Hmac {
+        /// The server secret keying the HMAC.
+        key: Vec<u8>,
+    },
+}
+
+impl fmt::Debug for Algorithm {
+    /// Formats the algorithm without revealing the key.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Algorithm::Bcrypt => f.write_str("Bcrypt"),
+            Algorithm::Hmac { .. } => f.debug_struct("Hmac").finish_non_exhaustive(),
+        }
+    }
+}
+
+impl From<Algorithm> for TokenStrategy {
+    fn from(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Bcrypt => TokenStrategy::Bcrypt,
+            Algorithm::Hmac { key } => TokenStrategy::SignedDoubleSubmit { secret: key },
+        }
+    }
+}
+
+impl TokenStrategy {
+    /// Returns the name reported in `ConfigSummary`.
+    fn name(&self) -> &'static str {
+        match self {
+            TokenStrategy::Bcrypt => "bcrypt",
+            TokenStrategy::TimeWindow { .. } => "time-window",
+            TokenStrategy::Split { .. } => "split",
+            TokenStrategy::SignedDoubleSubmit { .. } => "signed-double-submit",
+            #[cfg(feature = "json")]
+            TokenStrategy::Claims { .. } => "claims",
+            #[cfg(feature = "ed25519")]
+            TokenStrategy::Ed25519 { .. } => "ed25519",
+            TokenStrategy::Custom(_) => "custom",
+        }
+    }
+
+    /// Returns the length in bytes of session tokens issued under this strategy by default.
+    fn default_token_len(&self) -> usize {
+        match self {
+            // Time-window tokens only require the CSRF cookie to be present.
+            TokenStrategy::TimeWindow { .. } => 16,
+            _ => 32,
+        }
+    }
+
+    /// Checks whether authenticity tokens verify against the session token, so that none can
+    /// verify without a CSRF session.
+    fn is_session_bound(&self) -> bool {
+        match self {
+            TokenStrategy::Bcrypt
+            | TokenStrategy::Split { .. }
+            | TokenStrategy::SignedDoubleSubmit { .. } => true,
+            #[cfg(feature = "json")]
+            TokenStrategy::Claims { .. } => true,
+            // Custom verifiers may well ignore the session token.
+            _ => false,
+        }
+    }
+
+    /// Mints an authenticity token from the encoded session token under an optional scope.
+    fn mint(
+        &self,
+        value: &str,
+        scope: Option<&str>,
+        prehash: PrehashAlgo,
+        cost: u32,
+    ) -> Result<String, BcryptError> {
+        match self {
+            TokenStrategy::Bcrypt => hash(bcrypt_secret(value, scope, prehash).as_ref(), cost),
+            TokenStrategy::TimeWindow { step, secret } => {
+                let mac = time_window_mac(secret, time_step(*step), scope);
+                Ok(general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+            }
+            TokenStrategy::Split { secret } => {
+                let selector = split_selector(secret, value);
+                let validator = split_validator(secret, &selector, value, scope);
+                Ok(format!(
+                    "{}.{}",
+                    selector,
+                    general_purpose::URL_SAFE_NO_PAD.encode(validator.finalize().into_bytes())
+                ))
+            }
+            TokenStrategy::SignedDoubleSubmit { secret } => {
+                let nonce: [u8; 16] = rand::thread_rng().gen();
+                let message = general_purpose::URL_SAFE_NO_PAD.encode(nonce);
+                let mac = double_submit_mac(secret, &message, value, scope);
+                Ok(format!(
+                    "{}.{}",
+                    message,
+                    general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+                ))
+            }
+            #[cfg(feature = "json")]
+            TokenStrategy::Claims { secret } => {
+                let issued_at = OffsetDateTime::now_utc().unix_timestamp();
+                let claims = serde_json::json!({
+                    "iat": issued_at,
+                    "exp": issued_at + CLAIMS_LIFETIME.whole_seconds(),
+                    "scope": scope,
+                });
+                let claims = general_purpose::URL_SAFE_NO_PAD.encode(claims.to_string());
+                let mac = double_submit_mac(secret, &claims, value, None);
+                Ok(format!(
+                    "{}.{}",
+                    claims,
+                    general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+                ))
+            }
+            #[cfg(feature = "ed25519")]
+            TokenStrategy::Ed25519 { .. } => Err(mint_unsupported(
+                "Ed25519 tokens are minted by the external auth service",
+            )),
+            TokenStrategy::Custom(_) => Err(mint_unsupported(
+                "custom tokens are minted outside of this crate",
+            )),
+        }
+    }
+
+    /// Checks whether a submitted token has the shape of a token minted by this strategy.
+    fn is_well_formed(&self, submitted: &str) -> bool {
+        match self {
+            TokenStrategy::Bcrypt => CsrfToken::is_well_formed(submitted),
+            // 32 bytes of HMAC-SHA256 encode to 43 characters of unpadded URL-safe base64.
+            TokenStrategy::TimeWindow { .. } => is_url_safe_base64(submitted, 43),
+            // A 16-character selector and a 43-character validator, see `mint`.
+            TokenStrategy::Split { .. } => {
+                submitted
+                    .split_once('.')
+                    .is_some_and(|(selector, validator)| {
+                        is_url_safe_base64(selector, 16) && is_url_safe_base64(validator, 43)
+                    })
+            }
+            // A 22-character message of 16 random bytes and a 43-character MAC, see `mint`.
+            TokenStrategy::SignedDoubleSubmit { .. } => {
+                submitted.split_once('.').is_some_and(|(message, mac)| {
+                    is_url_safe_base64(message, 22) && is_url_safe_base64(mac, 43)
+                })
+            }
+            // Claims of any length and a 43-character MAC, see `mint`.
+            #[cfg(feature = "json")]
+            TokenStrategy::Claims { .. } => {
+                submitted.split_once('.').is_some_and(|(claims, mac)| {
+                    !claims.is_empty()
+                        && is_url_safe_base64(claims, claims.len())
+                        && is_url_safe_base64(mac, 43)
+                })
+            }
+            #[cfg(feature = "ed25519")]
+            TokenStrategy::Ed25519 { .. } => signed_token_parts(submitted).is_some(),
+            TokenStrategy::Custom(_) => true,
+        }
+    }
+
+    /// Checks a submitted token against the encoded session token under an optional scope.
+    fn check(
+        &self,
+        value: &str,
+        submitted: &str,
+        scope: Option<&str>,
+        prehash: PrehashAlgo,
+    ) -> bool {
+        match self {
+            TokenStrategy::Bcrypt => {
+                verify(bcrypt_secret(value, scope, prehash).as_ref(), submitted).unwrap_or(false)
+            }
+            TokenStrategy::TimeWindow { step, secret } => {
+                let Ok(tag) = general_purpose::URL_SAFE_NO_PAD.decode(submitted) else {
+                    return false;
+                };
+                let current = time_step(*step);
+                (current - 1..=current + 1).any(|counter| {
+                    time_window_mac(secret, counter, scope)
+                        .verify_slice(&tag)
+                        .is_ok()
+                })
+            }
+            TokenStrategy::Split { secret } => {
+                let Some((selector, validator)) = submitted.split_once('.') else {
+                    return false;
+                };
+                let Ok(validator) = general_purpose::URL_SAFE_NO_PAD.decode(validator) else {
+                    return false;
+                };
+                // An unknown selector belongs to another session, or to none at all. Both halves
+                // are checked before either result is looked at.
+                let known = constant_time_eq(
+                    selector.as_bytes(),
+                    split_selector(secret, value).as_bytes(),
+                );
+                let authentic = split_validator(secret, selector, value, scope)
+                    .verify_slice(&validator)
+                    .is_ok();
+                (known & Choice::from(u8::from(authentic))).into()
+            }
+            TokenStrategy::SignedDoubleSubmit { secret } => {
+                let Some((message, mac)) = submitted.split_once('.') else {
+                    return false;
+                };
+                let Ok(mac) = general_purpose::URL_SAFE_NO_PAD.decode(mac) else {
+                    return false;
+                };
+                double_submit_mac(secret, message, value, scope)
+                    .verify_slice(&mac)
+                    .is_ok()
+            }
+            #[cfg(feature = "json")]
+            TokenStrategy::Claims { secret } => verified_claims(secret, value, submitted)
+                .is_some_and(|claims| {
+                    claims.expires_at > OffsetDateTime::now_utc().unix_timestamp()
+                        && claims.scope.as_deref() == scope
+                }),
+            #[cfg(feature = "ed25519")]
+            TokenStrategy::Ed25519 { public_key } => {
+                use ed25519_dalek::{Signature, VerifyingKey};
+
+                let (Some((mut message, signature)), Ok(key)) = (
+                    signed_token_parts(submitted),
+                    VerifyingKey::from_bytes(public_key),
+                ) else {
+                    return false;
+                };
+                if let Some(scope) = scope {
+                    message.push(0);
+                    message.extend_from_slice(scope.as_bytes());
+                }
+                key.verify_strict(&message, &Signature::from_bytes(&signature))
+                    .is_ok()
+            }
+            TokenStrategy::Custom(verify) => verify(&scoped_secret(value, scope), submitted),
+        }
+    }
+
+    /// Checks whether a submitted token is authentic for the session token but has expired.
+    #[cfg_attr(not(feature = "json"), allow(unused_variables))]
+    fn is_expired(&self, value: &str, submitted: &str) -> bool {
+        match self {
+            #[cfg(feature = "json")]
+            TokenStrategy::Claims { secret } => verified_claims(secret, value, submitted)
+                .is_some_and(|claims| {
+                    claims.expires_at <= OffsetDateTime::now_utc().unix_timestamp()
+                }),
+            _ => false,
+        }
+    }
+}
+
+/// Mints an authenticity token from a raw session secret, without Rocket.
+/// # Arguments
+/// * `secret` - The raw bytes of the session token, as stored in the CSRF cookie.
+/// * `strategy` - The scheme the token is minted under.
+///
+/// The token is minted as `CsrfToken::authenticity_token` does under the default configuration:
+/// without a scope, checksum or method binding, and with the default pre-hash digest. It is the
+/// counterpart of `verify_standalone`, e.g. for CLI tools and tests.
+///
+/// # Returns
+/// (`Result<String, CsrfError>`): The authenticity token, or `CsrfError::HashError` if hashing
+/// failed or the strategy can't mint tokens.
+pub fn mint_standalone(secret: &[u8], strategy: &TokenStrategy) -> Result<String, CsrfError> {
+    let value = general_purpose::STANDARD.encode(secret);
+    let token = strategy.mint(&value, None, PrehashAlgo::default(), BCRYPT_COST);
+    wipe(value.into_bytes());
+
+    Ok(token?)
+}
+
+/// Verifies an authenticity token against a raw session secret, without Rocket.
+/// # Arguments
+/// * `secret` - The raw bytes of the session token, as stored in the CSRF cookie.
+/// * `submitted` - The token to verify.
+/// * `strategy` - The scheme the token was minted under.
+///
+/// This is the check `CsrfToken::verify` runs for a single session token under the default
+/// configuration, decoupled from requests, cookies and the fairing, so it can be reused in non-Rocket
+/// contexts such as CLI tools and tests. Tokens bound to a scope or method, or prefixed with a
+/// checksum, don't verify here.
+///
+/// # Returns
+/// (`Result<(), CsrfError>`): A result indicating success if the token is valid, or a `CsrfError`
+/// describing why not.
+pub fn verify_standalone(
+    secret: &[u8],
+    submitted: &str,
+    strategy: &TokenStrategy,
+) -> Result<(), CsrfError> {
+    if submitted.is_empty() {
+        return Err(CsrfError::Missing);
+    }
+    if !strategy.is_well_formed(submitted) {
+        return Err(CsrfError::Malformed);
+    }
+
+    let value = general_purpose::STANDARD.encode(secret);
+    let result = if strategy.check(&value, submitted, None, PrehashAlgo::default()) {
+        Ok(())
+    } else if strategy.is_expired(&value, submitted) {
+        Err(CsrfError::Expired)
+    } else {
+        Err(CsrfError::Mismatch)
+    };
+    wipe(value.into_bytes());
+
+    result
+}
+
+/// Compares two tokens in constant time.
+/// # Arguments
+/// * `a` - The expected token.
+/// * `b` - The submitted token.
+///
+/// The time taken only depends on the lengths of the tokens, not on where they differ, so
+/// attackers can't guess a token byte by byte from response times. The result is a `Choice`
+/// rather than a `bool`, so it isn't branched on by accident before the comparison completes.
+///
+/// Every comparison of secret material in this crate is constant-time: bcrypt hashes are
+/// compared by `bcrypt::verify` with `subtle`, HMAC tags are checked with `Mac::verify_slice`,
+/// and the selectors of `TokenStrategy::Split` tokens and the pre-shared test token are compared
+/// with this function.
+///
+/// # Returns
+/// (`Choice`): Whether the tokens are equal, convertible into a `bool`.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> Choice {
+    a.ct_eq(b)
+}
+
+/// The claims of a `TokenStrategy::Claims` token that are checked on verification.
+#[cfg(feature = "json")]
+struct TokenClaims {
+    expires_at: i64,
+    scope: Option<String>,
+}
+
+/// Checks the MAC of a `TokenStrategy::Claims` token against the session token and parses its
+/// claims, returning None if either fails.
+#[cfg(feature = "json")]
+fn verified_claims(secret: &[u8], value: &str, submitted: &str) -> Option<TokenClaims> {
+    let (claims, mac) = submitted.split_once('.')?;
+    let mac = general_purpose::URL_SAFE_NO_PAD.decode(mac).ok()?;
+    // The claims are only parsed once they are known to be authentic.
+    double_submit_mac(secret, claims, value, None)
+        .verify_slice(&mac)
+        .ok()?;
+
+    let claims = general_purpose::URL_SAFE_NO_PAD.decode(claims).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&claims).ok()?;
+    let scope = match &claims["scope"] {
+        serde_json::Value::Null => None,
+        scope => Some(scope.as_str()?.to_string()),
+    };
+
+    Some(TokenClaims {
+        expires_at: claims["exp"].as_i64()?,
+        scope,
+    })
+}
+
+/// Returns the identifier of an encoded session token, see `CsrfToken::token_id`.
+fn token_id(value: &str) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(&Sha256::digest(value.as_bytes())[..12])
+}
+
+/// Returns the scope of step-up tokens minted at the given unix timestamp.
+fn step_up_scope(minted_at: i64) -> String {
+    format!("{}:{}", STEP_UP_SCOPE, minted_at)
+}
+
+/// Builds the error returned when minting under a verification-only strategy.
+fn mint_unsupported(reason: &'static str) -> BcryptError {
+    BcryptError::Io(std::io::Error::new(std::io::ErrorKind::Unsupported, reason))
+}
+
+/// Splits an Ed25519-signed token into its decoded payload and signature.
+#[cfg(feature = "ed25519")]
+fn signed_token_parts(submitted: &str) -> Option<(Vec<u8>, [u8; 64])> {
+    let (payload, signature) = submitted.split_once('.')?;
+    let payload = general_purpose::URL_SAFE_NO_PAD.decode(payload).ok()?;
+    let signature = general_purpose::URL_SAFE_NO_PAD.decode(signature).ok()?;
+
+    Some((payload, signature.try_into().ok()?))
+}
+
+/// Returns the index of the current time step of length `step`.
+fn time_step(step: Duration) -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp() / step.whole_seconds().max(1)
+}
+
+/// Computes the HMAC of a time-window token for the given time step and optional scope.
+fn time_window_mac(secret: &[u8], counter: i64, scope: Option<&str>) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(&counter.to_be_bytes());
+    if let Some(scope) = scope {
+        mac.update(&[0]);
+        mac.update(scope.as_bytes());
+    }
+    mac
+}
+
+/// Checks whether `encoded` is `len` characters of unpadded URL-safe base64.
+fn is_url_safe_base64(encoded: &str, len: usize) -> bool {
+    encoded.len() == len
+        && encoded
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_'))
+}
+
+/// Computes the MAC of `TokenStrategy::SignedDoubleSubmit` tokens over the session token and
+/// the message, under an optional scope.
+fn double_submit_mac(
+    secret: &[u8],
+    message: &str,
+    value: &str,
+    scope: Option<&str>,
+) -> Hmac<Sha256> {
+    let session = scoped_secret(value, scope);
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    // The length prefix keeps the boundary between the session token and the message unambiguous.
+    mac.update(&(session.len() as u64).to_be_bytes());
+    mac.update(session.as_bytes());
+    mac.update(message.as_bytes());
+    mac
+}
+
+/// Derives the selector of `TokenStrategy::Split` tokens from the encoded session token.
+fn split_selector(secret: &[u8], value: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(b"selector\0");
+    mac.update(value.as_bytes());
+    general_purpose::URL_SAFE_NO_PAD.encode(&mac.finalize().into_bytes()[..12])
+}
+
+/// Computes the validator of `TokenStrategy::Split` tokens for a selector and optional scope.
+fn split_validator(
+    secret: &[u8],
+    selector: &str,
+    value: &str,
+    scope: Option<&str>,
+) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(b"validator\0");
+    mac.update(selector.as_bytes());
+    mac.update(&[0]);
+    mac.update(scoped_secret(value, scope).as_bytes());
+    mac
+}
+
+/// Callback modifying freshly generated session token bytes, see `CsrfConfig::with_token_transform`.
+pub type TokenTransform = Arc<dyn Fn(&mut Vec<u8>) + Send + Sync>;
+
+/// Holds the token transform so that `CsrfConfig` remains `Debug`.
+#[derive(Clone)]
+struct TokenTransformer(TokenTransform);
+
+impl fmt::Debug for TokenTransformer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TokenTransform")
+    }
+}
+
+/// Predicate on requests, see `CsrfConfig::with_skip_issue_when`.
+pub type IssuePredicate = Arc<dyn Fn(&Request<'_>) -> bool + Send + Sync>;
+
+/// Holds the issuance predicate so that `CsrfConfig` remains `Debug`.
+#[derive(Clone)]
+struct SkipIssue(IssuePredicate);
+
+impl fmt::Debug for SkipIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("IssuePredicate")
+    }
+}
+
+/// Storage of the tokens already used under `CsrfConfig::with_single_use`.
+///
+/// Implementations backed by a shared store, e.g. Redis with `SET NX EX`, extend replay
+/// protection across the nodes of a deployment.
+pub trait NonceStore: Send + Sync {
+    /// Records a nonce for `ttl`.
+    /// # Arguments
+    /// * `nonce` - The digest of a verified token.
+    /// * `ttl` - How long the nonce must be remembered.
+    ///
+    /// # Returns
+    /// (`bool`): `true` if the nonce was recorded, `false` if it already was.
+    fn insert_if_absent(&self, nonce: &str, ttl: Duration) -> bool;
+}
+
+/// The default `NonceStore`, keeping nonces in the memory of the current process.
+#[derive(Debug, Default)]
+pub struct MemoryNonceStore {
+    nonces: Mutex<ExpiringMap<String, ()>>,
+}
+
+impl NonceStore for MemoryNonceStore {
+    fn insert_if_absent(&self, nonce: &str, ttl: Duration) -> bool {
+        let now = OffsetDateTime::now_utc();
+        match lock(&self.nonces).live_entry(nonce.to_string(), now) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert((now + ttl, ()));
+                true
+            }
+        }
+    }
+}
+
+/// Entries remembered until their expiry.
+///
+/// An expired entry is dropped when its key is looked up again. The others are pruned in batches,
+/// once the map has doubled in size since the last pruning, so each lookup costs amortized
+/// constant time however many keys are live.
+#[derive(Debug)]
+struct ExpiringMap<K, V> {
+    entries: HashMap<K, (OffsetDateTime, V)>,
+    prune_at: usize,
+}
+
+impl<K, V> Default for ExpiringMap<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            prune_at: PRUNE_THRESHOLD,
+        }
+    }
+}
+
+impl<K: std::hash::Hash + Eq, V> ExpiringMap<K, V> {
+    /// Returns the entry of `key`, vacant if it expired by `now`.
+    fn live_entry(&mut self, key: K, now: OffsetDateTime) -> Entry<'_, K, (OffsetDateTime, V)> {
+        if self.entries.len() >= self.prune_at {
+            self.entries.retain(|_, (expires_at, _)| *expires_at > now);
+            self.prune_at = PRUNE_THRESHOLD.max(2 * self.entries.len());
+        }
+        if self
+            .entries
+            .get(&key)
+            .is_some_and(|(expires_at, _)| *expires_at <= now)
+        {
+            self.entries.remove(&key);
+        }
+
+        self.entries.entry(key)
+    }
+}
+
+/// Holds the nonce store so that `CsrfConfig` remains `Debug`.
+#[derive(Clone)]
+struct NonceStoreHandle(Arc<dyn NonceStore>);
+
+impl fmt::Debug for NonceStoreHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NonceStore")
+    }
+}
+
+/// The limit on CSRF cookies issued per client IP address, see
+/// `CsrfConfig::with_issue_rate_limit`. The counters are shared by the clones of the config.
+#[derive(Debug, Clone)]
+struct IssueRateLimit {
+    limit: u32,
+    window: Duration,
+    /// The end of the current window of each client IP address, and the cookies issued in it.
+    issued: Arc<Mutex<ExpiringMap<IpAddr, u32>>>,
+}
+
+impl IssueRateLimit {
+    /// Counts an issuance to `ip`, returning `false` if the client already reached the limit.
+    fn allow(&self, ip: IpAddr) -> bool {
+        let now = OffsetDateTime::now_utc();
+        let mut issued = lock(&self.issued);
+
+        let (_, count) = issued.live_entry(ip, now).or_insert((now + self.window, 0));
+        if *count >= self.limit {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+}
+
+/// Destination of the security event log written by `CsrfConfig::with_audit_sink`.
+pub trait AuditSink: Send + Sync {
+    /// Records the outcome of a verification.
+    /// # Arguments
+    /// * `entry` - The details of the verification.
+    fn record(&self, entry: &AuditEntry);
+}
+
+/// The default `AuditSink`, discarding every entry.
+#[derive(Debug, Default)]
+pub struct NoopAuditSink;
+
+impl AuditSink for NoopAuditSink {
+    fn record(&self, _entry: &AuditEntry) {}
+}
+
+/// Holds the audit sink so that `CsrfConfig` remains `Debug`.
+#[derive(Clone)]
+struct AuditSinkHandle(Arc<dyn AuditSink>);
+
+impl fmt::Debug for AuditSinkHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AuditSink")
+    }
+}
+
+/// The outcome of a verification, passed to the `AuditSink` of `CsrfConfig::with_audit_sink`.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct AuditEntry {
+    /// When the verification took place.
+    pub timestamp: OffsetDateTime,
+    /// The path of the request, if the token was read from one.
+    pub path: Option<String>,
+    /// Where the verified token was found, if it was verified through `CsrfToken::verify_request`.
+    pub source: Option<TokenSource>,
+    /// The IP address of the client, if known.
+    pub client_ip: Option<IpAddr>,
+    /// Whether the token verified, or why not.
+    pub outcome: Result<(), CsrfError>,
+}
+
+/// The request a `CsrfToken` was read from, reported in `AuditEntry`.
+#[derive(Debug, Clone)]
+struct RequestOrigin {
+    path: String,
+    client_ip: Option<IpAddr>,
+}
+
+/// Callback extracting the CSRF secret from the value of an SSO cookie, see
+/// `CsrfConfig::with_secret_from_cookie`.
+pub type SecretExtractor = Arc<dyn Fn(&str) -> Option<Vec<u8>> + Send + Sync>;
+
+/// The SSO cookie the session token is extracted from, see `CsrfConfig::with_secret_from_cookie`.
+#[derive(Clone)]
+struct CookieSecret {
+    cookie_name: String,
+    extract: SecretExtractor,
+}
+
+impl fmt::Debug for CookieSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CookieSecret")
+            .field("cookie_name", &self.cookie_name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A key signing CSRF cookies, see `CsrfConfig::migrate_to_shared_key`.
+#[derive(Clone)]
+struct SharedKey([u8; 32]);
+
+impl fmt::Debug for SharedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedKey(..)")
+    }
+}
+
+impl SharedKey {
+    /// Computes the MAC of a cookie value, bound to the cookie name like private cookies are.
+    fn mac(&self, name: &str, value: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.0).expect("HMAC accepts keys of any size");
+        mac.update(name.as_bytes());
+        mac.update(&[0]);
+        mac.update(value.as_bytes());
+        mac
+    }
+
+    /// Appends the signature of `value` to it.
+    fn sign(&self, name: &str, value: &str) -> String {
+        let tag = self.mac(name, value).finalize().into_bytes();
+        format!("{}.{}", value, general_purpose::URL_SAFE_NO_PAD.encode(tag))
+    }
+
+    /// Returns the value of a signed cookie if its signature is valid.
+    fn verify(&self, name: &str, signed: &str) -> Option<String> {
+        let (value, tag) = signed.rsplit_once('.')?;
+        let tag = general_purpose::URL_SAFE_NO_PAD.decode(tag).ok()?;
+        self.mac(name, value).verify_slice(&tag).ok()?;
+        Some(value.to_string())
+    }
+}
+
+/// Reads a numeric environment variable, warning about and ignoring unparseable values.
+fn env_number<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let value = env::var(name).ok()?;
+    match value.parse() {
+        Ok(number) => Some(number),
+        Err(_) => {
+            warn!("Ignoring {}: {:?} is not a valid number.", name, value);
+            None
+        }
+    }
+}
+
+/// Rocket fairing for CSRF protection. This fairing is responsible for handling and managing CSRF tokens
+/// during Rocket application runtime.
+#[derive(Clone)]
+pub struct Fairing {
+    config: CsrfConfig,
+    on_issue: Option<IssueHook>,
+}
+
+/// Callback invoked by the fairing whenever a new CSRF cookie is issued.
+pub type IssueHook = Arc<dyn Fn(&IssueEvent) + Send + Sync>;
+
+/// Details about a freshly issued CSRF cookie, passed to the `Fairing::with_on_issue` hook.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct IssueEvent {
+    /// The path of the request that triggered the issuance.
+    pub path: String,
+    /// The length of the issued token in bytes.
+    pub token_len: usize,
+}
+
+impl Default for Fairing {
+    /// Creates a default Fairing with the default CsrfConfig settings. This fairing can be used to
+    /// automatically manage CSRF tokens in a Rocket application.
+    ///
+    /// This function returns a new Fairing instance with the default CsrfConfig settings.
+    fn default() -> Self {
+        Self::new(CsrfConfig::default())
+    }
+}
+
+/// Define custom methods and functions for the `Fairing` type itself.
+/// It is like defining methods in a blueprint or abstract class.
+impl Fairing {
+    /// Creates a new CSRF protection fairing with the provided configuration.
+    /// # Arguments
+    /// * `config` - The configuration specifying how CSRF tokens should be managed.
+    ///
+    /// This function creates a new Fairing instance with the given configuration, allowing for
+    /// customization of CSRF token management in a Rocket application.
+    pub fn new(config: CsrfConfig) -> Self {
+        Self {
+            config,
+            on_issue: None,
+        }
+    }
+
+    /// Sets a hook invoked whenever a new CSRF cookie is issued.
+    /// # Arguments
+    /// * `hook` - The callback receiving the details of the issuance.
+    ///
+    /// The hook is not invoked when the request already carries a valid CSRF token, which makes it
+    /// suitable for collecting metrics on the issuance rate. A panicking hook is caught and logged
+    /// so it can't take the request down with it.
+    pub fn with_on_issue(mut self, hook: IssueHook) -> Self {
+        self.on_issue = Some(hook);
+        self
+    }
+
+    /// Issues the CSRF cookie unless the request already carries a valid CSRF token.
+    fn issue_if_needed(&self, request: &Request<'_>, config: &CsrfConfig) {
+        // The session token comes from a cookie that is the application's or the SSO's to set.
+        if config.has_external_session_token() {
+            return;
+        }
+        if !config.issue_on_head && request.method() == Method::Head {
+            return;
+        }
+        if let Some(SkipIssue(skip)) = &config.skip_issue_when {
+            if skip(request) {
+                return;
+            }
+        }
+
+        let active = config.preferred_configs().into_iter().find(|config| {
+            request
+                .valid_csrf_token_from_session(config)
+                .map(wipe)
+                .is_some()
+        });
+        if let Some(active) = active {
+            if config.clear_stale_cookies {
+                clear_stale_cookies(request, config, active);
+            }
+            if config.shared_key.is_some() {
+                migrate_private_cookie(request, config);
+            }
+            // A legacy cookie only stands in for the current one when it is preferred.
+            if std::ptr::eq(active, config) || config.cookie_preference == CookiePreference::Legacy
+            {
+                return;
+            }
+        }
+        if request.has_undecryptable_csrf_cookie(config) {
+            warn!(
+                "CSRF cookie `{}` could not be decrypted; Rocket's secret key may be missing or have changed.",
+                config.full_cookie_name()
+            );
+        }
+
+        if config.https_only && !is_https(request) {
+            error!(
+                "Refusing to issue CSRF cookie `{}` over plain HTTP.",
+                config.full_cookie_name()
+            );
+            return;
+        }
+        if !config.allow_issue(request) {
+            return;
+        }
+
+        let encoded = generate_session_token(config);
+        let _ = request
+            .local_cache(IssuedToken::default)
+            .0
+            .set((config.full_cookie_name(), encoded.clone()));
+
+        self.issue(request, config, encoded);
+        // Otherwise the hook waits for `on_response`, which may still withhold the cookie.
+        if !config.issue_on_success_only {
+            self.notify_issue(request, config);
+        }
+    }
+
+    /// Sets the CSRF cookie carrying the given encoded session token.
+    fn issue(&self, request: &Request<'_>, config: &CsrfConfig, encoded: String) {
+        let cookie = config.session_cookie(encoded);
+
+        if config.development {
+            info!(
+                "Issuing CSRF cookie `{}` (SameSite={}, Secure={:?}, Expires={:?}).",
+                cookie.name(),
+                config.same_site,
+                cookie.secure(),
+                cookie.expires_datetime()
+            );
+        }
+
+        let name = cookie.name().to_string();
+        config.add_cookie(request.cookies(), cookie);
+        // The jar applies its defaults when adding, so record the cookie as it will be sent.
+        if let Some(cookie) = request.cookies().get_pending(&name) {
+            *lock(&request.local_cache(IssuedCookieRecord::default).0) =
+                Some(IssuedCookie::from(&cookie));
+        }
+        info!("CSRF cookie added successfully.");
+    }
+
+    /// Counts the CSRF cookie issued during the request and notifies the issue hook.
+    fn notify_issue(&self, request: &Request<'_>, config: &CsrfConfig) {
+        config.metrics.issued.fetch_add(1, Ordering::Relaxed);
+
+        if let Some(hook) = &self.on_issue {
+            let event = IssueEvent {
+                path: request.uri().path().to_string(),
+                token_len: config.token_len(),
+            };
+            if panic::catch_unwind(AssertUnwindSafe(|| hook(&event))).is_err() {
+                error!("CSRF issuance hook panicked");
+            }
+        }
+    }
+
+    /// Returns the configuration this fairing manages.
+    pub fn effective_config(&self) -> &CsrfConfig {
+        &self.config
+    }
+
+    /// Returns a structured summary of the active configuration, e.g. for admin endpoints or
+    /// deployment checks.
+    pub fn summary(&self) -> ConfigSummary {
+        self.config.summary()
+    }
+
+    /// Returns a snapshot of the issuance and verification counters, e.g. for admin endpoints.
+    ///
+    /// The counters are shared with the configuration the fairing manages, so verifications made
+    /// through any `CsrfToken` guard are included. They are also shared between clones of the
+    /// fairing: clone it before attaching it to keep a handle, e.g. in managed state.
+    pub fn metrics(&self) -> CsrfMetrics {
+        self.config.metrics.snapshot()
+    }
+}
+
+/// Counters of issued, verified and rejected tokens, updated as requests are handled.
+#[derive(Debug, Default)]
+struct MetricsCounters {
+    issued: AtomicU64,
+    verified: AtomicU64,
+    rejected: AtomicU64,
+    /// The histogram of verification durations, see `latency_bucket`.
+    verify_latency: [AtomicU64; LATENCY_BUCKETS],
+}
+
+impl MetricsCounters {
+    /// Reads the current values of the counters.
+    fn snapshot(&self) -> CsrfMetrics {
+        CsrfMetrics {
+            issued: self.issued.load(Ordering::Relaxed),
+            verified: self.verified.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+            verify_latency: self
+                .verify_latency
+                .each_ref()
+                .map(|bucket| bucket.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Counts a verification that took `elapsed` in the latency histogram.
+    fn record_latency(&self, elapsed: std::time::Duration) {
+        self.verify_latency[latency_bucket(elapsed)].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns the histogram bucket of a duration: bucket `i` holds durations below `2^i`
+/// microseconds and at least half of that, with the last bucket holding everything longer.
+fn latency_bucket(elapsed: std::time::Duration) -> usize {
+    let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+    ((u64::BITS - micros.leading_zeros()) as usize).min(LATENCY_BUCKETS - 1)
+}
+
+/// A snapshot of the token counters of a `Fairing`, as returned by `Fairing::metrics`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CsrfMetrics {
+    /// The number of CSRF cookies issued.
+    pub issued: u64,
+    /// The number of submitted tokens that passed verification.
+    pub verified: u64,
+    /// The number of submitted tokens that failed verification.
+    pub rejected: u64,
+    /// The histogram of verification durations, see `latency_bucket`.
+    verify_latency: [u64; LATENCY_BUCKETS],
+}
+
+impl CsrfMetrics {
+    /// Returns percentiles of the verification durations recorded under
+    /// `CsrfConfig::with_latency_recording`.
+    ///
+    /// Durations are counted in power-of-two buckets, so each percentile is reported as the
+    /// upper bound of its bucket, within a factor of two of the actual durations.
+    ///
+    /// # Returns
+    /// (`Option<LatencyPercentiles>`): The percentiles, or None if no duration was recorded.
+    pub fn verify_latency_percentiles(&self) -> Option<LatencyPercentiles> {
+        let total: u64 = self.verify_latency.iter().sum();
+        if total == 0 {
+            return None;
+        }
+
+        let percentile = |percent: u64| {
+            // The rank of the sample at `percent`, counted from 1.
+            let rank = (total * percent).div_ceil(100).max(1);
+            let mut seen = 0;
+            let bucket = self
+                .verify_latency
+                .iter()
+                .position(|count| {
+                    seen += count;
+                    seen >= rank
+                })
+                .unwrap_or(LATENCY_BUCKETS - 1);
+            std::time::Duration::from_micros(1 << bucket)
+        };
+
+        Some(LatencyPercentiles {
+            samples: total,
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+        })
+    }
+}
+
+/// Percentiles of verification durations, as returned by `CsrfMetrics::verify_latency_percentiles`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LatencyPercentiles {
+    /// The number of recorded verifications.
+    pub samples: u64,
+    /// The median duration.
+    pub p50: std::time::Duration,
+    /// The duration 90% of verifications stayed under.
+    pub p90: std::time::Duration,
+    /// The duration 99% of verifications stayed under.
+    pub p99: std::time::Duration,
+}
+
+/// A snapshot of the settings of a `CsrfConfig`, readable without access to its private fields.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct ConfigSummary {
+    /// The duration for which the CSRF token remains valid.
+    pub lifespan: Option<Duration>,
+    /// The name of the CSRF cookie that stores the token.
+    pub cookie_name: String,
+    /// The length of the CSRF token in bytes.
+    pub cookie_len: usize,
+    /// The `SameSite` policy of the CSRF cookie.
+    pub same_site: SameSite,
+    /// Whether the `SameSite=None` attribute is left out for browsers that mishandle it.
+    pub same_site_none_fallback: bool,
+    /// Whether the CSRF cookie carries the `Secure` flag.
+    pub secure: bool,
+    /// Whether the CSRF cookie carries the `HttpOnly` flag.
+    pub http_only: bool,
+    /// The `Domain` attribute of the CSRF cookie, `None` for a host-only cookie.
+    pub cookie_domain: Option<String>,
+    /// The `Path` attribute of the CSRF cookie.
+    pub cookie_path: String,
+    /// Whether the development preset is active.
+    pub development: bool,
+    /// The number of legacy configurations whose tokens remain verifiable.
+    pub legacy_configs: usize,
+    /// How long the session token replaced by a rotation remains valid.
+    pub rotation_grace: Option<Duration>,
+    /// Whether the `CsrfQuery` guard accepts tokens from the query string.
+    pub query_tokens: bool,
+    /// Whether authenticity tokens are bound to the request path.
+    pub route_scoping: bool,
+    /// Whether CSRF meta tags are injected into HTML responses.
+    pub meta_tag_injection: bool,
+    /// Whether session tokens must match `cookie_len` exactly.
+    pub strict_length: bool,
+    /// The maximum number of verification attempts per submitted token.
+    pub max_verify_attempts: usize,
+    /// The name of the token strategy minting new tokens, e.g. `"bcrypt"`.
+    pub strategy: &'static str,
+    /// The names of the further strategies tokens are verified with.
+    pub fallback_strategies: Vec<&'static str>,
+    /// The names of the strategies tokens are verified with against every session token.
+    pub migration_set: Vec<&'static str>,
+    /// Where clients can fetch a fresh token after a stale one is rejected.
+    pub refresh_hint: Option<String>,
+    /// The status code of responses rejecting requests that came without a CSRF session.
+    pub no_session_status: u16,
+    /// How many CSRF cookies a client IP address may be issued per window.
+    pub issue_rate_limit: Option<(u32, Duration)>,
+    /// Whether the CSRF cookie is looked up regardless of the case of its name.
+    pub case_insensitive_cookie_lookup: bool,
+    /// Whether whitespace embedded in tokens is ignored.
+    pub lenient_whitespace: bool,
+    /// Whether the CSRF cookie is only set on successful responses.
+    pub issue_on_success_only: bool,
+    /// The `Authorization` scheme under which tokens are accepted.
+    pub authorization_scheme: Option<String>,
+    /// The path of the endpoint returning a fresh token, if mounted.
+    pub token_endpoint: Option<String>,
+    /// How long step-up tokens remain valid, if enabled.
+    pub step_up: Option<Duration>,
+    /// Whether the current or the legacy CSRF cookies are preferred when several are present.
+    pub cookie_preference: CookiePreference,
+    /// Whether the CSRF cookies that are not preferred are removed.
+    pub clear_stale_cookies: bool,
+    /// Whether CSRF cookies are signed with a shared key rather than encrypted as private cookies.
+    pub shared_key: bool,
+    /// Whether CSRF cookie values are signed with an additional server-side key.
+    pub extra_signing_key: bool,
+    /// The media types of request bodies carrying form fields.
+    pub form_content_types: Vec<String>,
+    /// Whether CSRF cookies are only issued over HTTPS.
+    pub https_only: bool,
+    /// The name of the parameter carrying the authenticity token.
+    pub param_name: String,
+    /// The path of the form field carrying the authenticity token.
+    pub form_field: String,
+    /// The name of the request header carrying the authenticity token.
+    pub header_name: String,
+    /// Whether verification is left to handlers, without the global `VerifyAllRequests` check.
+    pub manual_only: bool,
+    /// Whether mutating requests without a valid token are rejected by the fairing.
+    pub reject_invalid: bool,
+    /// The digest pre-hashing secrets too long for bcrypt.
+    pub prehash: PrehashAlgo,
+    /// The cost factor of the bcrypt hashes minted as authenticity tokens.
+    pub bcrypt_cost: u32,
+    /// The path on which the fairing removes the CSRF cookie.
+    pub logout_path: Option<String>,
+    /// The session cookie the session token is derived from, if any.
+    pub derive_from_session: Option<String>,
+    /// The SSO cookie the session token is extracted from.
+    pub secret_from_cookie: Option<String>,
+    /// Whether authenticity tokens are prefixed with a checksum.
+    pub token_checksum: bool,
+    /// Whether authenticity tokens are bound to the HTTP method of the request.
+    pub method_binding: bool,
+    /// Whether a pre-shared test token is accepted.
+    pub test_token: bool,
+    /// Whether responses carry an authenticity token in a header as well.
+    pub cdn_safe_token: bool,
+    /// The paths on which GET requests must carry a valid authenticity token.
+    pub protected_get_paths: Vec<String>,
+    /// The methods of the requests that must carry a valid authenticity token.
+    pub protected_methods: Vec<Method>,
+    /// Whether the durations of verifications are recorded in the metrics.
+    pub latency_recording: bool,
+    /// Whether responses announce the replaced and new session tokens after a rotation.
+    pub rotation_headers: bool,
+    /// Whether HEAD requests are issued a CSRF cookie like GET requests.
+    pub issue_on_head: bool,
+    /// Whether authenticity tokens verify only once.
+    pub single_use: bool,
+    /// Whether verification is skipped for requests a browser marks as same-origin.
+    pub same_origin_shortcut: bool,
+}
+
+#[cfg(feature = "json")]
+impl ConfigSummary {
+    /// Returns the summary as a JSON object, following `CsrfConfig::schema`.
+    ///
+    /// Durations are expressed in seconds, and enums by the names of their variants.
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::{json, Value};
+
+        let seconds = |duration: Option<Duration>| duration.map(|d| d.whole_seconds());
+
+        let fields = [
+            ("lifespan", json!(seconds(self.lifespan))),
+            ("cookie_name", json!(self.cookie_name)),
+            ("cookie_len", json!(self.cookie_len)),
+            ("same_site", json!(self.same_site.to_string())),
+            (
+                "same_site_none_fallback",
+                json!(self.same_site_none_fallback),
+            ),
+            ("secure", json!(self.secure)),
+            ("http_only", json!(self.http_only)),
+            ("cookie_domain", json!(self.cookie_domain)),
+            ("cookie_path", json!(self.cookie_path)),
+            ("development", json!(self.development)),
+            ("legacy_configs", json!(self.legacy_configs)),
+            ("rotation_grace", json!(seconds(self.rotation_grace))),
+            ("query_tokens", json!(self.query_tokens)),
+            ("route_scoping", json!(self.route_scoping)),
+            ("meta_tag_injection", json!(self.meta_tag_injection)),
+            ("strict_length", json!(self.strict_length)),
+            ("max_verify_attempts", json!(self.max_verify_attempts)),
+            ("strategy", json!(self.strategy)),
+            ("fallback_strategies", json!(self.fallback_strategies)),
+            ("migration_set", json!(self.migration_set)),
+            ("refresh_hint", json!(self.refresh_hint)),
+            ("no_session_status", json!(self.no_session_status)),
+            (
+                "issue_rate_limit",
+                json!(self.issue_rate_limit.map(|(limit, window)| {
+                    json!({ "limit": limit, "window": window.whole_seconds() })
+                })),
+            ),
+            (
+                "case_insensitive_cookie_lookup",
+                json!(self.case_insensitive_cookie_lookup),
+            ),
+            ("lenient_whitespace", json!(self.lenient_whitespace)),
+            ("issue_on_success_only", json!(self.issue_on_success_only)),
+            ("authorization_scheme", json!(self.authorization_scheme)),
+            ("token_endpoint", json!(self.token_endpoint)),
+            ("step_up", json!(seconds(self.step_up))),
+            (
+                "cookie_preference",
+                json!(format!("{:?}", self.cookie_preference)),
+            ),
+            ("clear_stale_cookies", json!(self.clear_stale_cookies)),
+            ("shared_key", json!(self.shared_key)),
+            ("extra_signing_key", json!(self.extra_signing_key)),
+            ("form_content_types", json!(self.form_content_types)),
+            ("https_only", json!(self.https_only)),
+            ("param_name", json!(self.param_name)),
+            ("form_field", json!(self.form_field)),
+            ("header_name", json!(self.header_name)),
+            ("manual_only", json!(self.manual_only)),
+            ("reject_invalid", json!(self.reject_invalid)),
+            ("prehash", json!(format!("{:?}", self.prehash))),
+            ("bcrypt_cost", json!(self.bcrypt_cost)),
+            ("logout_path", json!(self.logout_path)),
+            ("derive_from_session", json!(self.derive_from_session)),
+            ("secret_from_cookie", json!(self.secret_from_cookie)),
+            ("token_checksum", json!(self.token_checksum)),
+            ("method_binding", json!(self.method_binding)),
+            ("test_token", json!(self.test_token)),
+            ("cdn_safe_token", json!(self.cdn_safe_token)),
+            ("protected_get_paths", json!(self.protected_get_paths)),
+            (
+                "protected_methods",
+                json!(self
+                    .protected_methods
+                    .iter()
+                    .map(|method| method.as_str())
+                    .collect::<Vec<_>>()),
+            ),
+            ("latency_recording", json!(self.latency_recording)),
+            ("rotation_headers", json!(self.rotation_headers)),
+            ("issue_on_head", json!(self.issue_on_head)),
+            ("single_use", json!(self.single_use)),
+            ("same_origin_shortcut", json!(self.same_origin_shortcut)),
+        ];
+
+        Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        )
+    }
+}
+
+/// Structure to hold a CSRF token. This token can be used for generating authenticity tokens
+/// and verifying the authenticity of incoming requests.
+#[derive(Clone)]
+pub struct CsrfToken {
+    /// The base64-encoded session token.
+    value: String,
+    /// The name of the cookie the session token was read from.
+    cookie_name: Cow<'static, str>,
+    /// The configuration the session token was read under.
+    config: CsrfConfig,
+    /// The session token replaced by the last rotation, accepted during the grace window.
+    previous: Option<String>,
+    /// The scope authenticity tokens are bound to, i.e. the request path under route scoping.
+    scope: Option<String>,
+    /// The HTTP method authenticity tokens are bound to under method binding.
+    method: Option<Method>,
+    /// The session tokens read under each legacy configuration.
+    legacy: Vec<CsrfToken>,
+    /// Whether the request carried a CSRF cookie that could not be decrypted.
+    undecryptable_cookie: bool,
+    /// Whether the session token was issued during the request rather than sent along with it.
+    fresh_session: bool,
+    /// The request the session token was read from, if any.
+    origin: Option<RequestOrigin>,
+    /// Whether the request is trusted as same-origin under the same-origin shortcut.
+    same_origin: bool,
+    /// The rotations of the session token during the request, shared with the fairing.
+    rotation: RotationRecord,
+}
+
+/// Clears the session tokens held by the guard when it is dropped.
+#[cfg(feature = "zeroize")]
+impl Drop for CsrfToken {
+    fn drop(&mut self) {
+        self.value.zeroize();
+        self.previous.zeroize();
+    }
+}
+
+/// The state of a `CsrfToken` needed to verify authenticity tokens outside of the request, e.g. in
+/// a background worker, see `CsrfToken::snapshot`.
+///
+/// With the `snapshot` feature, the snapshot implements `Serialize` and `Deserialize`, e.g. to be
+/// handed to a job queue. The serialized form holds the session token in the clear, so it must
+/// only travel over trusted channels such as an internal job queue, and never back to the client.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "snapshot",
+    derive(Serialize, Deserialize),
+    serde(crate = "rocket::serde")
+)]
+pub struct CsrfSnapshot {
+    value: String,
+    previous: Option<String>,
+    cookie_name: String,
+    scope: Option<String>,
+    method: Option<String>,
+    token_len: usize,
+    prehash: PrehashAlgo,
+    token_checksum: bool,
+    lenient_whitespace: bool,
+    strict_length: bool,
+    max_verify_attempts: usize,
+}
+
+impl fmt::Debug for CsrfSnapshot {
+    /// Formats the snapshot without revealing the session tokens.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CsrfSnapshot")
+            .field("cookie_name", &self.cookie_name)
+            .field("scope", &self.scope)
+            .field("method", &self.method)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Clears the session tokens held by the snapshot when it is dropped.
+#[cfg(feature = "zeroize")]
+impl Drop for CsrfSnapshot {
+    fn drop(&mut self) {
+        self.value.zeroize();
+        self.previous.zeroize();
+    }
+}
+
+/// Forms carrying an authenticity token, verified with `CsrfProtected::verify`.
+///
+/// With the `derive` feature, `#[derive(CsrfProtected)]` implements it for structs whose
+/// `authenticity_token` field holds the token, or the field named by `#[csrf(field = "...")]`:
+///
+/// ```rust,ignore
+/// use rocket::{form::Form, post, FromForm};
+/// use rocket_csrf_token::{CsrfError, CsrfProtected, CsrfToken};
+///
+/// #[derive(FromForm, CsrfProtected)]
+/// struct Comment {
+///     authenticity_token: String,
+///     body: String,
+/// }
+///
+/// #[post("/comments", data = "<form>")]
+/// fn create(csrf_token: CsrfToken, form: Form<Comment>) -> Result<String, CsrfError> {
+///     form.verify(&csrf_token)?;
+///     Ok(format!("Created: {}", form.body))
+/// }
+/// ```
+pub trait CsrfProtected {
+    /// Returns the authenticity token submitted with the form.
+    fn authenticity_token(&self) -> &str;
+
+    /// Verifies the submitted authenticity token against the session's CSRF token.
+    /// # Arguments
+    /// * `csrf_token` - The CSRF token of the current request.
+    ///
+    /// # Returns
+    /// (`Result<(), CsrfError>`): A result indicating success if the tokens match, or a
+    /// `CsrfError` if they do not.
+    fn verify(&self, csrf_token: &CsrfToken) -> Result<(), CsrfError> {
+        csrf_token.verify(self.authenticity_token())
+    }
+}
+
+/// Define custom methods and functions for the `CsrfToken` type itself.
+/// Again, it is like defining methods in a blueprint or abstract class.
+impl CsrfToken {
+    /// Creates a CsrfToken from an encoded session token with no legacy tokens.
+    fn new(value: String, config: &CsrfConfig) -> Self {
+        Self {
+            value,
+            cookie_name: config.full_cookie_name(),
+            config: config.clone(),
+            previous: None,
+            scope: None,
+            method: None,
+            legacy: Vec::new(),
+            undecryptable_cookie: false,
+            fresh_session: false,
+            origin: None,
+            same_origin: false,
+            rotation: RotationRecord::default(),
+        }
+    }
+
+    /// Reads the CSRF token from the session, along with the tokens of every legacy configuration.
+    /// # Arguments
+    /// * `request` - The request whose session holds the token.
+    /// * `config` - The CsrfConfig to use for reading the token.
+    ///
+    /// The token read under the preferred configuration (see `CsrfConfig::with_cookie_preference`)
+    /// mints authenticity tokens, the others are kept as fallbacks for verification.
+    ///
+    /// # Returns
+    /// (`Option<CsrfToken>`): Some if the session holds a valid token for `config` or one of its
+    /// legacy configurations, None otherwise.
+    fn from_session(request: &Request<'_>, config: &CsrfConfig) -> Option<Self> {
+        let mut tokens = config
+            .preferred_configs()
+            .into_iter()
+            .filter_map(|config| Self::read_session(request, config));
+        let mut token = tokens.next()?;
+        token.legacy.extend(tokens);
+        Some(token)
+    }
+
+    /// Reads the CSRF token stored under `config` alone, ignoring its legacy configurations.
+    fn read_session(request: &Request<'_>, config: &CsrfConfig) -> Option<Self> {
+        let token = request.valid_csrf_token_from_session(config)?;
+        let value = general_purpose::STANDARD.encode(&token);
+        wipe(token);
+
+        Some(Self {
+            value,
+            cookie_name: config.full_cookie_name(),
+            config: config.clone(),
+            previous: request.previous_csrf_token_from_session(config),
+            scope: config
+                .route_scoping
+                .then(|| request.uri().path().to_string()),
+            method: config.method_binding.then(|| request.method()),
+            legacy: Vec::new(),
+            undecryptable_cookie: request.has_undecryptable_csrf_cookie(config),
+            fresh_session: request
+                .local_cache(IssuedToken::default)
+                .0
+                .get()
+                .is_some_and(|(cookie_name, _)| *cookie_name == config.full_cookie_name()),
+            origin: Some(RequestOrigin {
+                path: request.uri().path().to_string(),
+                client_ip: request.client_ip(),
+            }),
+            same_origin: config.same_origin_shortcut && is_same_origin(request),
+            rotation: request.local_cache(RotationRecord::default).clone(),
+        })
+    }
+
+    /// Issues a CSRF cookie for `CsrfContext` when the fairing didn't, returning the new token.
+    fn issue_for_context(request: &Request<'_>, config: &CsrfConfig) -> Option<Self> {
+        if config.has_external_session_token()
+            || (config.https_only && !is_https(request))
+            || !config.allow_issue(request)
+        {
+            return None;
+        }
+
+        let encoded = generate_session_token(config);
+        request
+            .local_cache(IssuedToken::default)
+            .0
+            .set((config.full_cookie_name(), encoded.clone()))
+            .ok()?;
+        config.add_cookie(request.cookies(), config.session_cookie(encoded));
+
+        Self::from_session(request, config)
+    }
+
+    /// Checks whether the CSRF cookie was issued by the fairing during the given request.
+    /// # Arguments
+    /// * `request` - The current request.
+    ///
+    /// This is the case on a client's first request, or once its CSRF cookie expired. Handlers
+    /// can use it e.g. to decide whether to show an onboarding hint.
+    ///
+    /// # Returns
+    /// (`bool`): `true` if the token was minted by this request, `false` if it came with the request.
+    pub fn was_freshly_issued(request: &Request<'_>) -> bool {
+        request.local_cache(IssuedToken::default).0.get().is_some()
+    }
+
+    /// Returns the name of the cookie holding the session's CSRF token.
+    ///
+    /// This is the cookie name configured through `CsrfConfig::with_cookie_name`, so handlers and
+    /// templates can tell clients (e.g. SPAs) which cookie is active without access to the config.
+    pub fn cookie_name(&self) -> &str {
+        &self.cookie_name
+    }
+
+    /// Captures the session token along with the settings verification depends on, so that a
+    /// background worker can check authenticity tokens after the request has completed.
+    ///
+    /// Only bcrypt authenticity tokens can be verified from a snapshot: server secrets, legacy
+    /// configurations and callbacks such as the nonce store are not captured.
+    ///
+    /// # Returns
+    /// (`Option<CsrfSnapshot>`): The state of the token, holding the session token itself, or
+    /// None if the configuration mints tokens under another `TokenStrategy` than
+    /// `TokenStrategy::Bcrypt`, which the snapshot couldn't verify.
+    pub fn snapshot(&self) -> Option<CsrfSnapshot> {
+        if !self
+            .config
+            .strategies
+            .iter()
+            .all(|strategy| matches!(strategy, TokenStrategy::Bcrypt))
+        {
+            return None;
+        }
+
+        Some(CsrfSnapshot {
+            value: self.value.clone(),
+            previous: self.previous.clone(),
+            cookie_name: self.cookie_name.to_string(),
+            scope: self.scope.clone(),
+            method: self.method.map(|method| method.as_str().to_string()),
+            token_len: self.config.token_len(),
+            prehash: self.config.prehash,
+            token_checksum: self.config.token_checksum,
+            lenient_whitespace: self.config.lenient_whitespace,
+            strict_length: self.config.strict_length,
+            max_verify_attempts: self.config.max_verify_attempts,
+        })
+    }
+
+    /// Reconstructs a CsrfToken from a snapshot taken by `CsrfToken::snapshot`.
+    /// # Arguments
+    /// * `snapshot` - The captured state of the token.
+    ///
+    /// # Returns
+    /// (`CsrfToken`): A token verifying authenticity tokens as the captured one did. It is not
+    /// tied to a request, so it neither rotates nor records metrics of the fairing.
+    pub fn from_snapshot(snapshot: CsrfSnapshot) -> Self {
+        let config = CsrfConfig {
+            cookie_len: Some(snapshot.token_len),
+            prehash: snapshot.prehash,
+            token_checksum: snapshot.token_checksum,
+            lenient_whitespace: snapshot.lenient_whitespace,
+            strict_length: snapshot.strict_length,
+            max_verify_attempts: snapshot.max_verify_attempts,
+            ..CsrfConfig::default()
+        };
+
+        let mut token = CsrfToken::new(snapshot.value.clone(), &config);
+        token.cookie_name = Cow::Owned(snapshot.cookie_name.clone());
+        token.previous = snapshot.previous.clone();
+        token.scope = snapshot.scope.clone();
+        token.method = snapshot
+            .method
+            .as_deref()
+            .and_then(|method| method.parse().ok());
+        token
+    }
+
+    /// Returns an identifier of the session token.
+    ///
+    /// The identifier is a digest of the session token that doesn't reveal it, so it can be
+    /// handed to clients, e.g. to match the `X-CSRF-Previous-Token-Id` header sent under
+    /// `CsrfConfig::with_rotation_headers` against the token they hold.
+    ///
+    /// # Returns
+    /// (`String`): 16 characters of unpadded URL-safe base64.
+    pub fn token_id(&self) -> String {
+        token_id(&self.value)
+    }
+
+    /// Replaces the session's CSRF token with a freshly generated one.
+    /// # Arguments
+    /// * `cookies` - The cookie jar of the current request.
+    ///
+    /// This function issues a new CSRF cookie and, when `CsrfConfig::with_rotation_grace` is set,
+    /// keeps the replaced session token in a companion cookie so that authenticity tokens minted
+    /// before the rotation keep verifying until the grace window elapses. `verify` falls back to
+    /// the previous token transparently, so handlers need no changes.
+    ///
+    /// Session tokens derived from another cookie, see `CsrfConfig::derive_from_session` and
+    /// `CsrfConfig::with_secret_from_cookie`, can't be replaced, so the jar is left untouched and
+    /// this token is returned as is.
+    ///
+    /// # Returns
+    /// (`CsrfToken`): The new token, which should be used to mint authenticity tokens from now on.
+    pub fn rotate(&self, cookies: &CookieJar<'_>) -> CsrfToken {
+        let config = &self.config;
+        if config.has_external_session_token() {
+            return self.clone();
+        }
+        let encoded = generate_session_token(config);
+        config.add_cookie(cookies, config.session_cookie(encoded.clone()));
+
+        let previous = match config.rotation_grace {
+            Some(grace) => {
+                let rotated_at = OffsetDateTime::now_utc();
+                let mut cookie = config.session_cookie(format!(
+                    "{}:{}",
+                    rotated_at.unix_timestamp(),
+                    self.value
+                ));
+                cookie.set_name(config.previous_cookie_name());
+                cookie.set_expires(rotated_at + grace);
+                config.add_cookie(cookies, cookie);
+                Some(self.value.clone())
+            }
+            None => {
+                cookies.remove_private(config.removal_cookie(config.previous_cookie_name()));
+                None
+            }
+        };
+        if config.rotation_headers {
+            let mut rotation = lock(&self.rotation.0);
+            // Over several rotations, clients still hold the token the request came with.
+            let replaced = rotation
+                .take()
+                .map_or_else(|| token_id(&self.value), |(replaced, _)| replaced);
+            *rotation = Some((replaced, token_id(&encoded)));
+        }
+
+        Self {
+            value: encoded,
+            cookie_name: config.full_cookie_name(),
+            config: config.clone(),
+            previous,
+            scope: self.scope.clone(),
+            method: self.method,
+            legacy: self.legacy.clone(),
+            undecryptable_cookie: false,
+            fresh_session: false,
+            origin: self.origin.clone(),
+            same_origin: self.same_origin,
+            rotation: self.rotation.clone(),
+        }
+    }
+
+    /// Verifies a submitted token and, on success, replaces the session's CSRF token.
+    /// # Arguments
+    /// * `form_authenticity_token` - The token to verify.
+    /// * `cookies` - The cookie jar of the current request.
+    ///
+    /// This function combines `verify` and `rotate` for single-use token flows: once the submitted
+    /// token is accepted, a fresh CSRF cookie is set on the response and the authenticity token for
+    /// the next form is returned. The submitted token stops verifying from then on, unless
+    /// `CsrfConfig::with_rotation_grace` keeps the replaced session token valid for a while.
+    ///
+    /// # Returns
+    /// (`Result<String, CsrfError>`): The authenticity token minted from the new session token, or
+    /// the `CsrfError` of the failed verification, in which case the session token is kept.
+    pub fn verify_and_renew(
+        &self,
+        form_authenticity_token: &str,
+        cookies: &CookieJar<'_>,
+    ) -> Result<String, CsrfError> {
+        self.verify(form_authenticity_token)?;
+
+        Ok(self.rotate(cookies).authenticity_token()?)
+    }
+
+    /// Generates an authenticity token using the stored CSRF token.
+    ///
+    /// This function generates an authenticity token based on the stored CSRF token. The authenticity
+    /// token is typically used in forms and requests to prevent Cross-Site Request Forgery attacks.
+    /// It provides an additional layer of security to ensure that the request is legitimate.
+    ///
+    /// # Returns
+    /// (`Result<String, BcryptError>`): The generated authenticity token or an error if token generation fails.
+    pub fn authenticity_token(&self) -> Result<String, BcryptError> {
+        self.mint(self.scope.as_deref())
+    }
+
+    /// Generates an authenticity token bound to the given scope.
+    /// # Arguments
+    /// * `scope` - The action or route the token is minted for, e.g. `"/transfer"`.
+    ///
+    /// The returned token only verifies through `verify_for` with the same scope (or through
+    /// `verify` on a route whose path is `scope` when route scoping is enabled), so a token
+    /// minted for one action can't be replayed against another.
+    ///
+    /// # Returns
+    /// (`Result<String, BcryptError>`): The generated authenticity token or an error if token generation fails.
+    pub fn authenticity_token_for(&self, scope: &str) -> Result<String, BcryptError> {
+        self.mint(Some(scope))
+    }
+
+    /// Generates an authenticity token bound to the given HTTP method under method binding.
+    /// # Arguments
+    /// * `method` - The method of the request the token is minted for, e.g. `Method::Delete`.
+    ///
+    /// This lets a page rendered through a GET request embed a token for the form it submits,
+    /// which only verifies through requests of `method` when `CsrfConfig::with_method_binding` is
+    /// enabled. Otherwise, the method is ignored and this is the same as `authenticity_token`.
+    ///
+    /// # Returns
+    /// (`Result<String, BcryptError>`): The generated authenticity token or an error if token generation fails.
+    pub fn authenticity_token_for_method(&self, method: Method) -> Result<String, BcryptError> {
+        let method = self.config.method_binding.then_some(method);
+        let scope = bind_method(self.scope.as_deref(), method);
+
+        self.config.mint(&self.value, scope.as_deref())
+    }
+
+    /// Generates a token for authenticating individual WebSocket messages.
+    ///
+    /// Message tokens are bound to a dedicated scope, so they can't be used as form authenticity
+    /// tokens and vice versa. Verify them with `verify_message_token`.
+    ///
+    /// # Returns
+    /// (`Result<String, BcryptError>`): The generated message token or an error if token generation fails.
+    pub fn message_token(&self) -> Result<String, BcryptError> {
+        self.authenticity_token_for(MESSAGE_SCOPE)
+    }
+
+    /// Generates a short-lived token authorizing a sensitive operation.
+    ///
+    /// Call this only after the user re-authenticated. The token embeds the time it was minted
+    /// at and only verifies through `verify_step_up` during the window set with
+    /// `CsrfConfig::with_step_up`. Fails when step-up tokens are not enabled.
+    ///
+    /// # Returns
+    /// (`Result<String, BcryptError>`): The generated step-up token or an error if token generation fails.
+    pub fn step_up_token(&self) -> Result<String, BcryptError> {
+        if self.config.step_up.is_none() {
+            return Err(mint_unsupported(
+                "step-up tokens require `CsrfConfig::with_step_up`",
+            ));
+        }
+
+        let minted_at = OffsetDateTime::now_utc().unix_timestamp();
+        let token = self.mint(Some(&step_up_scope(minted_at)))?;
+
+        Ok(format!("{}.{}", minted_at, token))
+    }
+
+    /// Verifies a token minted by `step_up_token`.
+    /// # Arguments
+    /// * `step_up_token` - The token to verify.
+    ///
+    /// Regular authenticity tokens never pass this check. A step-up token minted longer ago than
+    /// the step-up window yields `CsrfError::Expired`.
+    ///
+    /// # Returns
+    /// (`Result<(), CsrfError>`): A result indicating success if the token is valid, or a
+    /// `CsrfError` if it is not.
+    pub fn verify_step_up(&self, step_up_token: &str) -> Result<(), CsrfError> {
+        if step_up_token.is_empty() {
+            return Err(CsrfError::Missing);
+        }
+        let window = self.config.step_up.ok_or(CsrfError::Mismatch)?;
+        let (minted_at, token) = step_up_token
+            .split_once('.')
+            .and_then(|(minted_at, token)| Some((minted_at.parse::<i64>().ok()?, token)))
+            .ok_or(CsrfError::Malformed)?;
+
+        let age = OffsetDateTime::now_utc().unix_timestamp() - minted_at;
+        if !(0..=window.whole_seconds()).contains(&age) {
+            return Err(CsrfError::Expired);
+        }
+
+        self.verify_counted(token, Some(&step_up_scope(minted_at)), None)
+    }
+
+    /// Carries a freshly minted authenticity token through a Post/Redirect/Get cycle.
+    /// # Arguments
+    /// * `responder` - The response to attach the token to, typically a `Redirect`.
+    ///
+    /// The token is stored in Rocket's flash cookie, so the redirected GET can embed it with
+    /// `flashed_authenticity_token` without a separate fetch. As Rocket keeps a single flash
+    /// message, this replaces any other flash set on the same response.
+    ///
+    /// # Returns
+    /// (`Result<Flash<R>, BcryptError>`): The flashing responder or an error if token generation fails.
+    pub fn flash<R>(&self, responder: R) -> Result<Flash<R>, BcryptError> {
+        Ok(Flash::new(
+            responder,
+            FLASH_KIND,
+            self.authenticity_token()?,
+        ))
+    }
+
+    /// Verifies if a provided token matches the stored CSRF token.
+    /// # Arguments
+    /// * `form_authenticity_token` - The token to verify.
+    ///
+    /// This function verifies if the provided token matches the stored CSRF token. It is commonly
+    /// used to validate the authenticity of incoming requests. If the provided token matches the
+    /// stored CSRF token, this function returns `Ok(())`. Otherwise, it returns a `CsrfError` describing
+    /// why: `Missing` for an empty token, `Malformed` for a token that fails `is_well_formed`, and
+    /// `Mismatch` for a well-formed token that does not match.
+    ///
+    /// When route scoping is enabled, the token must have been minted for the path of the current
+    /// request. Tokens are compared in constant time, see `constant_time_eq`.
+    ///
+    /// # Returns
+    /// (`Result<(), CsrfError>`): A result indicating success if the tokens match, or a `CsrfError`
+    /// if they do not.
+    pub fn verify(&self, form_authenticity_token: &str) -> Result<(), CsrfError> {
+        self.verify_counted(form_authenticity_token, self.scope.as_deref(), None)
+    }
+
+    /// Verifies if a provided token matches the stored CSRF token and was minted for the given scope.
+    /// # Arguments
+    /// * `form_authenticity_token` - The token to verify.
+    /// * `scope` - The action or route the token must have been minted for.
+    ///
+    /// # Returns
+    /// (`Result<(), CsrfError>`): A result indicating success if the tokens match, or a `CsrfError`
+    /// if they do not.
+    pub fn verify_for(&self, form_authenticity_token: &str, scope: &str) -> Result<(), CsrfError> {
+        self.verify_counted(form_authenticity_token, Some(scope), None)
+    }
+
+    /// Verifies several authenticity tokens against the session, e.g. those of a batch operation
+    /// submitted from a dashboard.
+    /// # Arguments
+    /// * `tokens` - The tokens to verify.
+    ///
+    /// The session is read once, when the `CsrfToken` guard is created, and every token is checked
+    /// against it as by `verify`, so one invalid token doesn't hide the outcome of the others.
+    ///
+    /// # Returns
+    /// (`Vec<Result<(), CsrfError>>`): The result of each token, at the index of the token.
+    pub fn verify_batch(&self, tokens: &[String]) -> Vec<Result<(), CsrfError>> {
+        tokens.iter().map(|token| self.verify(token)).collect()
+    }
+
+    /// Verifies a `TokenStrategy::Claims` token and returns the scope embedded in its claims.
+    /// # Arguments
+    /// * `form_authenticity_token` - The token to verify.
+    ///
+    /// This lets handlers authorize the action against the scope the token was minted for, e.g.
+    /// through `authenticity_token_for`, without parsing the token themselves. The token is
+    /// verified as by `verify_for` with the embedded scope, so a token whose scope was tampered
+    /// with fails verification. Tokens that don't embed a scope yield `CsrfError::Malformed`.
+    ///
+    /// # Returns
+    /// (`Result<String, CsrfError>`): The embedded scope if the token is valid, or a `CsrfError`
+    /// if it is not.
+    #[cfg(feature = "json")]
+    pub fn verify_and_scope(&self, form_authenticity_token: &str) -> Result<String, CsrfError> {
+        if form_authenticity_token.is_empty() {
+            return Err(CsrfError::Missing);
+        }
+        let scope = self
+            .embedded_scope(form_authenticity_token)
+            .ok_or(CsrfError::Malformed)?;
+        self.verify_counted(form_authenticity_token, Some(&scope), None)?;
+
+        Ok(scope)
+    }
+
+    /// Reads the scope from the claims of a submitted token, without verifying it, and strips
+    /// the HTTP method bound to it under method binding.
+    #[cfg(feature = "json")]
+    fn embedded_scope(&self, submitted: &str) -> Option<String> {
+        let submitted = self.config.clean(submitted);
+        let submitted = if self.config.token_checksum {
+            strip_checksum(&submitted)?
+        } else {
+            &submitted
+        };
+
+        let (claims, _) = submitted.split_once('.')?;
+        let claims = general_purpose::URL_SAFE_NO_PAD.decode(claims).ok()?;
+        let claims: serde_json::Value = serde_json::from_slice(&claims).ok()?;
+        let scope = claims["scope"].as_str()?;
+        match self.method {
+            Some(method) => scope
+                .strip_prefix(method.as_str())?
+                .strip_prefix(' ')
+                .map(String::from),
+            None => Some(scope.to_string()),
+        }
+    }
+
+    /// Verifies the authenticity token carried by a parsed JSON body.
+    /// # Arguments
+    /// * `body` - The parsed JSON request body.
+    /// * `param` - The name of the field holding the token, usually `CsrfConfig::param_name`.
+    ///
+    /// The field may hold the token as a string or, as some frontends serialize form arrays,
+    /// as a single-element array of strings. An absent field yields `CsrfError::Missing`;
+    /// a multi-element array is ambiguous and, like any other value, yields `CsrfError::Malformed`.
+    ///
+    /// # Returns
+    /// (`Result<(), CsrfError>`): A result indicating success if the tokens match, or a `CsrfError`
+    /// if they do not.
+    #[cfg(feature = "json")]
+    pub fn verify_json(&self, body: &serde_json::Value, param: &str) -> Result<(), CsrfError> {
+        self.verify(json_token(body, param)?)
+    }
+
+    /// Verifies the authenticity token carried by a request and reports where it was found.
+    /// # Arguments
+    /// * `request` - The request carrying the token.
+    ///
+    /// The sources are tried in the order of the `TokenSource` variants: the headers (see
+    /// `csrf_header_tokens`), a form body, the query string when `CsrfConfig::with_query_tokens` is
+    /// enabled, and a JSON body. Tokens in bodies are read by the fairing from their first 512
+    /// bytes, and JSON bodies require the `json` feature. The first source holding a valid token
+    /// wins, which lets logging and metrics attribute verifications in mixed-client deployments.
+    /// Under `CsrfConfig::with_same_origin_shortcut`, same-origin requests verify as
+    /// `TokenSource::FetchMetadata` without looking at any source.
+    ///
+    /// # Returns
+    /// (`Result<TokenSource, CsrfError>`): The source of the valid token, or the `CsrfError` of the
+    /// first source holding a token, `CsrfError::Missing` if there is none.
+    pub fn verify_request(&self, request: &Request<'_>) -> Result<TokenSource, CsrfError> {
+        if self.same_origin {
+            let source = TokenSource::FetchMetadata;
+            return self
+                .verify_counted("", self.scope.as_deref(), Some(source))
+                .map(|()| source);
+        }
+        let body = request.local_cache(BodySources::default);
+        let query = match request.query_value::<&str>(self.config.param_name()) {
+            Some(Ok(token)) if self.config.query_tokens => Some(token),
+            _ => None,
+        };
+        let headers = csrf_header_tokens(request, &self.config)
+            .into_iter()
+            .map(|token| (TokenSource::Header, Some(token)));
+        let submitted = headers.chain([
+            (TokenSource::Form, body.token(TokenSource::Form)),
+            (TokenSource::Query, query),
+            (TokenSource::Json, body.token(TokenSource::Json)),
+        ]);
+
+        let mut first_error = None;
+        for (source, token) in submitted {
+            let Some(token) = token.filter(|token| !token.is_empty()) else {
+                continue;
+            };
+            match self.verify_counted(token, self.scope.as_deref(), Some(source)) {
+                Ok(()) => return Ok(source),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        Err(first_error.unwrap_or(CsrfError::Missing))
+    }
+
+    /// Verifies the authenticity token of a contextual form, reporting failures as a field error.
+    /// # Arguments
+    /// * `form` - The form parsed through `Form<Contextual<T>>`.
+    ///
+    /// Rocket's field validators can't reach the session, so instead of a `#[field(validate)]`
+    /// attribute, handlers call this function to let the `authenticity_token` field (or the one
+    /// set with `CsrfConfig::with_form_field`) take part in form validation. An invalid token pushes a validation error for that field onto the form
+    /// context, whose status becomes 422 Unprocessable Entity, and clears `form.value`, so the
+    /// form can be re-rendered with its errors rather than answered with a blanket 403:
+    ///
+    /// ```rust
+    /// # use rocket::{form::{Contextual, Form}, post, FromForm};
+    /// # use rocket_csrf_token::CsrfToken;
+    /// #[derive(FromForm)]
+    /// struct Comment<'r> {
+    ///     authenticity_token: &'r str,
+    ///     body: &'r str,
+    /// }
+    ///
+    /// #[post("/comments", data = "<form>")]
+    /// fn create(csrf_token: CsrfToken, mut form: Form<Contextual<'_, Comment<'_>>>) -> String {
+    ///     let _ = csrf_token.validate_form(&mut form);
+    ///     match &form.value {
+    ///         Some(comment) => format!("Created: {}", comment.body),
+    ///         None => format!("{:?}", form.context.field_errors("authenticity_token").count()),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    /// (`Result<(), CsrfError>`): A result indicating success if the token is valid, or the
+    /// `CsrfError` that was pushed onto the form context.
+    pub fn validate_form<'v, T>(&self, form: &mut Contextual<'v, T>) -> Result<(), CsrfError> {
+        let field = self.config.form_field();
+        let submitted = form
+            .context
+            .field_value(field)
+            .or_else(|| form.context.field_value(self.config.param_name()))
+            .unwrap_or_default();
+        let result = self.verify(submitted);
+
+        if let Err(err) = &result {
+            form.context
+                .push_error(form::Error::validation(err.to_string()).with_name(field.to_string()));
+            form.value = None;
+        }
+        result
+    }
+
+    /// Mints an authenticity token under an optional scope, bound to the method of the request.
+    fn mint(&self, scope: Option<&str>) -> Result<String, BcryptError> {
+        let scope = bind_method(scope, self.method);
+        self.config.mint(&self.value, scope.as_deref())
+    }
+
+    /// Verifies the submitted token under an optional scope.
+    fn verify_scoped(
+        &self,
+        form_authenticity_token: &str,
+        scope: Option<&str>,
+    ) -> Result<(), CsrfError> {
+        let scope = bind_method(scope, self.method);
+        let scope = scope.as_deref();
+        let form_authenticity_token = &*self.config.clean(form_authenticity_token);
+        let mut candidates = Vec::new();
+        self.collect_candidates(&mut candidates, &self.config.migration_set);
+
+        // Reject junk input before paying for the bcrypt verification.
+        if form_authenticity_token.is_empty() {
+            return Err(CsrfError::Missing);
+        }
+        if self.config.active_test_token().is_some_and(|token| {
+            constant_time_eq(token.as_bytes(), form_authenticity_token.as_bytes()).into()
+        }) {
+            warn!("Accepting the pre-shared CSRF test token.");
+            return Ok(());
+        }
+        let form_authenticity_token = if self.config.token_checksum {
+            strip_checksum(form_authenticity_token).ok_or(CsrfError::Malformed)?
+        } else {
+            form_authenticity_token
+        };
+        if !candidates
+            .iter()
+            .any(|(_, strategy, _)| strategy.is_well_formed(form_authenticity_token))
+        {
+            return Err(CsrfError::Malformed);
+        }
+        if self.config.strict_length && self.secret_len() != self.config.token_len() {
+            return Err(CsrfError::LengthMismatch);
+        }
+
+        if self.matches(&candidates, form_authenticity_token, scope) {
+            if self.config.single_use && !self.consume(form_authenticity_token) {
+                return Err(CsrfError::Replayed);
+            }
+            // CSRF token verification succeeded.
+            info!("CSRF token verification succeeded.");
+            Ok(())
+        } else if candidates
+            .iter()
+            .any(|(value, strategy, _)| strategy.is_expired(value, form_authenticity_token))
+        {
+            Err(CsrfError::Expired)
+        } else if self.undecryptable_cookie {
+            // The token was likely minted from the cookie the current key can't decrypt.
+            Err(CsrfError::SecretKeyError)
+        } else if self.fresh_session
+            && self.legacy.is_empty()
+            && candidates
+                .iter()
+                .all(|(_, strategy, _)| strategy.is_session_bound())
+        {
+            // No token can match a session minted after the client submitted it.
+            Err(CsrfError::NoSession)
+        } else {
+            Err(CsrfError::Mismatch)
+        }
+    }
+
+    /// Records a verified token in the nonce store, returning `false` if it was used before.
+    fn consume(&self, form_authenticity_token: &str) -> bool {
+        let nonce = general_purpose::URL_SAFE_NO_PAD
+            .encode(Sha256::digest(form_authenticity_token.as_bytes()));
+        let ttl = self.config.lifespan.unwrap_or(Duration::days(1));
+
+        self.config.nonce_store.0.insert_if_absent(&nonce, ttl)
+    }
+
+    /// Verifies the submitted token under an optional scope, updating the metrics counters and
+    /// recording the outcome in the audit sink.
+    fn verify_counted(
+        &self,
+        form_authenticity_token: &str,
+        scope: Option<&str>,
+        source: Option<TokenSource>,
+    ) -> Result<(), CsrfError> {
+        let started = self.config.latency_recording.then(Instant::now);
+        // Only `verify_request` vouches for same-origin requests, explicit checks always verify.
+        let result = if source == Some(TokenSource::FetchMetadata) {
+            info!("Skipping CSRF token verification for a same-origin request.");
+            Ok(())
+        } else {
+            self.verify_scoped(form_authenticity_token, scope)
+        };
+        if let Some(started) = started {
+            self.config.metrics.record_latency(started.elapsed());
+        }
+        let counter = match result {
+            Ok(()) => &self.config.metrics.verified,
+            Err(_) => &self.config.metrics.rejected,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        self.config.audit_sink.0.record(&AuditEntry {
+            timestamp: OffsetDateTime::now_utc(),
+            path: self.origin.as_ref().map(|origin| origin.path.clone()),
+            source,
+            client_ip: self.origin.as_ref().and_then(|origin| origin.client_ip),
+            outcome: result.clone(),
+        });
+
+        result
+    }
+
+    /// Returns the length in bytes of the session token.
+    fn secret_len(&self) -> usize {
+        general_purpose::STANDARD
+            .decode(&self.value)
+            .map_or(0, |secret| {
+                let len = secret.len();
+                wipe(secret);
+                len
+            })
+    }
+
+    /// Checks the submitted token against the session token, then against the token replaced by
+    /// the last rotation, then against each legacy token, giving up after
+    /// `CsrfConfig::with_max_verify_attempts` candidates.
+    fn matches(
+        &self,
+        candidates: &[(&str, &TokenStrategy, PrehashAlgo)],
+        form_authenticity_token: &str,
+        scope: Option<&str>,
+    ) -> bool {
+        candidates
+            .iter()
+            .filter(|(_, strategy, _)| strategy.is_well_formed(form_authenticity_token))
+            .take(self.config.max_verify_attempts)
+            .any(|(value, strategy, prehash)| {
+                strategy.check(value, form_authenticity_token, scope, *prehash)
+            })
+    }
+
+    /// Collects the session tokens a submitted token may be verified against, in order, along
+    /// with each strategy and the pre-hash digest of the configuration they were read under.
+    ///
+    /// Each session token is paired with the strategies of its configuration, then with those of
+    /// the migration set, see `CsrfConfig::with_migration_set`.
+    fn collect_candidates<'a>(
+        &'a self,
+        candidates: &mut Vec<(&'a str, &'a TokenStrategy, PrehashAlgo)>,
+        migration_set: &'a [TokenStrategy],
+    ) {
+        for value in [Some(&self.value), self.previous.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            for strategy in self.config.strategies.iter().chain(migration_set) {
+                candidates.push((value, strategy, self.config.prehash));
+            }
+        }
+        for legacy in &self.legacy {
+            legacy.collect_candidates(candidates, migration_set);
+        }
+    }
+
+    /// Checks whether a submitted token has the shape of an authenticity token.
+    /// # Arguments
+    /// * `submitted` - The token to inspect.
+    ///
+    /// This function performs a cheap structural check (bcrypt prefix, cost, length and charset)
+    /// of tokens minted by the default `TokenStrategy::Bcrypt`, without doing any cryptographic work. It is used by `verify` to reject junk input early,
+    /// and can be called by handlers that want to do the same.
+    ///
+    /// # Returns
+    /// (`bool`): `true` if the token looks like an authenticity token, `false` otherwise.
+    pub fn is_well_formed(submitted: &str) -> bool {
+        // A bcrypt hash looks like `$2b$08$` followed by 53 characters of bcrypt's base64 alphabet.
+        let bytes = submitted.as_bytes();
+        if bytes.len() != BCRYPT_HASH_LEN {
+            return false;
+        }
+
+        let prefix_ok = bytes[0] == b'$'
+            && bytes[1] == b'2'
+            && matches!(bytes[2], b'a' | b'b' | b'x' | b'y')
+            && bytes[3] == b'$'
+            && bytes[4].is_ascii_digit()
+            && bytes[5].is_ascii_digit()
+            && bytes[6] == b'$';
+
+        prefix_ok
+            && bytes[7..]
+                .iter()
+                .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'/'))
+    }
+}
+
+/// Reads the authenticity token flashed by `CsrfToken::flash`.
+/// # Arguments
+/// * `flash` - The flash message of the redirected request.
+///
+/// # Returns
+/// (`Option<&str>`): The flashed token, or None if the flash message carries something else.
+pub fn flashed_authenticity_token<'a>(flash: &'a FlashMessage<'_>) -> Option<&'a str> {
+    (flash.kind() == FLASH_KIND).then(|| flash.message())
+}
+
+/// Combines a token scope with the HTTP method the token is bound to, if any.
+fn bind_method(scope: Option<&str>, method: Option<Method>) -> Option<Cow<'_, str>> {
+    match (method, scope) {
+        (None, scope) => scope.map(Cow::Borrowed),
+        (Some(method), None) => Some(Cow::Borrowed(method.as_str())),
+        (Some(method), Some(scope)) => Some(Cow::Owned(format!("{} {}", method, scope))),
+    }
+}
+
+/// Computes the CRC-32 (IEEE) checksum of `bytes`.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Splits the checksum prefix off a submitted token, returning the token if the checksum matches.
+fn strip_checksum(submitted: &str) -> Option<&str> {
+    let checksum = submitted.get(..8)?;
+    let token = &submitted[8..];
+    let checksum = u32::from_str_radix(checksum, 16).ok()?;
+
+    (checksum == crc32(token.as_bytes())).then_some(token)
+}
+
+/// Derives the secret authenticity tokens are hashed from, binding it to `scope` when present.
+///
+/// The scope is folded in through SHA-256 rather than appended, since bcrypt ignores everything
+/// past its first 72 input bytes.
+fn scoped_secret<'a>(value: &'a str, scope: Option<&str>) -> Cow<'a, str> {
+    match scope {
+        Some(scope) => {
+            let digest = Sha256::new()
+                .chain_update(value)
+                .chain_update([0])
+                .chain_update(scope)
+                .finalize();
+            Cow::Owned(general_purpose::STANDARD.encode(digest))
+        }
+        None => Cow::Borrowed(value),
+    }
+}
+
+/// Derives the input of bcrypt, pre-hashing secrets longer than bcrypt's 72-byte limit.
+///
+/// bcrypt silently ignores input past 72 bytes, so the tail of a long session token (from a large
+/// `cookie_len`) would otherwise not contribute to authenticity tokens. Shorter secrets are used
+/// as they are, keeping the tokens minted from them verifiable.
+fn bcrypt_secret<'a>(value: &'a str, scope: Option<&str>, prehash: PrehashAlgo) -> Cow<'a, str> {
+    let secret = scoped_secret(value, scope);
+    if secret.len() <= BCRYPT_MAX_INPUT_LEN {
+        return secret;
+    }
+
+    match prehash {
+        PrehashAlgo::Sha256 => {
+            Cow::Owned(general_purpose::STANDARD.encode(Sha256::digest(secret.as_bytes())))
+        }
+        PrehashAlgo::Sha512 => {
+            Cow::Owned(general_purpose::STANDARD.encode(Sha512::digest(secret.as_bytes())))
+        }
+        PrehashAlgo::None => secret,
+    }
+}
+
+/// Extracts the authenticity token from the `param` field of a JSON body, accepting a string or
+/// a single-element array of strings.
+#[cfg(feature = "json")]
+fn json_token<'a>(body: &'a serde_json::Value, param: &str) -> Result<&'a str, CsrfError> {
+    use serde_json::Value;
+
+    match body.get(param) {
+        None | Some(Value::Null) => Err(CsrfError::Missing),
+        Some(Value::String(token)) => Ok(token),
+        Some(Value::Array(values)) => match values.as_slice() {
+            [] => Err(CsrfError::Missing),
+            [Value::String(token)] => Ok(token),
+            _ => Err(CsrfError::Malformed),
+        },
+        Some(_) => Err(CsrfError::Malformed),
+    }
+}
+
+/// Generates a random session token of `cookie_len` bytes, applies the configured transform and
+/// encodes it in base64.
+fn generate_session_token(config: &CsrfConfig) -> String {
+    let mut values: Vec<u8> = rand::thread_rng()
+        .sample_iter(Standard)
+        .take(config.token_len())
+        .collect();
+    if let Some(TokenTransformer(transform)) = &config.token_transform {
+        transform(&mut values);
+    }
+    let encoded = general_purpose::STANDARD.encode(&values[..]);
+    wipe(values);
+
+    encoded
+}
+
+/// Clears sensitive token bytes from memory when the `zeroize` feature is enabled, and simply
+/// drops them otherwise.
+fn wipe(bytes: Vec<u8>) {
+    #[cfg(feature = "zeroize")]
+    {
+        let mut bytes = bytes;
+        bytes.zeroize();
+    }
+    #[cfg(not(feature = "zeroize"))]
+    drop(bytes);
+}
+
+#[async_trait]
+impl RocketFairing for Fairing {
+    /// Get information about the CSRF protection fairing, including its name and kind.
+    ///
+    /// The response hook is only registered when meta tag injection or issuance on successful
+    /// responses is enabled, so Rocket skips it entirely otherwise.
+    ///
+    /// # Returns
+    /// (`Info`): Information about the CSRF protection fairing.
+    fn info(&self) -> Info {
+        Info {
+            name: "CSRF",
+            kind: response_kind(&self.config, Kind::Ignite | Kind::Request),
+        }
+    }
+
+    /// Initialize the CSRF protection fairing when the Rocket application is ignited.
+    /// # Arguments
+    /// * `rocket` - The Rocket instance to initialize the fairing with.
+    ///
+    /// This function is responsible for initializing the CSRF protection fairing when the Rocket
+    /// application is started. It ensures that the CSRF protection configuration is available for
+    /// use in the application.
+    ///
+    /// # Returns
+    /// (`Result<(), fairing::Error>`): A result indicating success or an error.
+    async fn on_ignite(&self, rocket: Rocket<rocket::Build>) -> fairing::Result {
+        if self.config.development {
+            warn!("CSRF development configuration is active; do not use it in production.");
+        }
+        if self.config.query_tokens {
+            warn!("CSRF tokens are accepted from query strings; they may leak through logs and the Referer header.");
+        }
+        if self.config.active_test_token().is_some() {
+            warn!("A pre-shared CSRF test token is accepted; CSRF protection is disabled for anyone who knows it.");
+        }
+        info!("CSRF configuration: {:?}", self.summary());
+
+        let rocket = match &self.config.token_endpoint {
+            Some(path) if Origin::parse(path).is_err() => {
+                error!("Invalid CSRF token endpoint path: {}", path);
+                return Err(rocket);
+            }
+            Some(path) => rocket.mount(
+                path.as_str(),
+                vec![Route::new(Method::Get, "/", TokenEndpoint)],
+            ),
+            None => rocket,
+        };
+
+        let rocket = if self.config.reject_invalid {
+            let mut methods = self.config.protected_methods.clone();
+            if !self.config.protected_get_paths.is_empty() && !methods.contains(&Method::Get) {
+                methods.push(Method::Get);
+            }
+            let gates = methods
+                .into_iter()
+                .map(|method| Route::ranked(isize::MIN, method, "/<_..>", RejectionGate))
+                .collect::<Vec<_>>();
+            rocket.mount("/", gates)
+        } else {
+            rocket
+        };
+
+        Ok(rocket.manage(self.config.clone()))
+    }
+
+    /// Handle incoming requests and add CSRF cookies when necessary.
+    /// # Arguments
+    /// * `request` - The incoming request to handle.
+    /// * `_data` - Data associated with the request.
+    ///
+    /// This function is responsible for handling incoming requests and adding CSRF cookies when necessary.
+    /// It ensures that a valid CSRF token is available for each request to prevent Cross-Site Request Forgery attacks.
+    ///
+    /// # Examples
+    /// ```
+    /// // Handling incoming requests and adding CSRF cookies
+    /// ```
+    async fn on_request(&self, request: &mut Request<'_>, data: &mut Data<'_>) {
+        let config = match request.guard::<&State<CsrfConfig>>().await {
+            Outcome::Success(cfg) => cfg,
+            Outcome::Error(e) => {
+                // Log an error for the missing CSRF config.
+                error!("CSRF config is missing: {:?}", e);
+                return;
+            }
+            Outcome::Forward(_) => {
+                // Log an error for the forward case.
+                error!("Request should be forwarded");
+                return;
+            }
+        };
+
+        let body_sources = detect_body_sources(request, config, data).await;
+        request.local_cache(|| BodySources(body_sources));
+
+        if config.is_logout_path(request) {
+            remove_csrf_cookies(request, config);
+        } else {
+            self.issue_if_needed(request, config);
+        }
+
+        if !config.manual_only {
+            let _ = CsrfToken::new(String::new(), config)
+                .on_request(request, data)
+                .await;
+        }
+    }
+
+    /// Set the CSRF cookie on successful responses and inject CSRF meta tags into HTML responses,
+    /// when enabled.
+    /// # Arguments
+    /// * `request` - The request being responded to.
+    /// * `response` - The response to rewrite.
+    ///
+    /// Under `CsrfConfig::with_issue_on_success_only`, this function removes the cookie issued
+    /// during this request from the response unless its status is 2xx or 3xx.
+    ///
+    /// On the path set with `CsrfConfig::with_logout_path`, it replaces any CSRF cookie set by
+    /// the handler with the removal of the cookie.
+    ///
+    /// Under `CsrfConfig::with_cdn_safe_token`, it adds an authenticity token to the
+    /// `X-CSRF-Token` response header.
+    ///
+    /// Under `CsrfConfig::with_rotation_headers`, it adds the identifiers of the replaced and new
+    /// session tokens to the response when the handler rotated the session token.
+    ///
+    /// It also splices `<meta name="csrf-token">` and `<meta name="csrf-param">` tags
+    /// before the closing `</head>` of HTML responses to GET requests, using an authenticity token
+    /// minted from the session's CSRF token (including one issued during this request).
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if self.config.is_logout_path(request) {
+            enforce_cookie_removal(request, response, &self.config);
+            return;
+        }
+
+        let mut withheld = false;
+        if self.config.issue_on_success_only && CsrfToken::was_freshly_issued(request) {
+            match response.status().class() {
+                StatusClass::Success | StatusClass::Redirection => {
+                    self.notify_issue(request, &self.config)
+                }
+                _ => {
+                    strip_cookie(response, &self.config.full_cookie_name());
+                    *lock(&request.local_cache(IssuedCookieRecord::default).0) = None;
+                    withheld = true;
+                }
+            }
+        }
+
+        if self.config.same_site_none_fallback
+            && self.config.same_site == SameSite::None
+            && request
+                .headers()
+                .get_one("User-Agent")
+                .is_some_and(rejects_same_site_none)
+        {
+            omit_same_site(response, &self.config);
+        }
+
+        if self.config.cdn_safe_token && !withheld {
+            match CsrfToken::from_session(request, &self.config)
+                .map(|token| token.authenticity_token())
+            {
+                Some(Ok(token)) => {
+                    response.set_raw_header(self.config.header_name.clone(), token);
+                }
+                Some(Err(err)) => {
+                    error!("Failed to mint CSRF token for the token header: {:?}", err)
+                }
+                None => {}
+            }
+        }
+
+        if self.config.rotation_headers {
+            announce_rotation(request, response);
+        }
+
+        if self.config.meta_tag_injection {
+            splice_meta_tags(request, response, &self.config).await;
+        }
+    }
+}
+
+/// Splices the CSRF meta tags into the HTML response to a GET request, see
+/// `CsrfConfig::with_meta_tag_injection`.
+///
+/// Documents that already carry the tags are left as is, so the tags are spliced once when both
+/// `Fairing` and the `CsrfToken` fairing are attached.
+async fn splice_meta_tags<'r>(
+    request: &'r Request<'_>,
+    response: &mut Response<'r>,
+    config: &CsrfConfig,
+) {
+    if request.method() != Method::Get {
+        return;
+    }
+    if !response.content_type().is_some_and(|ct| ct.is_html()) {
+        return;
+    }
+
+    let Some(csrf_token) = CsrfToken::from_session(request, config) else {
+        return;
+    };
+
+    let bytes = match response.body_mut().to_bytes().await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            error!("Failed to read HTML response for CSRF meta tags: {:?}", err);
+            return;
+        }
+    };
+    // Documents in other encodings are sent back untouched.
+    let body = match String::from_utf8(bytes) {
+        Ok(body) => body,
+        Err(err) => {
+            let bytes = err.into_bytes();
+            response.set_sized_body(bytes.len(), Cursor::new(bytes));
+            return;
+        }
+    };
+
+    let spliced = format!(r#"<meta name="{}""#, TOKEN_META_NAME);
+    let body = if body.contains(&spliced) {
+        body
+    } else {
+        match csrf_token.authenticity_token() {
+            Ok(token) => inject_meta_tags(body, config.param_name(), &token),
+            Err(err) => {
+                error!("Failed to mint CSRF token for meta tags: {:?}", err);
+                body
+            }
+        }
+    };
+    response.set_sized_body(body.len(), Cursor::new(body));
+}
+
+/// The body sources found to carry a token by the fairing, as reported by `csrf_detected_sources`,
+/// along with the token when it could be read in full.
+#[derive(Default)]
+struct BodySources(Vec<(TokenSource, Option<String>)>);
+
+impl BodySources {
+    /// Checks whether the body carries a token in `source`.
+    fn contains(&self, source: TokenSource) -> bool {
+        self.0.iter().any(|(found, _)| *found == source)
+    }
+
+    /// Returns the token read from `source`, if any.
+    fn token(&self, source: TokenSource) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(found, _)| *found == source)
+            .and_then(|(_, token)| token.as_deref())
+    }
+}
+
+/// Peeks at the start of a form or JSON body to find whether it carries an authenticity token.
+///
+/// Bodies are treated as forms according to `CsrfConfig::with_form_content_types`. The token
+/// itself is kept when it is not cut off by the peek limit.
+async fn detect_body_sources(
+    request: &Request<'_>,
+    config: &CsrfConfig,
+    data: &mut Data<'_>,
+) -> Vec<(TokenSource, Option<String>)> {
+    let content_type = match request.content_type() {
+        Some(content_type) => content_type,
+        None => return Vec::new(),
+    };
+    let source = if config.is_form_content_type(content_type) {
+        TokenSource::Form
+    } else if content_type.is_json() {
+        TokenSource::Json
+    } else {
+        return Vec::new();
+    };
+
+    let peeked = String::from_utf8_lossy(data.peek(BODY_PEEK_LIMIT).await).into_owned();
+    let complete = data.peek_complete();
+    let found = match source {
+        TokenSource::Form if content_type.is_form_data() => content_type
+            .param("boundary")
+            .and_then(|boundary| peeked_multipart_token(config, &peeked, boundary)),
+        TokenSource::Form => {
+            let fields: Vec<_> = Form::values(&peeked).collect();
+            fields
+                .iter()
+                .position(|field| {
+                    config.is_token_field(field.name.source()) && !field.value.is_empty()
+                })
+                .map(|i| {
+                    (complete || i + 1 < fields.len())
+                        .then(|| RawStr::new(fields[i].value).url_decode_lossy().into_owned())
+                })
+        }
+        _ if peeked.contains(&format!("\"{}\"", config.param_name())) => Some(
+            complete
+                .then(|| peeked_json_token(config, &peeked))
+                .flatten(),
+        ),
+        _ => None,
+    };
+
+    found.map(|token| vec![(source, token)]).unwrap_or_default()
+}
+
+/// Finds the authenticity token part of a peeked multipart body, returning its value unless the
+/// part may be cut off, i.e. isn't followed by another boundary.
+fn peeked_multipart_token(
+    config: &CsrfConfig,
+    peeked: &str,
+    boundary: &str,
+) -> Option<Option<String>> {
+    let parts: Vec<&str> = peeked.split(&format!("--{}", boundary)).collect();
+
+    parts.iter().enumerate().find_map(|(i, part)| {
+        let (headers, value) = part.split_once("\r\n\r\n")?;
+        let (_, name) = headers.split_once("; name=\"")?;
+        if !config.is_token_field(name.split('"').next()?) {
+            return None;
+        }
+        let value = value.strip_suffix("\r\n").unwrap_or(value);
+        let complete = i + 1 < parts.len();
+        (!value.is_empty() || !complete).then(|| complete.then(|| value.to_string()))
+    })
+}
+
+/// Reads the authenticity token of a complete JSON body.
+#[cfg(feature = "json")]
+fn peeked_json_token(config: &CsrfConfig, body: &str) -> Option<String> {
+    let body = serde_json::from_str(body).ok()?;
+    json_token(&body, config.param_name())
+        .ok()
+        .map(String::from)
+}
+
+/// Reads the authenticity token of a complete JSON body, which requires the `json` feature.
+#[cfg(not(feature = "json"))]
+fn peeked_json_token(_config: &CsrfConfig, _body: &str) -> Option<String> {
+    None
+}
+
+/// A place in a request that can carry a CSRF token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TokenSource {
+    /// The `X-CSRF-Token` header (or the configured header name), or the `Authorization` header
+    /// under the configured scheme.
+    Header,
+    /// The `authenticity_token` field of a form body.
+    Form,
+    /// The `authenticity_token` query parameter.
+    Query,
+    /// The `authenticity_token` field of a JSON body.
+    Json,
+    /// The CSRF cookie holding the session token.
+    Cookie,
+    /// No token: the browser marked the request as same-origin in its `Sec-Fetch-Site` header,
+    /// see `CsrfConfig::with_same_origin_shortcut`.
+    FetchMetadata,
+}
+
+/// Extracts the authenticity token sent in the headers of a request.
+/// # Arguments
+/// * `request` - The request to inspect.
+/// * `config` - The CsrfConfig naming the accepted `Authorization` scheme.
+///
+/// The `X-CSRF-Token` header, or the one named with `CsrfConfig::with_header_name`, takes
+/// precedence. Otherwise, when `CsrfConfig::with_authorization_scheme` is set, the credentials of
+/// that scheme are read from the `Authorization` header, which may carry several comma-separated
+/// credentials or be repeated. An `X-CSRF-Token` header merged by a proxy into a comma-separated
+/// list yields its first value, see `csrf_header_tokens` for all of them.
+///
+/// # Returns
+/// (`Option<&str>`): The non-empty token, or None if the headers carry none.
+pub fn csrf_header_token<'r>(request: &'r Request<'_>, config: &CsrfConfig) -> Option<&'r str> {
+    csrf_header_tokens(request, config).first().copied()
+}
+
+/// Extracts every authenticity token candidate sent in the headers of a request.
+/// # Arguments
+/// * `request` - The request to inspect.
+/// * `config` - The CsrfConfig naming the accepted `Authorization` scheme.
+///
+/// Some proxies merge duplicate headers into a comma-separated list, e.g. `X-CSRF-Token: a, b`,
+/// so the values of the `X-CSRF-Token` header are split on commas, and verification accepts the
+/// request if any of them is valid. Only the first four values are considered, bounding the
+/// hashing work a request can cause. Without an `X-CSRF-Token` header, the credentials of the
+/// scheme set with `CsrfConfig::with_authorization_scheme` are read as in `csrf_header_token`.
+///
+/// # Returns
+/// (`Vec<&str>`): The non-empty tokens, in the order they were sent.
+pub fn csrf_header_tokens<'r>(request: &'r Request<'_>, config: &CsrfConfig) -> Vec<&'r str> {
+    if request.headers().contains(&*config.header_name) {
+        return request
+            .headers()
+            .get(&config.header_name)
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .take(MAX_HEADER_TOKENS)
+            .collect();
+    }
+
+    let Some(scheme) = config.authorization_scheme.as_deref() else {
+        return Vec::new();
+    };
+    request
+        .headers()
+        .get("Authorization")
+        .flat_map(|value| value.split(','))
+        .filter_map(|credentials| credentials.trim().split_once(' '))
+        .find(|(name, _)| name.eq_ignore_ascii_case(scheme))
+        .map(|(_, token)| token.trim())
+        .filter(|token| !token.is_empty())
+        .into_iter()
+        .collect()
+}
+
+/// Lists the sources that carry a token value in the given request, for debugging.
+/// # Arguments
+/// * `request` - The request to inspect.
+/// * `config` - The CsrfConfig naming the CSRF cookie.
+///
+/// This function reports every source holding a non-empty value, whether or not the application
+/// verifies it, which helps diagnose tokens that are sent but never verified. Form and JSON bodies
+/// are inspected by the fairing, which peeks at their first 512 bytes, so they are only reported
+/// when the fairing is attached.
+///
+/// # Returns
+/// (`Vec<TokenSource>`): The sources found, in the order of the `TokenSource` variants.
+pub fn csrf_detected_sources(request: &Request<'_>, config: &CsrfConfig) -> Vec<TokenSource> {
+    let body = request.local_cache(BodySources::default);
+    let mut sources = Vec::new();
+
+    if csrf_header_token(request, config).is_some() {
+        sources.push(TokenSource::Header);
+    }
+    if body.contains(TokenSource::Form) {
+        sources.push(TokenSource::Form);
+    }
+    if request
+        .query_value::<&str>(config.param_name())
+        .is_some_and(|value| value.is_ok_and(|value| !value.is_empty()))
+    {
+        sources.push(TokenSource::Query);
+    }
+    if body.contains(TokenSource::Json) {
+        sources.push(TokenSource::Json);
+    }
+    if config
+        .read_cookie(request.cookies(), &config.full_cookie_name())
+        .is_some()
+    {
+        sources.push(TokenSource::Cookie);
+    }
+
+    sources
+}
+
+/// The handler of the routes mounted by `CsrfConfig::with_reject_invalid`, which fail requests
+/// rejected by the `VerifyAllRequests` check and forward all others.
+#[derive(Clone)]
+struct RejectionGate;
+
+#[async_trait]
+impl Handler for RejectionGate {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> route::Outcome<'r> {
+        match request.local_cache(FairingRejection::default).0.get() {
+            Some(err) => route::Outcome::Error(rejection_status(request.rocket().state(), err)),
+            None => route::Outcome::forward(data, Status::NotFound),
+        }
+    }
+}
+
+/// The handler of the route mounted by `CsrfConfig::with_token_endpoint`.
+#[derive(Clone)]
+struct TokenEndpoint;
+
+#[async_trait]
+impl Handler for TokenEndpoint {
+    /// Respond with a fresh authenticity token for the session as JSON.
+    async fn handle<'r>(&self, request: &'r Request<'_>, _data: Data<'r>) -> route::Outcome<'r> {
+        let csrf_token = match request.guard::<CsrfToken>().await {
+            Outcome::Success(csrf_token) => csrf_token,
+            _ => return route::Outcome::Error(Status::Forbidden),
+        };
+
+        match csrf_token.authenticity_token() {
+            Ok(token) => {
+                let body = format!(
+                    r#"{{"token":"{}","param":"{}","header":"{}"}}"#,
+                    escape_json(&token),
+                    escape_json(&csrf_token.config.param_name),
+                    escape_json(&csrf_token.config.header_name)
+                );
+                let response = Response::build()
+                    .header(ContentType::JSON)
+                    .raw_header("Cache-Control", "no-store")
+                    .sized_body(body.len(), Cursor::new(body))
+                    .finalize();
+                route::Outcome::Success(response)
+            }
+            Err(err) => {
+                error!(
+                    "Failed to mint CSRF token for the token endpoint: {:?}",
+                    err
+                );
+                route::Outcome::Error(Status::InternalServerError)
+            }
+        }
+    }
+}
+
+/// Checks whether a browser marked the request as a same-origin GET, HEAD or POST request, see
+/// `CsrfConfig::with_same_origin_shortcut`.
+fn is_same_origin(request: &Request<'_>) -> bool {
+    let mut sites = request.headers().get("Sec-Fetch-Site");
+    let same_origin = sites.next() == Some("same-origin") && sites.next().is_none();
+
+    same_origin && matches!(request.method(), Method::Get | Method::Head | Method::Post)
+}
+
+/// Checks whether a request was made over HTTPS, directly or through a TLS-terminating proxy.
+fn is_https(request: &Request<'_>) -> bool {
+    let headers = request.headers();
+    let forwarded_proto = headers
+        .get_one("X-Forwarded-Proto")
+        .and_then(|protos| protos.split(',').next())
+        .is_some_and(|proto| proto.trim().eq_ignore_ascii_case("https"));
+    let forwarded = headers
+        .get("Forwarded")
+        .flat_map(|value| value.split(&[',', ';'][..]))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| name.eq_ignore_ascii_case("proto"))
+        .is_some_and(|(_, proto)| proto.trim_matches('"').eq_ignore_ascii_case("https"));
+
+    request.rocket().config().tls_enabled() || forwarded_proto || forwarded
+}
+
+/// Re-issues the private CSRF cookie of `config`, if any, as a cookie signed with the shared key.
+///
+/// The session token is kept, so authenticity tokens minted from it remain valid.
+fn migrate_private_cookie(request: &Request<'_>, config: &CsrfConfig) {
+    let cookies = request.cookies();
+    let name = config.full_cookie_name();
+    let Some(cookie) = cookies.get_private(&name) else {
+        return;
+    };
+    if let Some(value) = config.verify_extra_signature(&name, cookie.value().to_string()) {
+        info!("Migrating CSRF cookie `{}` to the shared key.", name);
+        config.add_cookie(cookies, config.session_cookie(value));
+    }
+}
+
+/// Removes the current and legacy CSRF cookies of `config` carried by the request.
+fn remove_csrf_cookies(request: &Request<'_>, config: &CsrfConfig) {
+    let cookies = request.cookies();
+    for csrf_config in config.preferred_configs() {
+        let name = csrf_config.full_cookie_name();
+        if cookies.get(&name).is_some() {
+            info!("Removing CSRF cookie `{}` on logout.", name);
+            cookies.remove_private(csrf_config.removal_cookie(name));
+        }
+    }
+}
+
+/// Replaces the CSRF cookies set on a response with the removal of those carried by the request.
+fn enforce_cookie_removal(request: &Request<'_>, response: &mut Response<'_>, config: &CsrfConfig) {
+    for csrf_config in config.preferred_configs() {
+        let name = csrf_config.full_cookie_name();
+        strip_cookie(response, &name);
+
+        if request.cookies().get(&name).is_some() {
+            let mut removal = csrf_config.removal_cookie(name);
+            removal.make_removal();
+            response.adjoin_raw_header("Set-Cookie", removal.to_string());
+        }
+    }
+    *lock(&request.local_cache(IssuedCookieRecord::default).0) = None;
+}
+
+/// Removes the current and legacy CSRF cookies of `config` other than the one of `active`.
+fn clear_stale_cookies(request: &Request<'_>, config: &CsrfConfig, active: &CsrfConfig) {
+    let cookies = request.cookies();
+    for stale in config.preferred_configs() {
+        let name = stale.full_cookie_name();
+        if name != active.full_cookie_name() && cookies.get(&name).is_some() {
+            info!("Removing stale CSRF cookie `{}`.", name);
+            cookies.remove_private(stale.removal_cookie(name));
+        }
+    }
+}
+
+/// Removes the `Set-Cookie` header of the cookie named `name` from a response.
+fn strip_cookie(response: &mut Response<'_>, name: &str) {
+    let kept: Vec<String> = response
+        .headers()
+        .get("Set-Cookie")
+        .filter(|value| Cookie::parse(*value).map_or(true, |cookie| cookie.name() != name))
+        .map(String::from)
+        .collect();
+
+    response.remove_header("Set-Cookie");
+    for value in kept {
+        response.adjoin_raw_header("Set-Cookie", value);
+    }
+}
+
+/// Removes the `SameSite` attribute from the `Set-Cookie` headers of the current and previous
+/// CSRF cookies, see `CsrfConfig::with_same_site_none_fallback`.
+fn omit_same_site(response: &mut Response<'_>, config: &CsrfConfig) {
+    let names = [
+        config.full_cookie_name().into_owned(),
+        config.previous_cookie_name(),
+    ];
+    let values: Vec<String> = response
+        .headers()
+        .get("Set-Cookie")
+        .map(|value| match Cookie::parse(value) {
+            Ok(mut cookie) if names.iter().any(|name| name == cookie.name()) => {
+                cookie.set_same_site(None);
+                cookie.to_string()
+            }
+            _ => value.to_string(),
+        })
+        .collect();
+
+    response.remove_header("Set-Cookie");
+    for value in values {
+        response.adjoin_raw_header("Set-Cookie", value);
+    }
+}
+
+/// Checks whether a browser mishandles cookies with `SameSite=None`, going by its user agent.
+///
+/// These are the clients listed at <https://www.chromium.org/updates/same-site/incompatible-clients>.
+fn rejects_same_site_none(user_agent: &str) -> bool {
+    let version = |product: &str| -> Vec<u32> {
+        user_agent
+            .split_once(product)
+            .map(|(_, rest)| {
+                rest.split(|c: char| !c.is_ascii_digit() && c != '.')
+                    .next()
+                    .unwrap_or_default()
+                    .split('.')
+                    .map_while(|part| part.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let webkit = user_agent.contains(") AppleWebKit/");
+    let chromium = user_agent.contains("Chrome/") || user_agent.contains("Chromium/");
+
+    // Safari, and every browser on iOS, drop SameSite=None cookies on iOS 12 and macOS 10.14.
+    let ios_12 = user_agent.contains("(iP")
+        && ["OS 12_", "OS 12 "]
+            .iter()
+            .any(|os| user_agent.contains(os));
+    let macos_10_14 = user_agent.contains("Mac OS X 10_14");
+    if webkit && (ios_12 || (macos_10_14 && !chromium)) {
+        return true;
+    }
+
+    let chrome = [version("Chrome/"), version("Chromium/")];
+    if chrome
+        .iter()
+        .any(|version| matches!(version.first(), Some(51..=66)))
+    {
+        return true;
+    }
+
+    let uc_browser = version("UCBrowser/");
+    !uc_browser.is_empty() && uc_browser < vec![12, 13, 2]
+}
+
+/// Adds the identifiers of the replaced and new session tokens to the response when the handler
+/// rotated the session token.
+fn announce_rotation(request: &Request<'_>, response: &mut Response<'_>) {
+    let rotation = lock(&request.local_cache(RotationRecord::default).0).clone();
+    if let Some((replaced, current)) = rotation {
+        response.set_raw_header(PREVIOUS_TOKEN_ID_HEADER_NAME, replaced);
+        response.set_raw_header(TOKEN_ID_HEADER_NAME, current);
+    }
+}
+
+/// Adds `Kind::Response` to the given fairing kind when a feature rewriting responses, e.g. meta
+/// tag injection or issuance on successful responses, is enabled.
+fn response_kind(config: &CsrfConfig, kind: Kind) -> Kind {
+    if config.meta_tag_injection
+        || config.issue_on_success_only
+        || config.logout_path.is_some()
+        || config.cdn_safe_token
+        || config.rotation_headers
+        || config.same_site_none_fallback
+    {
+        kind | Kind::Response
+    } else {
+        kind
+    }
+}
+
+/// The attributes of the CSRF cookie issued by the fairing during the current request.
+#[derive(Default)]
+struct IssuedCookieRecord(Mutex<Option<IssuedCookie>>);
+
+/// The attributes of a CSRF cookie issued by the fairing, as returned by
+/// `CsrfRequestExt::csrf_issued_cookie`.
+///
+/// The attributes include the defaults Rocket applies when adding cookies, so they match the
+/// `Set-Cookie` header sent with the response. The cookie value is not recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct IssuedCookie {
+    /// The name of the cookie.
+    pub name: String,
+    /// The `SameSite` attribute.
+    pub same_site: Option<SameSite>,
+    /// The `Secure` attribute.
+    pub secure: Option<bool>,
+    /// The `HttpOnly` attribute.
+    pub http_only: Option<bool>,
+    /// The `Path` attribute.
+    pub path: Option<String>,
+    /// The `Domain` attribute.
+    pub domain: Option<String>,
+    /// When the cookie expires, `None` for a session cookie.
+    pub expires: Option<OffsetDateTime>,
+}
+
+impl From<&Cookie<'_>> for IssuedCookie {
+    fn from(cookie: &Cookie<'_>) -> Self {
+        Self {
+            name: cookie.name().to_string(),
+            same_site: cookie.same_site(),
+            secure: cookie.secure(),
+            http_only: cookie.http_only(),
+            path: cookie.path().map(String::from),
+            domain: cookie.domain().map(String::from),
+            expires: cookie.expires_datetime(),
+        }
+    }
+}
+
+/// Marks the CSRF token issued by the fairing during the current request, keyed by cookie name.
+#[derive(Default)]
+struct IssuedToken(OnceLock<(Cow<'static, str>, String)>);
+
+/// Renders the CSRF meta tags for the given parameter name and authenticity token.
+fn csrf_meta_tags(param_name: &str, authenticity_token: &str) -> String {
+    format!(
+        r#"<meta name="{}" content="{}"><meta name="{}" content="{}">"#,
+        PARAM_META_NAME,
+        escape_html(param_name),
+        TOKEN_META_NAME,
+        escape_html(authenticity_token)
+    )
+}
+
+/// Escapes `text` for use in a JSON string.
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes `text` for use in an HTML attribute value.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Splices the CSRF meta tags before the closing `</head>` of an HTML document.
+fn inject_meta_tags(body: String, param_name: &str, authenticity_token: &str) -> String {
+    match body.to_ascii_lowercase().find("</head>") {
+        Some(index) => {
+            let mut body = body;
+            body.insert_str(index, &csrf_meta_tags(param_name, authenticity_token));
+            body
+        }
+        None => body,
+    }
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for CsrfToken {
+    type Error = ();
+
+    /// Create a CsrfToken from the request or return a Forbidden status if it's not valid.
+    /// # Arguments
+    /// * `request` - The request from which to extract the token.
+    ///
+    /// This function is responsible for creating a CsrfToken from the request or returning a Forbidden
+    /// status if the token is not valid. It ensures that the CsrfToken is available for use in the application.
+    ///
+    /// # Returns
+    /// (`Outcome<Self, Self::Error>`): An outcome indicating success with a CsrfToken or a Forbidden status on failure.
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let config = request.guard::<&State<CsrfConfig>>().await.unwrap();
+
+        match CsrfToken::from_session(request, config) {
+            Some(token) => Outcome::Success(token),
+            None => Outcome::Error((config.no_session_status, ())),
+        }
+    }
+}
+
+/// Request guard yielding a freshly minted authenticity token, ready to embed in a form or
+/// template context without calling `CsrfToken::authenticity_token` by hand.
+///
+/// The guard fails with `CsrfError::NoSession` when the session holds no CSRF token, with the
+/// status set with `CsrfConfig::with_no_session_status` (403 Forbidden by default), and with
+/// 500 Internal Server Error (`CsrfError::HashError`) when minting the token fails.
+#[derive(Debug, Clone)]
+pub struct AuthenticityToken(String);
+
+impl AuthenticityToken {
+    /// Returns the authenticity token as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the guard, returning the authenticity token.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for AuthenticityToken {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
     }
 }
 
-/// Define custom methods and functions for the `Fairing` type itself.
-/// It is like defining methods in a blueprint or abstract class.
-impl Fairing {
-    /// Creates a new CSRF protection fairing with the provided configuration.
-    /// # Arguments
-    /// * `config` - The configuration specifying how CSRF tokens should be managed.
-    ///
-    /// This function creates a new Fairing instance with the given configuration, allowing for
-    /// customization of CSRF token management in a Rocket application.
-    pub fn new(config: CsrfConfig) -> Self {
-        Self { config }
+impl fmt::Display for AuthenticityToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
-/// Structure to hold a CSRF token. This token can be used for generating authenticity tokens
-/// and verifying the authenticity of incoming requests.
-#[derive(Clone)]
-pub struct CsrfToken(String);
+#[async_trait]
+impl<'r> FromRequest<'r> for AuthenticityToken {
+    type Error = CsrfError;
 
-/// Define custom methods and functions for the `CsrfToken` type itself.
-/// Again, it is like defining methods in a blueprint or abstract class.
-impl CsrfToken {
-    /// Generates an authenticity token using the stored CSRF token.
-    ///
-    /// This function generates an authenticity token based on the stored CSRF token. The authenticity
-    /// token is typically used in forms and requests to prevent Cross-Site Request Forgery attacks.
-    /// It provides an additional layer of security to ensure that the request is legitimate.
+    /// Mint an authenticity token from the session's CSRF token.
+    /// # Arguments
+    /// * `request` - The request whose session holds the CSRF token.
     ///
     /// # Returns
-    /// (`Result<String, BcryptError>`): The generated authenticity token or an error if token generation fails.
-    pub fn authenticity_token(&self) -> Result<String, BcryptError> {
-        // Handle potential errors from the hash function.
-        match hash(&self.0, BCRYPT_COST) {
-            Ok(token) => Ok(token),
-            Err(err) => Err(err),
+    /// (`Outcome<Self, Self::Error>`): The authenticity token on success, or an error status
+    /// with the `CsrfError` on failure.
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let csrf_token = match request.guard::<CsrfToken>().await {
+            Outcome::Success(token) => token,
+            _ => return forbidden(request, CsrfError::NoSession),
+        };
+
+        match csrf_token.authenticity_token() {
+            Ok(token) => Outcome::Success(AuthenticityToken(token)),
+            Err(err) => Outcome::Error((Status::InternalServerError, err.into())),
         }
     }
+}
 
-    /// Verifies if a provided token matches the stored CSRF token.
+/// Request guard sharing one `CsrfToken` between every guard and responder of a request.
+///
+/// The token is read from the session once and kept in request-local state, so further
+/// `CsrfContext` guards in the same request get the same instance at no cost. When the session
+/// holds no CSRF token yet, one is issued as the fairing would, so the guard succeeds unless the
+/// fairing isn't attached, the session token is derived with `CsrfConfig::derive_from_session`
+/// from a missing session cookie (or extracted with `CsrfConfig::with_secret_from_cookie` from a
+/// missing SSO cookie), or `CsrfConfig::with_https_only` refuses a plain-HTTP request.
+/// It fails with 403 Forbidden (`CsrfError::Missing`) without the fairing, and with
+/// `CsrfError::NoSession` and the status set with `CsrfConfig::with_no_session_status` otherwise.
+pub struct CsrfContext<'r>(&'r CsrfToken);
+
+impl<'r> CsrfContext<'r> {
+    /// Returns the token shared by the request.
+    pub fn token(&self) -> &'r CsrfToken {
+        self.0
+    }
+}
+
+impl Deref for CsrfContext<'_> {
+    type Target = CsrfToken;
+
+    fn deref(&self) -> &CsrfToken {
+        self.0
+    }
+}
+
+/// The identifiers of the session token the request came with and of the one that replaced it,
+/// recorded by `CsrfToken::rotate` under `CsrfConfig::with_rotation_headers`.
+#[derive(Clone, Default)]
+struct RotationRecord(Arc<Mutex<Option<(String, String)>>>);
+
+/// The token shared through `CsrfContext` during the current request.
+struct SharedToken(Option<CsrfToken>);
+
+#[async_trait]
+impl<'r> FromRequest<'r> for CsrfContext<'r> {
+    type Error = CsrfError;
+
+    /// Read the shared token from request-local state, reading or issuing it on first use.
     /// # Arguments
-    /// * `form_authenticity_token` - The token to verify.
-    ///
-    /// This function verifies if the provided token matches the stored CSRF token. It is commonly
-    /// used to validate the authenticity of incoming requests. If the provided token matches the
-    /// stored CSRF token, this function returns `Ok(())`. Otherwise, it returns an error of type `VerificationFailure`.
+    /// * `request` - The request whose session holds the CSRF token.
     ///
     /// # Returns
-    /// (`Result<(), VerificationFailure>`): A result indicating success if the tokens match, or a `VerificationFailure`
-    /// error if they do not.
-    pub fn verify(&self, form_authenticity_token: &String) -> Result<(), VerificationFailure> {
-        // Use a Result to propagate potential errors from the verify function.
-        if verify(&self.0, form_authenticity_token).unwrap_or(false) {
-            // CSRF token verification succeeded.
-            info!("CSRF token verification succeeded.");
-            Ok(())
-        } else {
-            Err(VerificationFailure {})
+    /// (`Outcome<Self, Self::Error>`): The shared token on success, or an error status with
+    /// `CsrfError::Missing` without the fairing and `CsrfError::NoSession` when no token can be
+    /// had.
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(config) = request.rocket().state::<CsrfConfig>() else {
+            return forbidden(request, CsrfError::Missing);
+        };
+        let shared = request.local_cache(|| {
+            SharedToken(
+                CsrfToken::from_session(request, config)
+                    .or_else(|| CsrfToken::issue_for_context(request, config)),
+            )
+        });
+
+        match &shared.0 {
+            Some(token) => Outcome::Success(CsrfContext(token)),
+            None => forbidden(request, CsrfError::NoSession),
         }
     }
 }
 
+/// Request guard verifying the authenticity token submitted with a form, so POST handlers don't
+/// have to extract and check it themselves.
+///
+/// Request guards run before the body is parsed, so the guard relies on the fairing, which peeks
+/// at the first 512 bytes of form bodies. The token is read from the field named after
+/// `CsrfConfig::param_name` (or the one set with `CsrfConfig::with_form_field`), which must
+/// therefore come early in the form: render the hidden input first. Like
+/// `CsrfToken::verify_request`, the guard also accepts a token in the `X-CSRF-Token` header, a
+/// JSON body or, when enabled, the query string.
+///
+/// ```rust
+/// # use rocket::{post, form::Form, FromForm};
+/// use rocket_csrf_token::VerifiedCsrf;
+///
+/// #[derive(FromForm)]
+/// struct Comment<'r> {
+///     body: &'r str,
+/// }
+///
+/// #[post("/comments", data = "<form>")]
+/// fn create(_csrf: VerifiedCsrf, form: Form<Comment<'_>>) -> String {
+///     form.body.to_string()
+/// }
+/// ```
+///
+/// A missing or invalid token fails with 403 Forbidden and the `CsrfError`, as does a request
+/// without the fairing attached; `CsrfError::NoSession` uses the status set with
+/// `CsrfConfig::with_no_session_status`. The form guard still parses the whole body, token
+/// field included, so `T` may ignore or capture it.
+pub struct VerifiedCsrf {
+    token: CsrfToken,
+    source: TokenSource,
+}
+
+impl VerifiedCsrf {
+    /// Returns the session's CSRF token, e.g. to mint the token of the next form.
+    pub fn token(&self) -> &CsrfToken {
+        &self.token
+    }
+
+    /// Returns where the valid authenticity token was found.
+    pub fn source(&self) -> TokenSource {
+        self.source
+    }
+}
+
 #[async_trait]
-impl RocketFairing for Fairing {
-    /// Get information about the CSRF protection fairing, including its name and kind.
+impl<'r> FromRequest<'r> for VerifiedCsrf {
+    type Error = CsrfError;
+
+    /// Verify the authenticity token submitted with the request.
+    /// # Arguments
+    /// * `request` - The request carrying the token.
     ///
     /// # Returns
-    /// (`Info`): Information about the CSRF protection fairing.
-    fn info(&self) -> Info {
-        Info {
-            name: "CSRF",
-            kind: Kind::Ignite | Kind::Request,
+    /// (`Outcome<Self, Self::Error>`): The verified token on success, a Forbidden status with the
+    /// `CsrfError` when verification fails.
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(config) = request.rocket().state::<CsrfConfig>() else {
+            error!("VerifiedCsrf requires the CSRF fairing to be attached");
+            return forbidden(request, CsrfError::Missing);
+        };
+        let Some(token) = CsrfToken::from_session(request, config) else {
+            return forbidden(request, CsrfError::NoSession);
+        };
+
+        match token.verify_request(request) {
+            Ok(source) => Outcome::Success(VerifiedCsrf { token, source }),
+            Err(err) => forbidden(request, err),
         }
     }
+}
 
-    /// Initialize the CSRF protection fairing when the Rocket application is ignited.
-    /// # Arguments
-    /// * `rocket` - The Rocket instance to initialize the fairing with.
-    ///
-    /// This function is responsible for initializing the CSRF protection fairing when the Rocket
-    /// application is started. It ensures that the CSRF protection configuration is available for
-    /// use in the application.
-    ///
-    /// # Returns
-    /// (`Result<(), fairing::Error>`): A result indicating success or an error.
-    async fn on_ignite(&self, rocket: Rocket<rocket::Build>) -> fairing::Result {
-        Ok(rocket.manage(self.config.clone()))
+/// Verifies a per-message CSRF token carried in a WebSocket message envelope.
+/// # Arguments
+/// * `secret` - The `CsrfToken` obtained from the guard during the WebSocket handshake and kept
+///   for the lifetime of the connection.
+/// * `submitted` - The token carried by the message, as minted by `CsrfToken::message_token`.
+///
+/// The handshake alone only proves the connection was opened by the user; for long-lived
+/// connections where individual messages trigger state changes, each message can carry its own
+/// token and be checked against the connection's session secret with this function.
+///
+/// # Returns
+/// (`Result<(), CsrfError>`): A result indicating success if the token is valid for the session,
+/// or a `CsrfError` if it is not.
+pub fn verify_message_token(secret: &CsrfToken, submitted: &str) -> Result<(), CsrfError> {
+    secret.verify_for(submitted, MESSAGE_SCOPE)
+}
+
+/// Request guard for query-string submissions. It verifies the `authenticity_token` query
+/// parameter against the session's CSRF token and parses the query string into `T`.
+///
+/// State-changing GET requests are discouraged, and tokens carried in URLs leak through browser
+/// history, server logs and the `Referer` header. This guard therefore only works when enabled
+/// with `CsrfConfig::with_query_tokens(true)`; otherwise it always fails with 403 Forbidden.
+///
+/// A missing or invalid token fails with 403 Forbidden, while a query string that doesn't parse
+/// into `T` forwards with 422 Unprocessable Entity.
+#[derive(Debug)]
+pub struct CsrfQuery<T>(pub T);
+
+impl<T> CsrfQuery<T> {
+    /// Consumes the guard, returning the parsed query.
+    pub fn into_inner(self) -> T {
+        self.0
     }
+}
 
-    /// Handle incoming requests and add CSRF cookies when necessary.
+impl<T> Deref for CsrfQuery<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<'r, T: FromForm<'r>> FromRequest<'r> for CsrfQuery<T> {
+    type Error = CsrfError;
+
+    /// Verify the query-string token and parse the query into `T`.
     /// # Arguments
-    /// * `request` - The incoming request to handle.
-    /// * `_data` - Data associated with the request.
-    ///
-    /// This function is responsible for handling incoming requests and adding CSRF cookies when necessary.
-    /// It ensures that a valid CSRF token is available for each request to prevent Cross-Site Request Forgery attacks.
+    /// * `request` - The request whose query string holds the token.
     ///
-    /// # Examples
-    /// ```
-    /// // Handling incoming requests and adding CSRF cookies
-    /// ```
-    async fn on_request(&self, request: &mut Request<'_>, data: &mut Data<'_>) {
-        let config = match request.guard::<&State<CsrfConfig>>().await {
-            Outcome::Success(cfg) => cfg,
-            Outcome::Error(e) => {
-                // Log an error for the missing CSRF config.
-                error!("CSRF config is missing: {:?}", e);
-                return;
-            }
-            Outcome::Forward(_) => {
-                // Log an error for the forward case.
-                error!("Request should be forwarded");
-                return;
-            }
-        };
+    /// # Returns
+    /// (`Outcome<Self, Self::Error>`): The parsed query on success, a Forbidden status with the
+    /// `CsrfError` when verification fails.
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let config = request.guard::<&State<CsrfConfig>>().await.unwrap();
 
-        if let Some(_) = request.valid_csrf_token_from_session(&config) {
-            return;
+        if !config.query_tokens {
+            error!("CsrfQuery requires `CsrfConfig::with_query_tokens(true)`");
+            return forbidden(request, CsrfError::Missing);
         }
 
-        let values: Vec<u8> = rand::thread_rng()
-            .sample_iter(Standard)
-            .take(config.cookie_len)
-            .collect();
-
-        let encoded = general_purpose::STANDARD.encode(&values[..]);
-
-        let expires = match config.lifespan {
-            Some(duration) => Some(OffsetDateTime::now_utc() + duration),
-            None => None, // Expiration of None means a session cookie
+        let submitted = match request.query_value::<&str>(config.param_name()) {
+            Some(Ok(token)) => token,
+            _ => return forbidden(request, CsrfError::Missing),
         };
 
-        let cookie_builder = Cookie::build((config.cookie_name.clone(), encoded)).path("/");
-
-        let cookie_builder = match expires {
-            Some(expiration) => cookie_builder.expires(expiration),
-            None => cookie_builder.expires(None), // Expiration of None means duration of session
-                                                  // Reference: https://api.rocket.rs/master/rocket/http/struct.Cookie.html#method.set_expires
+        let csrf_token = match CsrfToken::from_session(request, config) {
+            Some(token) => token,
+            None => return forbidden(request, CsrfError::NoSession),
         };
 
-        let cookie = cookie_builder.build();
+        if let Err(err) = csrf_token.verify(submitted) {
+            return forbidden(request, err);
+        }
 
-        if request.cookies().add_private(cookie) == () {
-            // The cookie was added successfully.
-            info!("CSRF cookie added successfully.");
-        } else {
-            // Handle the case where adding the CSRF cookie fails.
-            // Log an error.
-            error!("Failed to add CSRF cookie");
+        match Form::<T>::parse_iter(request.query_fields()) {
+            Ok(value) => Outcome::Success(CsrfQuery(value)),
+            Err(_) => Outcome::Forward(Status::UnprocessableEntity),
         }
-        let _ = CsrfToken("".to_string()).on_request(request, data).await;
     }
 }
 
+/// A JSON body guard for APIs, verifying the `X-CSRF-Token` header on non-idempotent requests.
+///
+/// Requests with a method outside `CsrfConfig::with_protected_methods`, by default the safe ones
+/// (GET, HEAD, OPTIONS and TRACE), are exempt, except for GET requests to paths set with
+/// `CsrfConfig::with_protected_get_paths`. Other requests must carry a valid authenticity token
+/// in the `X-CSRF-Token` header; otherwise the guard fails with 403 Forbidden before the body is
+/// read. The body is then deserialized into `T` like `Json<T>` does.
+#[cfg(feature = "json")]
+#[derive(Debug)]
+pub struct CsrfJson<T>(pub T);
+
+#[cfg(feature = "json")]
+impl<T> CsrfJson<T> {
+    /// Consumes the guard, returning the deserialized body.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> Deref for CsrfJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Error returned by the `CsrfJson` guard.
+#[cfg(feature = "json")]
+#[derive(Debug)]
+pub enum CsrfJsonError<'r> {
+    /// The CSRF token was missing or invalid.
+    Csrf(CsrfError),
+    /// The body could not be read or deserialized.
+    Json(rocket::serde::json::Error<'r>),
+}
+
+#[cfg(feature = "json")]
 #[async_trait]
-impl<'r> FromRequest<'r> for CsrfToken {
-    type Error = ();
+impl<'r, T: rocket::serde::Deserialize<'r>> FromData<'r> for CsrfJson<T> {
+    type Error = CsrfJsonError<'r>;
 
-    /// Create a CsrfToken from the request or return a Forbidden status if it's not valid.
+    /// Verify the header token of non-idempotent requests and deserialize the body into `T`.
     /// # Arguments
-    /// * `request` - The request from which to extract the token.
-    ///
-    /// This function is responsible for creating a CsrfToken from the request or returning a Forbidden
-    /// status if the token is not valid. It ensures that the CsrfToken is available for use in the application.
+    /// * `request` - The request whose header holds the token.
+    /// * `data` - The JSON body.
     ///
     /// # Returns
-    /// (`Outcome<Self, Self::Error>`): An outcome indicating success with a CsrfToken or a Forbidden status on failure.
-    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        let config = request.guard::<&State<CsrfConfig>>().await.unwrap();
+    /// (`data::Outcome<'r, Self>`): The deserialized body on success, a Forbidden status with the
+    /// `CsrfError` when verification fails, or the error of `Json<T>` when the body is invalid.
+    async fn from_data(request: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        use rocket::serde::json::Json;
+
+        if request.csrf_token_required() {
+            if let Err(err) = verify_header_token(request).await {
+                record_error(request, err.clone());
+                let status = rejection_status(request.rocket().state::<CsrfConfig>(), &err);
+                return data::Outcome::Error((status, CsrfJsonError::Csrf(err)));
+            }
+        }
 
-        match request.valid_csrf_token_from_session(&config) {
-            Some(token) => {
-                let encoded = general_purpose::STANDARD.encode(token);
-                Outcome::Success(Self(encoded))
+        match Json::<T>::from_data(request, data).await {
+            data::Outcome::Success(json) => data::Outcome::Success(CsrfJson(json.into_inner())),
+            data::Outcome::Error((status, err)) => {
+                data::Outcome::Error((status, CsrfJsonError::Json(err)))
             }
-            None => Outcome::Error((Status::Forbidden, ())),
+            data::Outcome::Forward(forward) => data::Outcome::Forward(forward),
         }
     }
 }
 
-impl fmt::Display for CsrfToken {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+/// Checks whether `method` is safe, i.e. should not change state and needs no CSRF protection.
+fn is_safe_method(method: Method) -> bool {
+    matches!(
+        method,
+        Method::Get | Method::Head | Method::Options | Method::Trace
+    )
+}
+
+/// Verifies the authenticity token carried by the request headers, see `csrf_header_token`.
+#[cfg(feature = "json")]
+async fn verify_header_token(request: &Request<'_>) -> Result<(), CsrfError> {
+    let config = request.guard::<&State<CsrfConfig>>().await.unwrap();
+    let submitted = csrf_header_tokens(request, config);
+    if submitted.is_empty() {
+        return Err(CsrfError::Missing);
+    }
+
+    let csrf_token = CsrfToken::from_session(request, config).ok_or(CsrfError::NoSession)?;
+    let mut first_error = None;
+    for token in submitted {
+        match csrf_token.verify(token) {
+            Ok(()) => return Ok(()),
+            Err(err) => {
+                first_error.get_or_insert(err);
+            }
+        }
     }
+    Err(first_error.unwrap_or(CsrfError::Missing))
 }
 
-// TODO
+/// Records `err` for `CsrfRequestExt::csrf_error` and fails the guard with 403 Forbidden, or
+/// the configured status for `CsrfError::NoSession`.
+fn forbidden<S>(request: &Request<'_>, err: CsrfError) -> Outcome<S, CsrfError> {
+    record_error(request, err.clone());
+    let status = rejection_status(request.rocket().state::<CsrfConfig>(), &err);
+    Outcome::Error((status, err))
+}
 
-fn _ajax_csrf_meta_tags(request: &Request) -> String {
-    // Retrieve the CSRF token from the request headers
-    let csrf_token = request.local_cache(|| CsrfToken("".to_string())); // Modify this to get the actual token
+/// Returns the status rejecting a request with `err`, see `CsrfConfig::with_no_session_status`.
+fn rejection_status(config: Option<&CsrfConfig>, err: &CsrfError) -> Status {
+    match (config, err) {
+        (Some(config), CsrfError::NoSession) => config.no_session_status,
+        _ => Status::Forbidden,
+    }
+}
 
-    // Generate the HTML meta tags
-    format!(
-        r#"<meta name="csrf-token" content="{}">
-           <meta name="csrf-param" content="{}">"#,
-        csrf_token, _PARAM_NAME
-    )
+/// Records `err` for `CsrfRequestExt::csrf_error`.
+fn record_error(request: &Request<'_>, err: CsrfError) {
+    *lock(&request.local_cache(RecordedError::default).0) = Some(err);
+}
+
+/// The verification failure of a request rejected by the `VerifyAllRequests` check, see
+/// `CsrfConfig::with_reject_invalid`.
+#[derive(Default)]
+struct FairingRejection(OnceLock<CsrfError>);
+
+/// Locks a request-local record, recovering it from a panicked holder.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The last CSRF verification failure of the current request.
+#[derive(Default)]
+struct RecordedError(Mutex<Option<CsrfError>>);
+
+/// CSRF-related accessors on Rocket requests.
+pub trait CsrfRequestExt {
+    /// Returns why a CSRF guard rejected this request, if one did.
+    ///
+    /// When a CSRF guard fails with 403 Forbidden, Rocket routes the request to the `403`
+    /// catcher, which can call this function to render a page explaining the failure:
+    ///
+    /// ```rust
+    /// use rocket::{catch, Request};
+    /// use rocket_csrf_token::CsrfRequestExt;
+    ///
+    /// #[catch(403)]
+    /// fn forbidden(request: &Request) -> String {
+    ///     match request.csrf_error() {
+    ///         Some(err) => format!("Invalid form submission: {}", err),
+    ///         None => "Forbidden".to_string(),
+    ///     }
+    /// }
+    /// ```
+    fn csrf_error(&self) -> Option<CsrfError>;
+
+    /// Returns the attributes of the CSRF cookie the fairing issued for this request, if it did.
+    ///
+    /// This allows tests and diagnostics to assert on the cookie's `SameSite`, `Secure`, `Path`,
+    /// `Domain` and expiry without parsing the `Set-Cookie` header. Nothing is recorded when the
+    /// request already carried a valid CSRF cookie, or when the cookie was withheld under
+    /// `CsrfConfig::with_issue_on_success_only`.
+    fn csrf_issued_cookie(&self) -> Option<IssuedCookie>;
+
+    /// Returns whether this request must carry a valid authenticity token.
+    ///
+    /// This is the case for requests with a method set with `CsrfConfig::with_protected_methods`,
+    /// by default anything but GET, HEAD, OPTIONS and TRACE, and for GET requests to a path set
+    /// with `CsrfConfig::with_protected_get_paths`. Without a managed `CsrfConfig`, only the safe
+    /// methods are exempt.
+    /// Custom guards can use it to apply the same exemptions as `CsrfJson`.
+    fn csrf_token_required(&self) -> bool;
 }
 
-struct _AjaxCsrfMetaTagsResponder<'o>(Response<'o>);
+impl CsrfRequestExt for Request<'_> {
+    fn csrf_error(&self) -> Option<CsrfError> {
+        lock(&self.local_cache(RecordedError::default).0).clone()
+    }
+
+    fn csrf_issued_cookie(&self) -> Option<IssuedCookie> {
+        lock(&self.local_cache(IssuedCookieRecord::default).0).clone()
+    }
 
-// impl<'r> Responder<'r, 'static> for AjaxCsrfMetaTagsResponder<'_> {
-//     fn respond_to(self, request: &Request) -> rocket::response::Result<'static> {
-//         let csrf_meta_tags = ajax_csrf_meta_tags(request);
-//         let body = format!(
-//             "<!DOCTYPE html>\n<html>\n<head>{}</head>\n<body></body>\n</html>",
-//             csrf_meta_tags
-//         );
+    fn csrf_token_required(&self) -> bool {
+        match self.rocket().state::<CsrfConfig>() {
+            Some(config) => config.requires_token(self),
+            None => !is_safe_method(self.method()),
+        }
+    }
+}
 
-//         Response::build()
-//             .header(ContentType::HTML)
-//             .sized_body(Cursor::new(body))
-//             .respond_to(request)
-//     }
-// }
+impl fmt::Display for CsrfToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
 
 #[async_trait]
 impl RocketFairing for CsrfToken {
@@ -449,7 +5786,7 @@ impl RocketFairing for CsrfToken {
     fn info(&self) -> Info {
         Info {
             name: "VerifyAllRequests",
-            kind: Kind::Request,
+            kind: response_kind(&self.config, Kind::Request),
         }
     }
 
@@ -463,22 +5800,45 @@ impl RocketFairing for CsrfToken {
     /// * `request` - A mutable reference to the incoming request.
     /// * `_data` - A mutable reference to the Rocket Data.
     async fn on_request(&self, request: &mut Request<'_>, _data: &mut Data<'_>) {
+        // Safe methods don't change state, so there is nothing to verify.
+        if !self.config.requires_token(request) {
+            return;
+        }
+        if self.config.reject_invalid {
+            let result = match CsrfToken::from_session(request, &self.config) {
+                Some(csrf_token) => csrf_token.verify_request(request).map(|_| ()),
+                None => Err(CsrfError::NoSession),
+            };
+            if let Err(err) = result {
+                warn!("Rejecting {} {}: {}", request.method(), request.uri(), err);
+                record_error(request, err.clone());
+                let _ = request.local_cache(FairingRejection::default).0.set(err);
+            }
+            return;
+        }
+
+        if self.config.same_origin_shortcut && is_same_origin(request) {
+            info!("Skipping CSRF token verification for a same-origin request.");
+            return;
+        }
+
         // Retrieve CSRF token from the request and CSRF configuration
-        let csrf_token = request.headers().get_one(HEADER_NAME).map(String::from);
+        let csrf_token = csrf_header_token(request, &self.config).map(String::from);
         let csrf_config = request.guard::<&State<CsrfConfig>>().await;
         match csrf_config {
-            Outcome::Success(_config) => {
+            Outcome::Success(config) => {
                 // CSRF config is available, continue with verification
                 if csrf_token.is_some() {
-                    match self.verify(&csrf_token.clone().unwrap()) {
+                    // Not a verification of the request, so it is neither counted nor audited.
+                    match self.verify_scoped(&csrf_token.clone().unwrap(), None) {
                         Ok(_) => {
                             // Request is valid, continue processing
                             // CsrfToken is successfully created, add it to the request's local cache
                             info!("CsrfToken is successfully created");
-                            request.local_cache(|| CsrfToken(csrf_token.unwrap()));
+                            request.local_cache(|| CsrfToken::new(csrf_token.unwrap(), config));
                         }
                         Err(err) => {
-                            // Handle the VerificationFailure error
+                            // Handle the CsrfError
                             // Log the error
                             error!("{:?}", err);
                             // TODO: Set the response status to Forbidden
@@ -488,7 +5848,7 @@ impl RocketFairing for CsrfToken {
                 } else {
                     // Handle the case where the request lacks an authenticity token
                     // Log the error or perform appropriate error handling
-                    error!("Request lacks X-CSRF-Token");
+                    error!("Request lacks {}", self.config.header_name);
 
                     // TODO: Set the response status to Forbidden
                     // return an error response to the client
@@ -510,34 +5870,112 @@ impl RocketFairing for CsrfToken {
         }
     }
 
-    async fn on_response<'r>(&self, _req: &'r Request<'_>, res: &mut Response<'r>) {
-        // Check if the response is HTML
-        if let Some(content_type) = res.content_type() {
-            if content_type.is_html() {
-                // TODO:
-                // res.set_body(AjaxCsrfMetaTagsResponder(res.take()));
-            }
+    /// Splice CSRF meta tags into HTML responses to GET requests, when enabled.
+    /// # Arguments
+    /// * `request` - The request being responded to.
+    /// * `response` - The response to rewrite.
+    ///
+    /// Under `CsrfConfig::with_meta_tag_injection`, `<meta name="csrf-token">` and
+    /// `<meta name="csrf-param">` tags are inserted before the closing `</head>`, leaving the
+    /// rest of the document as is, like the `Fairing` does. Documents already carrying the tags,
+    /// e.g. spliced by the `Fairing`, are left untouched.
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if self.config.meta_tag_injection {
+            splice_meta_tags(request, response, &self.config).await;
         }
     }
 }
 
-/// Custom error type for CSRF token verification failure. It is returned when CSRF token
-/// verification fails during request processing.
-pub struct VerificationFailure;
+/// Error type for CSRF token verification failure. It is returned when CSRF token
+/// verification fails during request processing, and tells the caller why.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum CsrfError {
+    /// No token was submitted.
+    Missing,
+    /// The submitted token does not have the shape of an authenticity token.
+    Malformed,
+    /// The submitted token is well-formed but does not match the session token.
+    Mismatch,
+    /// The session token length differs from the configured one (see `CsrfConfig::with_strict_length`).
+    LengthMismatch,
+    /// Hashing the session token failed.
+    HashError(Arc<BcryptError>),
+    /// The submitted token was valid for a limited time only, which has elapsed.
+    Expired,
+    /// The request carried a CSRF cookie that could not be decrypted, which happens when Rocket's
+    /// secret key is missing or has changed since the cookie was issued.
+    SecretKeyError,
+    /// The submitted token was already used once (see `CsrfConfig::with_single_use`).
+    Replayed,
+    /// The request came without a CSRF session, e.g. a client posting before fetching a token
+    /// (see `CsrfConfig::with_no_session_status`).
+    NoSession,
+}
+
+/// Former name of [`CsrfError`], kept so existing code keeps compiling.
+#[deprecated(note = "use `CsrfError` instead")]
+pub type VerificationFailure = CsrfError;
 
-impl fmt::Debug for VerificationFailure {
+impl fmt::Display for CsrfError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "CSRF token verification failed!")
+        match self {
+            CsrfError::Missing => write!(f, "CSRF token is missing"),
+            CsrfError::Malformed => write!(f, "CSRF token is malformed"),
+            CsrfError::Mismatch => write!(f, "CSRF token verification failed!"),
+            CsrfError::LengthMismatch => write!(f, "CSRF session token has an unexpected length"),
+            CsrfError::HashError(err) => write!(f, "CSRF token hashing failed: {}", err),
+            CsrfError::Expired => write!(f, "CSRF token has expired"),
+            CsrfError::SecretKeyError => write!(
+                f,
+                "CSRF cookie could not be decrypted, the secret key may be missing or changed"
+            ),
+            CsrfError::Replayed => write!(f, "CSRF token was already used"),
+            CsrfError::NoSession => write!(f, "CSRF session is missing, fetch a token first"),
+        }
+    }
+}
+
+impl std::error::Error for CsrfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsrfError::HashError(err) => Some(err.as_ref()),
+            _ => None,
+        }
     }
 }
 
-// Implement Responder for VerificationFailure to return a Forbidden status response
-impl<'r> Responder<'r, 'static> for VerificationFailure {
-    fn respond_to(self, _request: &Request) -> rocket::response::Result<'static> {
-        // Create a Forbidden response
-        let response = Response::build().status(Status::Forbidden).finalize();
+impl From<BcryptError> for CsrfError {
+    fn from(err: BcryptError) -> Self {
+        CsrfError::HashError(Arc::new(err))
+    }
+}
+
+// Implement Responder for CsrfError to return a Forbidden status response
+impl<'r> Responder<'r, 'static> for CsrfError {
+    fn respond_to(self, request: &Request) -> rocket::response::Result<'static> {
+        // Create a Forbidden response, or one with the configured status for missing sessions
+        let config = request.rocket().state::<CsrfConfig>();
+        let mut response = Response::build();
+        response.status(rejection_status(config, &self));
+
+        // Point clients holding a stale token to where they can fetch a fresh one.
+        if matches!(
+            self,
+            CsrfError::Mismatch
+                | CsrfError::LengthMismatch
+                | CsrfError::Expired
+                | CsrfError::SecretKeyError
+                | CsrfError::Replayed
+                | CsrfError::NoSession
+        ) {
+            let hint = config.and_then(|config| config.refresh_hint.clone());
+            if let Some(hint) = hint {
+                response.raw_header(REFRESH_HEADER_NAME, hint);
+            }
+        }
 
-        Ok(response)
+        Ok(response.finalize())
     }
 }
 
@@ -554,8 +5992,12 @@ trait RequestCsrf {
     /// (`Option<Vec<u8>>`): Some if the token is valid, None otherwise.
     fn valid_csrf_token_from_session(&self, config: &CsrfConfig) -> Option<Vec<u8>> {
         match self.csrf_token_from_session(config) {
-            Some(raw) if raw.len() >= config.cookie_len => Some(raw),
-            _ => None,
+            Some(raw) if raw.len() >= config.token_len() => Some(raw),
+            Some(raw) => {
+                wipe(raw);
+                None
+            }
+            None => None,
         }
     }
 
@@ -569,6 +6011,22 @@ trait RequestCsrf {
     /// # Returns
     /// (`Option<Vec<u8>>`): Some with the decoded token if found, None otherwise.
     fn csrf_token_from_session(&self, config: &CsrfConfig) -> Option<Vec<u8>>;
+
+    /// Retrieve the session token replaced by the last rotation, if still within its grace window.
+    /// # Arguments
+    /// * `config` - The CsrfConfig to use for retrieving the previous token.
+    ///
+    /// # Returns
+    /// (`Option<String>`): Some with the encoded previous token if it is still accepted, None otherwise.
+    fn previous_csrf_token_from_session(&self, config: &CsrfConfig) -> Option<String>;
+
+    /// Check whether the request carries a CSRF cookie that can't be decrypted.
+    /// # Arguments
+    /// * `config` - The CsrfConfig naming the CSRF cookie.
+    ///
+    /// # Returns
+    /// (`bool`): `true` if the cookie is present but fails decryption with the current secret key.
+    fn has_undecryptable_csrf_cookie(&self, config: &CsrfConfig) -> bool;
 }
 
 impl RequestCsrf for Request<'_> {
@@ -576,12 +6034,86 @@ impl RequestCsrf for Request<'_> {
     ///
     /// This function retrieves and decodes the CSRF token from the session. It ensures that the token
     /// is available for use in the application, and that it can be verified and used to generate authenticity tokens.
+    ///
+    /// A token issued by the fairing earlier in the same request is returned as well, since it is
+    /// not part of the request's cookies yet.
     fn csrf_token_from_session(&self, config: &CsrfConfig) -> Option<Vec<u8>> {
-        if let Some(cookie) = self.cookies().get_private(&config.cookie_name) {
-            if let Ok(decoded) = general_purpose::STANDARD.decode(cookie.value()) {
+        if config.derive_from_session.is_some() {
+            return config.derive_session_token(self.cookies());
+        }
+        if let Some(source) = &config.secret_from_cookie {
+            return (source.extract)(self.cookies().get(&source.cookie_name)?.value());
+        }
+
+        let cookie_name = config.full_cookie_name();
+        let value = config
+            .read_cookie(self.cookies(), &cookie_name)
+            .or_else(|| {
+                if !config.case_insensitive_cookie_lookup {
+                    return None;
+                }
+                let name = self
+                    .cookies()
+                    .iter()
+                    .find(|cookie| cookie.name().eq_ignore_ascii_case(&cookie_name))?
+                    .name()
+                    .to_string();
+                config.read_cookie(self.cookies(), &name)
+            });
+        if let Some(value) = value {
+            if let Ok(decoded) = general_purpose::STANDARD.decode(&*config.clean(&value)) {
                 return Some(decoded);
             }
         }
+        if let Some((cookie_name, encoded)) = self.local_cache(IssuedToken::default).0.get() {
+            if *cookie_name == config.full_cookie_name() {
+                return general_purpose::STANDARD.decode(encoded).ok();
+            }
+        }
         None
     }
+
+    /// Retrieve the previous session token and check that its grace window hasn't elapsed.
+    ///
+    /// The companion cookie stores the rotation time alongside the token, so the window is enforced
+    /// on the server regardless of the cookie's expiry.
+    fn previous_csrf_token_from_session(&self, config: &CsrfConfig) -> Option<String> {
+        let grace = config.rotation_grace?;
+        let value = config.read_cookie(self.cookies(), &config.previous_cookie_name())?;
+        let (rotated_at, token) = value.split_once(':')?;
+        let rotated_at = OffsetDateTime::from_unix_timestamp(rotated_at.parse().ok()?).ok()?;
+
+        if OffsetDateTime::now_utc() < rotated_at + grace {
+            Some(config.clean(token).into_owned())
+        } else {
+            None
+        }
+    }
+
+    /// Check whether the CSRF cookie is present but undecryptable.
+    ///
+    /// Private and signed cookies are authenticated, so a cookie that fails decryption was either
+    /// issued under another key or tampered with.
+    fn has_undecryptable_csrf_cookie(&self, config: &CsrfConfig) -> bool {
+        let cookie_name = config.full_cookie_name();
+        self.cookies().get(&cookie_name).is_some()
+            && config.read_cookie(self.cookies(), &cookie_name).is_none()
+    }
+}
+
+/// Aliases for code written against the 0.2.x API, easing upgrades.
+///
+/// Importing `rocket_csrf_token::compat::*` keeps older code compiling with deprecation warnings
+/// pointing at each replacement, so call sites can be migrated one at a time.
+pub mod compat {
+    use crate::CsrfError;
+
+    /// Former name of [`CsrfError`], which used to be a unit struct.
+    #[deprecated(note = "use `CsrfError` instead")]
+    pub type VerificationFailure = CsrfError;
+
+    /// The former unit value of `VerificationFailure`, returned for any failed verification.
+    #[deprecated(note = "use `CsrfError::Mismatch` or another `CsrfError` variant instead")]
+    #[allow(non_upper_case_globals)]
+    pub const VerificationFailure: CsrfError = CsrfError::Mismatch;
 }
@@ -0,0 +1,91 @@
+#[macro_use]
+extern crate rocket;
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use rand::RngCore;
+use rocket::http::{Cookie, Header};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_csrf_token::{AuditEntry, AuditSink, CsrfConfig, CsrfError, CsrfToken, TokenSource};
+
+use base64::{engine::general_purpose, Engine as _};
+
+#[derive(Default)]
+struct MockSink(Mutex<Vec<AuditEntry>>);
+
+impl AuditSink for MockSink {
+    fn record(&self, entry: &AuditEntry) {
+        self.0.lock().unwrap().push(entry.clone());
+    }
+}
+
+struct Verified(Result<TokenSource, CsrfError>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Verified {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        request
+            .guard::<CsrfToken>()
+            .await
+            .map(|csrf_token| Verified(csrf_token.verify_request(request)))
+    }
+}
+
+fn client(sink: Arc<MockSink>) -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_audit_sink(sink),
+        ))
+        .mount("/", routes![submit]);
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[post("/comments")]
+fn submit(verified: Verified) -> String {
+    format!("{:?}", verified.0)
+}
+
+fn submit_token(sink: &Arc<MockSink>, token: impl FnOnce(&str) -> String) {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let remote: SocketAddr = "192.0.2.7:4000".parse().unwrap();
+    let client = client(sink.clone());
+    client
+        .post("/comments")
+        .remote(remote)
+        .header(Header::new("X-CSRF-Token", token(&encoded)))
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .dispatch();
+}
+
+#[test]
+fn record_successful_verification() {
+    let sink = Arc::new(MockSink::default());
+    submit_token(&sink, |session| bcrypt::hash(session, 4).unwrap());
+
+    let entries = sink.0.lock().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert!(entries[0].outcome.is_ok());
+    assert_eq!(entries[0].path.as_deref(), Some("/comments"));
+    assert_eq!(entries[0].source, Some(TokenSource::Header));
+    assert_eq!(entries[0].client_ip, Some("192.0.2.7".parse().unwrap()));
+}
+
+#[test]
+fn record_failed_verification() {
+    let sink = Arc::new(MockSink::default());
+    submit_token(&sink, |_| bcrypt::hash("another session", 4).unwrap());
+
+    let entries = sink.0.lock().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert!(matches!(entries[0].outcome, Err(CsrfError::Mismatch)));
+    assert_eq!(entries[0].path.as_deref(), Some("/comments"));
+    assert_eq!(entries[0].source, Some(TokenSource::Header));
+    assert!(entries[0].timestamp <= rocket::time::OffsetDateTime::now_utc());
+}
@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate rocket;
+
+use bcrypt::verify;
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::AuthenticityToken;
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![index])
+}
+
+#[get("/")]
+fn index(authenticity_token: AuthenticityToken) -> String {
+    authenticity_token.into_inner()
+}
+
+#[test]
+fn yield_verifiable_authenticity_token() {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+
+    let client = client();
+    let response = client
+        .get("/")
+        .private_cookie(Cookie::new("csrf_token", encoded.clone()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert!(verify(&encoded, &response.into_string().unwrap()).unwrap());
+}
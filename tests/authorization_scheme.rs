@@ -0,0 +1,87 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::{Header, Status};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::State;
+use rocket_csrf_token::{csrf_header_token, CsrfConfig, CsrfToken, Fairing};
+
+struct HeaderToken(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for HeaderToken {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        request
+            .guard::<&State<CsrfConfig>>()
+            .await
+            .map(|config| HeaderToken(csrf_header_token(request, config).map(String::from)))
+    }
+}
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(Fairing::new(
+            CsrfConfig::default().with_authorization_scheme(Some("CSRF")),
+        ))
+        .mount("/", routes![index, submit])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[post("/")]
+fn submit(csrf_token: CsrfToken, token: HeaderToken) -> Status {
+    match token.0.map(|token| csrf_token.verify(&token)) {
+        Some(Ok(())) => Status::Ok,
+        Some(Err(_)) => Status::Forbidden,
+        None => Status::BadRequest,
+    }
+}
+
+fn submit_with_authorization(values: &[String]) -> Status {
+    let client = client();
+    let token = client.get("/").dispatch().into_string().unwrap();
+
+    let mut request = client.post("/");
+    for value in values {
+        request = request.header(Header::new("Authorization", value.replace("{}", &token)));
+    }
+    let status = request.dispatch().status();
+    status
+}
+
+#[test]
+fn verify_token_from_custom_scheme() {
+    assert_eq!(
+        submit_with_authorization(&["CSRF {}".to_string()]),
+        Status::Ok
+    );
+    assert_eq!(
+        submit_with_authorization(&["csrf {}".to_string()]),
+        Status::Ok
+    );
+}
+
+#[test]
+fn skip_bearer_credentials() {
+    assert_eq!(
+        submit_with_authorization(&["Bearer abc.def.ghi, CSRF {}".to_string()]),
+        Status::Ok
+    );
+    assert_eq!(
+        submit_with_authorization(&["Bearer abc.def.ghi".to_string(), "CSRF {}".to_string()]),
+        Status::Ok
+    );
+    assert_eq!(
+        submit_with_authorization(&["Bearer {}".to_string()]),
+        Status::BadRequest
+    );
+}
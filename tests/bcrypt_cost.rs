@@ -0,0 +1,73 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(
+        rocket::build()
+            .attach(Fairing::new(config))
+            .mount("/", routes![index, verify]),
+    )
+    .unwrap()
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(_) => "rejected",
+    }
+}
+
+fn encode(token: &str) -> String {
+    token.replace('$', "%24").replace('/', "%2F")
+}
+
+#[test]
+fn embed_configured_cost_in_tokens() {
+    let client = client(CsrfConfig::default().with_bcrypt_cost(10));
+    let token = client.get("/").dispatch().into_string().unwrap();
+
+    assert!(token.starts_with("$2b$10$"), "{}", token);
+    assert_eq!(
+        client
+            .get(format!("/verify?token={}", encode(&token)))
+            .dispatch()
+            .into_string()
+            .unwrap(),
+        "ok"
+    );
+}
+
+#[test]
+fn embed_default_cost_in_tokens() {
+    let client = client(CsrfConfig::default());
+    let token = client.get("/").dispatch().into_string().unwrap();
+
+    assert!(token.starts_with("$2b$08$"), "{}", token);
+}
+
+#[test]
+fn clamp_cost_into_bcrypt_range() {
+    assert_eq!(
+        CsrfConfig::default()
+            .with_bcrypt_cost(2)
+            .summary()
+            .bcrypt_cost,
+        4
+    );
+    assert_eq!(
+        CsrfConfig::default()
+            .with_bcrypt_cost(40)
+            .summary()
+            .bcrypt_cost,
+        31
+    );
+    assert_eq!(CsrfConfig::default().summary().bcrypt_cost, 8);
+}
@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(lenient: bool) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(lenient)).unwrap()
+}
+
+fn rocket(lenient: bool) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(Fairing::new(
+            CsrfConfig::default().with_case_insensitive_cookie_lookup(lenient),
+        ))
+        .mount("/", routes![index])
+}
+
+#[get("/?<token>")]
+fn index(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(_) => "rejected",
+    }
+}
+
+fn verify_with_uppercase_cookie(lenient: bool) -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+
+    client(lenient)
+        .get(format!("/?token={}", token))
+        .private_cookie(Cookie::new("CSRF_TOKEN", encoded))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn match_differently_cased_cookie_when_lenient() {
+    assert_eq!(verify_with_uppercase_cookie(true), "ok");
+}
+
+#[test]
+fn match_cookie_name_exactly_by_default() {
+    assert_eq!(verify_with_uppercase_cookie(false), "rejected");
+}
@@ -0,0 +1,76 @@
+#[macro_use]
+extern crate rocket;
+
+use bcrypt::hash;
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket::Request;
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfQuery, CsrfRequestExt};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_query_tokens(true),
+        ))
+        .register("/", catchers![forbidden])
+        .mount("/", routes![archive])
+}
+
+#[derive(FromForm)]
+struct Archive {
+    id: u32,
+}
+
+#[get("/archive")]
+fn archive(query: CsrfQuery<Archive>) -> String {
+    query.id.to_string()
+}
+
+#[catch(403)]
+fn forbidden(request: &Request) -> &'static str {
+    match request.csrf_error() {
+        Some(CsrfError::Missing) => "missing",
+        Some(CsrfError::Mismatch) => "mismatch",
+        Some(_) => "other",
+        None => "none",
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn catcher_reads_mismatch_reason() {
+    let token = hash(session_token(), 4).unwrap();
+
+    let client = client();
+    let response = client
+        .get(format!("/archive?id=7&authenticity_token={}", token))
+        .private_cookie(Cookie::new("csrf_token", session_token()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Forbidden);
+    assert_eq!(response.into_string().unwrap(), "mismatch");
+}
+
+#[test]
+fn catcher_reads_missing_reason() {
+    let client = client();
+    let response = client
+        .get("/archive?id=7")
+        .private_cookie(Cookie::new("csrf_token", session_token()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Forbidden);
+    assert_eq!(response.into_string().unwrap(), "missing");
+}
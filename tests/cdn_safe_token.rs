@@ -0,0 +1,90 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(config)).unwrap()
+}
+
+fn rocket(config: CsrfConfig) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![page, verify])
+}
+
+// A page served from a cache: it neither renders a token nor sets a cookie itself.
+#[get("/")]
+fn page() -> &'static str {
+    "cached page"
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn deliver_verifiable_token_in_header() {
+    let session = session_token();
+    let client = client(CsrfConfig::default().with_cdn_safe_token(true));
+
+    let response = client
+        .get("/")
+        .private_cookie(Cookie::new("csrf_token", session.clone()))
+        .dispatch();
+    // The client already holds its cookie, so none is set on the page.
+    assert!(response.cookies().get("csrf_token").is_none());
+    let token = response
+        .headers()
+        .get_one("X-CSRF-Token")
+        .unwrap()
+        .to_string();
+
+    let status = client
+        .get(format!("/verify?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", session))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn deliver_token_of_freshly_issued_cookie() {
+    let client = client(CsrfConfig::default().with_cdn_safe_token(true));
+
+    let response = client.get("/").dispatch();
+    let token = response
+        .headers()
+        .get_one("X-CSRF-Token")
+        .unwrap()
+        .to_string();
+
+    let status = client
+        .get(format!("/verify?token={}", token))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn omit_header_by_default() {
+    let client = client(CsrfConfig::default());
+    let response = client.get("/").dispatch();
+
+    assert!(response.headers().get_one("X-CSRF-Token").is_none());
+}
@@ -0,0 +1,47 @@
+use rocket::time::Duration;
+use rocket_csrf_token::{CsrfConfig, TokenStrategy};
+
+fn rocket_with_secret_key() -> rocket::Rocket<rocket::Build> {
+    let figment = rocket::Config::figment().merge(("secret_key", vec![1u8; 64]));
+
+    rocket::custom(figment)
+}
+
+#[test]
+fn report_private_cookies_without_secret_key() {
+    let errors = CsrfConfig::default()
+        .check_runtime(&rocket::build())
+        .unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("secret_key"));
+}
+
+#[test]
+fn accept_private_cookies_with_secret_key() {
+    assert_eq!(
+        CsrfConfig::default().check_runtime(&rocket_with_secret_key()),
+        Ok(())
+    );
+}
+
+#[test]
+fn accept_shared_key_without_secret_key() {
+    let config = CsrfConfig::default().migrate_to_shared_key([3; 32]);
+
+    assert_eq!(config.check_runtime(&rocket::build()), Ok(()));
+}
+
+#[test]
+fn report_stateless_strategy_without_secret() {
+    let config = CsrfConfig::default().with_strategy(TokenStrategy::TimeWindow {
+        step: Duration::minutes(5),
+        secret: Vec::new(),
+    });
+
+    let errors = config.check_runtime(&rocket_with_secret_key()).unwrap_err();
+    assert_eq!(
+        errors,
+        ["The `time-window` token strategy has an empty server secret."]
+    );
+}
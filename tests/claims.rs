@@ -0,0 +1,166 @@
+#![cfg(feature = "json")]
+
+#[macro_use]
+extern crate rocket;
+
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket::time::OffsetDateTime;
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, Fairing, TokenStrategy};
+use sha2::Sha256;
+
+const SECRET: &[u8] = b"server secret";
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    let strategy = TokenStrategy::Claims {
+        secret: SECRET.to_vec(),
+    };
+
+    rocket::build()
+        .attach(Fairing::new(CsrfConfig::default().with_strategy(strategy)))
+        .mount("/", routes![index, verify])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(CsrfError::Malformed) => "malformed",
+        Err(CsrfError::Mismatch) => "mismatch",
+        Err(CsrfError::Expired) => "expired",
+        Err(_) => "other",
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+/// Mints a claims token for `session` as an external service would.
+fn mint(session: &str, claims: serde_json::Value) -> String {
+    let claims = general_purpose::URL_SAFE_NO_PAD.encode(claims.to_string());
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET).unwrap();
+    mac.update(&(session.len() as u64).to_be_bytes());
+    mac.update(session.as_bytes());
+    mac.update(claims.as_bytes());
+
+    format!(
+        "{}.{}",
+        claims,
+        general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+    )
+}
+
+fn verify_with(session: &str, token: &str) -> String {
+    client()
+        .get(format!("/verify?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", session.to_string()))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+#[test]
+fn accept_minted_claims() {
+    let client = client();
+    let token = client.get("/").dispatch().into_string().unwrap();
+
+    let (claims, _) = token.split_once('.').unwrap();
+    let claims: serde_json::Value =
+        serde_json::from_slice(&general_purpose::URL_SAFE_NO_PAD.decode(claims).unwrap()).unwrap();
+    assert_eq!(
+        claims["exp"].as_i64().unwrap() - claims["iat"].as_i64().unwrap(),
+        3600
+    );
+
+    let response = client.get(format!("/verify?token={}", token)).dispatch();
+    assert_eq!(response.into_string().unwrap(), "ok");
+}
+
+#[test]
+fn accept_valid_external_claims() {
+    let session = session_token();
+    let token = mint(
+        &session,
+        serde_json::json!({ "iat": now(), "exp": now() + 60 }),
+    );
+
+    assert_eq!(verify_with(&session, &token), "ok");
+}
+
+#[test]
+fn reject_expired_claims() {
+    let session = session_token();
+    let token = mint(
+        &session,
+        serde_json::json!({ "iat": now() - 120, "exp": now() - 60 }),
+    );
+
+    assert_eq!(verify_with(&session, &token), "expired");
+}
+
+#[test]
+fn reject_tampered_claims() {
+    let session = session_token();
+    let token = mint(
+        &session,
+        serde_json::json!({ "iat": now() - 120, "exp": now() - 60 }),
+    );
+    let (_, mac) = token.split_once('.').unwrap();
+    let extended = general_purpose::URL_SAFE_NO_PAD
+        .encode(serde_json::json!({ "iat": now(), "exp": now() + 3600 }).to_string());
+
+    assert_eq!(
+        verify_with(&session, &format!("{}.{}", extended, mac)),
+        "mismatch"
+    );
+}
+
+#[test]
+fn reject_claims_for_another_scope() {
+    let session = session_token();
+    let token = mint(
+        &session,
+        serde_json::json!({ "iat": now(), "exp": now() + 60, "scope": "admin" }),
+    );
+
+    assert_eq!(verify_with(&session, &token), "mismatch");
+}
+
+#[test]
+fn reject_claims_signed_for_another_session() {
+    let token = mint(
+        &session_token(),
+        serde_json::json!({ "iat": now(), "exp": now() + 60 }),
+    );
+
+    assert_eq!(verify_with(&session_token(), &token), "mismatch");
+}
+
+#[test]
+fn report_claims_strategy_in_summary() {
+    let strategy = TokenStrategy::Claims {
+        secret: SECRET.to_vec(),
+    };
+    let summary = CsrfConfig::default().with_strategy(strategy).summary();
+
+    assert_eq!(summary.strategy, "claims");
+}
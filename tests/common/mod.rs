@@ -0,0 +1,37 @@
+use std::sync::Mutex;
+
+/// Collects the messages logged while a test runs.
+pub struct CapturingLogger(Mutex<Vec<String>>);
+
+impl log::Log for CapturingLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.0.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+impl CapturingLogger {
+    /// Installs the logger. The logger is global to the test binary, so binaries using it hold a
+    /// single test.
+    pub fn install(&'static self) {
+        log::set_logger(self).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+    }
+
+    /// Drops the messages captured so far.
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    /// Returns whether a captured message contains `text`.
+    pub fn captured(&self, text: &str) -> bool {
+        self.0.lock().unwrap().iter().any(|log| log.contains(text))
+    }
+}
+
+pub static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
@@ -0,0 +1,84 @@
+#![allow(deprecated)]
+
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::compat::VerificationFailure;
+use rocket_csrf_token::{CsrfError, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![legacy, current])
+}
+
+// Written as against 0.2.x: the token is a `&String` and failures are `VerificationFailure`.
+#[allow(clippy::ptr_arg)]
+fn legacy_check(csrf_token: &CsrfToken, token: &String) -> Result<(), VerificationFailure> {
+    csrf_token.verify(token).map_err(|_| VerificationFailure)
+}
+
+#[get("/legacy?<token>")]
+fn legacy(csrf_token: CsrfToken, token: String) -> Status {
+    match legacy_check(&csrf_token, &token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+#[get("/current?<token>")]
+fn current(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn behave_like_the_current_api() {
+    let session = session_token();
+    let valid = bcrypt::hash(&session, 4).unwrap();
+    let foreign = bcrypt::hash(session_token(), 4).unwrap();
+
+    let client = client();
+    for (token, expected) in [
+        (valid, Status::Ok),
+        (foreign, Status::Forbidden),
+        ("garbage".to_string(), Status::Forbidden),
+    ] {
+        let statuses: Vec<_> = ["legacy", "current"]
+            .iter()
+            .map(|path| {
+                client
+                    .get(format!("/{}?token={}", path, token))
+                    .private_cookie(Cookie::new("csrf_token", session.clone()))
+                    .dispatch()
+                    .status()
+            })
+            .collect();
+        assert_eq!(statuses, [expected, expected]);
+    }
+}
+
+#[test]
+fn alias_the_error_type() {
+    let err: VerificationFailure = VerificationFailure;
+
+    assert!(matches!(err, CsrfError::Mismatch));
+    assert_eq!(err.to_string(), CsrfError::Mismatch.to_string());
+}
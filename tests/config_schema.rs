@@ -0,0 +1,91 @@
+#![cfg(feature = "json")]
+
+use rocket_csrf_token::CsrfConfig;
+use serde_json::json;
+
+/// Lists the fields of `ConfigSummary` from its pretty-printed `Debug` output.
+fn summary_fields() -> Vec<String> {
+    format!("{:#?}", CsrfConfig::default().summary())
+        .lines()
+        .filter_map(|line| line.strip_prefix("    "))
+        .filter(|line| !line.starts_with(' '))
+        .filter_map(|line| line.split_once(':').map(|(name, _)| name.to_string()))
+        .collect()
+}
+
+#[test]
+fn list_every_configurable_field() {
+    let schema = CsrfConfig::schema();
+    let properties = schema["properties"].as_object().unwrap();
+
+    let fields = summary_fields();
+    assert!(!fields.is_empty());
+    assert_eq!(properties.len(), fields.len());
+    for field in fields {
+        let property = &properties[&field];
+        assert!(property.get("type").is_some(), "{} has no type", field);
+        assert!(
+            property.get("default").is_some(),
+            "{} has no default",
+            field
+        );
+        assert!(property["description"].is_string(), "{}", field);
+    }
+}
+
+#[test]
+fn describe_exactly_the_summary_fields() {
+    let schema = CsrfConfig::schema();
+    let mut properties: Vec<&String> = schema["properties"].as_object().unwrap().keys().collect();
+    let summary = CsrfConfig::default().summary().to_json();
+    let mut reported: Vec<&String> = summary.as_object().unwrap().keys().collect();
+    let mut fields = summary_fields();
+
+    properties.sort();
+    reported.sort();
+    fields.sort();
+    assert_eq!(properties, reported);
+    assert_eq!(reported, fields.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn report_types_and_defaults() {
+    let schema = CsrfConfig::schema();
+    let properties = &schema["properties"];
+
+    assert_eq!(properties["cookie_name"]["type"], "string");
+    assert_eq!(properties["cookie_name"]["default"], "csrf_token");
+    assert_eq!(properties["cookie_len"]["type"], "integer");
+    assert_eq!(properties["cookie_len"]["default"], 32);
+    assert_eq!(properties["lifespan"]["type"], json!(["integer", "null"]));
+    assert_eq!(properties["lifespan"]["default"], 86400);
+    assert_eq!(
+        properties["same_site"]["enum"],
+        json!(["Strict", "Lax", "None"])
+    );
+    assert_eq!(properties["same_site"]["default"], "Strict");
+    assert_eq!(properties["logout_path"]["default"], json!(null));
+    assert_eq!(
+        properties["form_content_types"]["default"],
+        json!(["application/x-www-form-urlencoded", "multipart/form-data"])
+    );
+}
+
+#[test]
+fn validate_summaries_against_schema_types() {
+    let summary = CsrfConfig::development()
+        .with_logout_path(Some("/logout".to_string()))
+        .summary()
+        .to_json();
+
+    assert_eq!(summary["same_site"], "Lax");
+    assert_eq!(summary["lifespan"], 3600);
+    assert_eq!(summary["logout_path"], "/logout");
+    assert_eq!(
+        summary.as_object().unwrap().len(),
+        CsrfConfig::schema()["properties"]
+            .as_object()
+            .unwrap()
+            .len()
+    );
+}
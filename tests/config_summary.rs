@@ -0,0 +1,41 @@
+use rocket::http::SameSite;
+use rocket::time::Duration;
+use rocket_csrf_token::{CsrfConfig, Fairing};
+
+#[test]
+fn summary_reflects_default_config() {
+    let summary = Fairing::default().summary();
+
+    assert_eq!(summary.cookie_name, "csrf_token");
+    assert_eq!(summary.cookie_len, 32);
+    assert_eq!(summary.lifespan, Some(Duration::days(1)));
+    assert_eq!(summary.same_site, SameSite::Strict);
+    assert!(!summary.development);
+}
+
+#[test]
+fn summary_reflects_builder_values() {
+    let fairing = Fairing::new(
+        CsrfConfig::default()
+            .with_cookie_name("foobar")
+            .with_cookie_len(64)
+            .with_lifetime(None)
+            .with_legacy_configs(vec![CsrfConfig::default()])
+            .with_rotation_grace(Some(Duration::minutes(5)))
+            .with_route_scoping(true)
+            .with_strict_length(true)
+            .with_max_verify_attempts(2),
+    );
+
+    let summary = fairing.summary();
+
+    assert_eq!(summary.cookie_name, "foobar");
+    assert_eq!(summary.cookie_len, 64);
+    assert_eq!(summary.lifespan, None);
+    assert_eq!(summary.legacy_configs, 1);
+    assert_eq!(summary.rotation_grace, Some(Duration::minutes(5)));
+    assert!(summary.route_scoping);
+    assert!(summary.strict_length);
+    assert_eq!(summary.max_verify_attempts, 2);
+    assert_eq!(fairing.effective_config().summary(), summary);
+}
@@ -0,0 +1,47 @@
+use rocket_csrf_token::{
+    constant_time_eq, mint_standalone, verify_standalone, Choice, CsrfError, TokenStrategy,
+};
+
+#[test]
+fn compare_through_a_choice() {
+    // Returning a `subtle::Choice` makes the comparison constant-time by construction: the
+    // result can't be branched on until it's converted once the comparison has completed.
+    let compare: fn(&[u8], &[u8]) -> Choice = constant_time_eq;
+
+    assert!(bool::from(compare(b"token", b"token")));
+}
+
+#[test]
+fn tell_tokens_apart() {
+    assert!(!bool::from(constant_time_eq(b"token", b"tokem")));
+    assert!(!bool::from(constant_time_eq(b"token", b"token+")));
+    assert!(!bool::from(constant_time_eq(b"", b"token")));
+    assert!(bool::from(constant_time_eq(b"", b"")));
+}
+
+/// Replaces the character at `index` of `token` with another one of the base64 alphabet.
+fn tamper(token: &str, index: usize) -> String {
+    let mut chars: Vec<char> = token.chars().collect();
+    chars[index] = if chars[index] == 'A' { 'B' } else { 'A' };
+    chars.into_iter().collect()
+}
+
+#[test]
+fn check_every_part_of_split_tokens() {
+    // Split selectors are derived from the session token, so they are compared in constant time
+    // too, and a token only passes when both its selector and its validator match.
+    let secret = [7u8; 32];
+    let strategy = TokenStrategy::Split {
+        secret: b"server secret".to_vec(),
+    };
+    let token = mint_standalone(&secret, &strategy).unwrap();
+    let dot = token.find('.').unwrap();
+
+    assert!(verify_standalone(&secret, &token, &strategy).is_ok());
+    for index in [0, dot - 1, dot + 1, token.len() - 2] {
+        assert!(matches!(
+            verify_standalone(&secret, &tamper(&token, index), &strategy),
+            Err(CsrfError::Mismatch)
+        ));
+    }
+}
@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+#[get("/")]
+fn index(_csrf_token: CsrfToken) {}
+
+fn issued_cookie_attributes(config: CsrfConfig) -> Vec<String> {
+    let client = rocket::local::blocking::Client::untracked(
+        rocket::build()
+            .attach(Fairing::new(config))
+            .mount("/", routes![index]),
+    )
+    .unwrap();
+
+    let response = client.get("/").dispatch();
+    let header = response
+        .headers()
+        .get("Set-Cookie")
+        .find(|header| header.starts_with("csrf_token="))
+        .unwrap()
+        .to_string();
+    header.split("; ").map(String::from).collect()
+}
+
+#[test]
+fn issue_cookie_for_configured_domain() {
+    let attributes =
+        issued_cookie_attributes(CsrfConfig::default().with_cookie_domain("example.com"));
+
+    assert!(attributes.contains(&"Domain=example.com".to_string()));
+}
+
+#[test]
+fn issue_host_only_cookie_by_default() {
+    let attributes = issued_cookie_attributes(CsrfConfig::default());
+
+    assert!(!attributes.iter().any(|a| a.starts_with("Domain=")));
+}
+
+#[test]
+fn report_cookie_domain_in_summary() {
+    assert_eq!(
+        CsrfConfig::default()
+            .with_cookie_domain("example.com")
+            .summary()
+            .cookie_domain
+            .as_deref(),
+        Some("example.com")
+    );
+    assert_eq!(CsrfConfig::default().summary().cookie_domain, None);
+}
@@ -0,0 +1,63 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+#[get("/")]
+fn index(_csrf_token: CsrfToken) {}
+
+fn issued_cookie_attributes(config: CsrfConfig) -> Vec<String> {
+    let client = rocket::local::blocking::Client::untracked(
+        rocket::build()
+            .attach(Fairing::new(config))
+            .mount("/", routes![index]),
+    )
+    .unwrap();
+
+    let response = client.get("/").dispatch();
+    let header = response
+        .headers()
+        .get("Set-Cookie")
+        .find(|header| header.starts_with("csrf_token="))
+        .unwrap()
+        .to_string();
+    header.split("; ").map(String::from).collect()
+}
+
+#[test]
+fn issue_secure_http_only_cookie_by_default() {
+    let attributes = issued_cookie_attributes(CsrfConfig::default());
+
+    assert!(attributes.contains(&"Secure".to_string()));
+    assert!(attributes.contains(&"HttpOnly".to_string()));
+}
+
+#[test]
+fn issue_cookie_with_configured_flags() {
+    let attributes =
+        issued_cookie_attributes(CsrfConfig::default().with_secure(true).with_http_only(true));
+
+    assert!(attributes.contains(&"Secure".to_string()));
+    assert!(attributes.contains(&"HttpOnly".to_string()));
+}
+
+#[test]
+fn leave_out_disabled_flags() {
+    let attributes = issued_cookie_attributes(
+        CsrfConfig::default()
+            .with_secure(false)
+            .with_http_only(false),
+    );
+
+    assert!(!attributes.contains(&"Secure".to_string()));
+    assert!(!attributes.contains(&"HttpOnly".to_string()));
+}
+
+#[test]
+fn report_cookie_flags_in_summary() {
+    let summary = CsrfConfig::default().with_http_only(false).summary();
+
+    assert!(summary.secure);
+    assert!(!summary.http_only);
+    assert!(!CsrfConfig::development().summary().secure);
+}
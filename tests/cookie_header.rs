@@ -0,0 +1,84 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::{Cookie, Status};
+use rocket::time::Duration;
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+const SHARED_KEY: [u8; 32] = [42; 32];
+
+fn config() -> CsrfConfig {
+    CsrfConfig::default().migrate_to_shared_key(SHARED_KEY)
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+#[test]
+fn include_configured_attributes_and_signed_token() {
+    let token = [7u8; 32];
+    let header = config()
+        .with_cookie_name("edge_csrf")
+        .with_lifetime(Some(Duration::hours(1)))
+        .build_cookie_header(&token)
+        .unwrap();
+
+    let attributes: Vec<&str> = header.split("; ").collect();
+    assert!(attributes[0].starts_with(&format!(
+        "edge_csrf={}.",
+        general_purpose::STANDARD.encode(token)
+    )));
+    assert!(attributes.contains(&"HttpOnly"));
+    assert!(attributes.contains(&"SameSite=Strict"));
+    assert!(attributes.contains(&"Path=/"));
+    assert!(attributes.iter().any(|a| a.starts_with("Expires=")));
+    assert!(attributes.contains(&"Secure"));
+}
+
+#[test]
+fn omit_expiry_for_session_cookies() {
+    let header = config()
+        .with_lifetime(None)
+        .build_cookie_header(&[1u8; 32])
+        .unwrap();
+
+    assert!(header.starts_with("csrf_token="));
+    assert!(!header.contains("Expires="));
+}
+
+#[test]
+fn refuse_without_shared_key() {
+    assert!(CsrfConfig::default()
+        .build_cookie_header(&[1u8; 32])
+        .is_none());
+}
+
+#[test]
+fn round_trip_through_fairing() {
+    let config = config().with_extra_signing_key([9; 32]);
+    let header = config.build_cookie_header(&[1u8; 32]).unwrap();
+    let (name, value) = header
+        .split("; ")
+        .next()
+        .and_then(|pair| pair.split_once('='))
+        .unwrap();
+    let token = bcrypt::hash(general_purpose::STANDARD.encode([1u8; 32]), 4).unwrap();
+
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![verify]);
+    let status = rocket::local::blocking::Client::untracked(rocket)
+        .unwrap()
+        .get(format!("/verify?token={}", token))
+        .cookie(Cookie::new(name.to_string(), value.to_string()))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
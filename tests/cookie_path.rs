@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+#[get("/")]
+fn index(_csrf_token: CsrfToken) {}
+
+fn issued_cookie_attributes(config: CsrfConfig) -> Vec<String> {
+    let client = rocket::local::blocking::Client::untracked(
+        rocket::build()
+            .attach(Fairing::new(config))
+            .mount("/", routes![index]),
+    )
+    .unwrap();
+
+    let response = client.get("/").dispatch();
+    let header = response
+        .headers()
+        .get("Set-Cookie")
+        .find(|header| header.starts_with("csrf_token="))
+        .unwrap()
+        .to_string();
+    header.split("; ").map(String::from).collect()
+}
+
+#[test]
+fn issue_cookie_under_configured_path() {
+    let attributes = issued_cookie_attributes(CsrfConfig::default().with_cookie_path("/admin"));
+
+    assert!(attributes.contains(&"Path=/admin".to_string()));
+    assert!(!attributes.contains(&"Path=/".to_string()));
+}
+
+#[test]
+fn issue_cookie_under_root_path_by_default() {
+    let attributes = issued_cookie_attributes(CsrfConfig::default());
+
+    assert!(attributes.contains(&"Path=/".to_string()));
+    assert_eq!(CsrfConfig::default().summary().cookie_path, "/");
+}
@@ -0,0 +1,111 @@
+#[macro_use]
+extern crate rocket;
+
+use bcrypt::hash;
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::{CookiePreference, CsrfConfig, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+const LEGACY_COOKIE_NAME: &str = "legacy_csrf";
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(config)).unwrap()
+}
+
+fn rocket(config: CsrfConfig) -> rocket::Rocket<rocket::Build> {
+    let config = config
+        .with_legacy_configs(vec![
+            CsrfConfig::default().with_cookie_name(LEGACY_COOKIE_NAME)
+        ])
+        .with_clear_stale_cookies(true);
+
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![index])
+}
+
+#[get("/?<token>")]
+fn index(csrf_token: CsrfToken, token: &str) -> (Status, String) {
+    match csrf_token.verify(token) {
+        Ok(()) => (Status::Ok, csrf_token.cookie_name().to_string()),
+        Err(_) => (Status::Forbidden, csrf_token.cookie_name().to_string()),
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn prefer_current_cookie_and_clear_legacy_one() {
+    let current_token = session_token();
+    let authenticity_token = hash(&current_token, 4).unwrap();
+    let client = client(CsrfConfig::default());
+
+    let response = client
+        .get(format!("/?token={}", authenticity_token))
+        .private_cookie(Cookie::new("csrf_token", current_token))
+        .private_cookie(Cookie::new(LEGACY_COOKIE_NAME, session_token()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    let removal = response.cookies().get(LEGACY_COOKIE_NAME).unwrap();
+    assert_eq!(removal.value(), "");
+    assert!(response.cookies().get("csrf_token").is_none());
+    assert_eq!(response.into_string().unwrap(), "csrf_token");
+}
+
+#[test]
+fn prefer_legacy_cookie_when_configured() {
+    let legacy_token = session_token();
+    let authenticity_token = hash(&legacy_token, 4).unwrap();
+    let client = client(CsrfConfig::default().with_cookie_preference(CookiePreference::Legacy));
+
+    let response = client
+        .get(format!("/?token={}", authenticity_token))
+        .private_cookie(Cookie::new("csrf_token", session_token()))
+        .private_cookie(Cookie::new(LEGACY_COOKIE_NAME, legacy_token))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.cookies().get("csrf_token").unwrap().value(), "");
+    assert!(response.cookies().get(LEGACY_COOKIE_NAME).is_none());
+    assert_eq!(response.into_string().unwrap(), LEGACY_COOKIE_NAME);
+}
+
+#[test]
+fn fall_back_to_legacy_cookie_when_current_is_missing() {
+    let legacy_token = session_token();
+    let authenticity_token = hash(&legacy_token, 4).unwrap();
+    let client = client(CsrfConfig::default());
+
+    let response = client
+        .get(format!("/?token={}", authenticity_token))
+        .private_cookie(Cookie::new(LEGACY_COOKIE_NAME, legacy_token))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    // The current cookie is issued alongside, while the legacy one is kept until then.
+    assert!(response.cookies().get_private("csrf_token").is_some());
+    assert!(response.cookies().get(LEGACY_COOKIE_NAME).is_none());
+}
+
+#[test]
+fn report_cookie_preference_in_summary() {
+    let summary = CsrfConfig::default()
+        .with_cookie_preference(CookiePreference::Legacy)
+        .with_clear_stale_cookies(true)
+        .summary();
+
+    assert_eq!(summary.cookie_preference, CookiePreference::Legacy);
+    assert!(summary.clear_stale_cookies);
+    assert_eq!(
+        CsrfConfig::default().summary().cookie_preference,
+        CookiePreference::Current
+    );
+}
@@ -0,0 +1,72 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_csrf_token::{CsrfContext, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+/// A component reading the shared token on its own, reporting where it lives.
+struct Component(usize);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Component {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.guard::<CsrfContext<'_>>().await {
+            Outcome::Success(context) => {
+                Outcome::Success(Component(context.token() as *const CsrfToken as usize))
+            }
+            _ => Outcome::Error((Status::Forbidden, ())),
+        }
+    }
+}
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![index])
+}
+
+#[get("/?<token>")]
+fn index(first: Component, second: Component, context: CsrfContext<'_>, token: &str) -> String {
+    format!(
+        "{} {}",
+        first.0 == second.0 && second.0 == context.token() as *const CsrfToken as usize,
+        context.verify(token).is_ok()
+    )
+}
+
+#[test]
+fn share_one_token_between_components() {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+
+    let client = client();
+    let response = client
+        .get(format!("/?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "true true");
+}
+
+#[test]
+fn succeed_without_prior_session() {
+    let client = client();
+    let response = client.get("/?token=none").dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "true false");
+    assert!(client.cookies().get_private("csrf_token").is_some());
+}
@@ -0,0 +1,90 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::{CsrfError, CsrfToken};
+use std::error::Error as _;
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![describe, respond]);
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[get("/describe?<token>")]
+fn describe(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(CsrfError::Missing) => "missing",
+        Err(CsrfError::Malformed) => "malformed",
+        Err(CsrfError::Mismatch) => "mismatch",
+        Err(_) => "other",
+    }
+}
+
+#[get("/respond?<token>")]
+fn respond(csrf_token: CsrfToken, token: &str) -> Result<&'static str, CsrfError> {
+    csrf_token.verify(token).map(|()| "ok")
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+fn encode(token: &str) -> String {
+    token.replace('$', "%24").replace('/', "%2F")
+}
+
+fn get(path: &str, session: &str, token: &str) -> (Status, String) {
+    let client = client();
+    let response = client
+        .get(format!("{}?token={}", path, encode(token)))
+        .private_cookie(Cookie::new("csrf_token", session.to_string()))
+        .dispatch();
+
+    (
+        response.status(),
+        response.into_string().unwrap_or_default(),
+    )
+}
+
+#[test]
+fn report_each_failure_as_its_variant() {
+    let session = session_token();
+    let valid = bcrypt::hash(&session, 4).unwrap();
+    let foreign = bcrypt::hash(session_token(), 4).unwrap();
+
+    assert_eq!(get("/describe", &session, &valid).1, "ok");
+    assert_eq!(get("/describe", &session, "").1, "missing");
+    assert_eq!(get("/describe", &session, "garbage").1, "malformed");
+    assert_eq!(get("/describe", &session, &foreign).1, "mismatch");
+}
+
+#[test]
+fn respond_to_every_failure_with_forbidden() {
+    let session = session_token();
+    let foreign = bcrypt::hash(session_token(), 4).unwrap();
+
+    for token in ["", "garbage", foreign.as_str()] {
+        assert_eq!(get("/respond", &session, token).0, Status::Forbidden);
+    }
+    let valid = bcrypt::hash(&session, 4).unwrap();
+    assert_eq!(get("/respond", &session, &valid), (Status::Ok, "ok".into()));
+}
+
+#[test]
+fn wrap_bcrypt_errors() {
+    let err = CsrfError::from(bcrypt::BcryptError::CostNotAllowed(2));
+
+    assert!(matches!(err, CsrfError::HashError(_)));
+    assert!(err.source().is_some());
+    assert!(CsrfError::Mismatch.source().is_none());
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "derive")]
+
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::form::Form;
+use rocket::http::{ContentType, Cookie};
+use rocket_csrf_token::{CsrfProtected, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![comment, message])
+}
+
+#[derive(FromForm, CsrfProtected)]
+struct Comment {
+    authenticity_token: String,
+    body: String,
+}
+
+#[derive(FromForm, CsrfProtected)]
+#[csrf(field = "csrf")]
+struct Message<'r> {
+    csrf: &'r str,
+    text: &'r str,
+}
+
+#[post("/comments", data = "<form>")]
+fn comment(csrf_token: CsrfToken, form: Form<Comment>) -> String {
+    match form.verify(&csrf_token) {
+        Ok(()) => form.body.clone(),
+        Err(err) => err.to_string(),
+    }
+}
+
+#[post("/messages", data = "<form>")]
+fn message(csrf_token: CsrfToken, form: Form<Message<'_>>) -> String {
+    match form.verify(&csrf_token) {
+        Ok(()) => form.text.to_string(),
+        Err(err) => err.to_string(),
+    }
+}
+
+fn submit(uri: &str, field: &str, token: impl FnOnce(&str) -> String) -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = token(&encoded).replace('$', "%24").replace('/', "%2F");
+
+    let client = client();
+    let response = client
+        .post(uri)
+        .header(ContentType::Form)
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .body(format!("{}={}&body=hello&text=hello", field, token))
+        .dispatch();
+
+    response.into_string().unwrap()
+}
+
+#[test]
+fn verify_derived_form() {
+    let body = submit("/comments", "authenticity_token", |session| {
+        bcrypt::hash(session, 4).unwrap()
+    });
+
+    assert_eq!(body, "hello");
+}
+
+#[test]
+fn reject_derived_form_with_foreign_token() {
+    let body = submit("/comments", "authenticity_token", |_| {
+        bcrypt::hash("another session", 4).unwrap()
+    });
+
+    assert_eq!(body, "CSRF token verification failed!");
+}
+
+#[test]
+fn verify_derived_form_with_renamed_field() {
+    let body = submit("/messages", "csrf", |session| {
+        bcrypt::hash(session, 4).unwrap()
+    });
+
+    assert_eq!(body, "hello");
+}
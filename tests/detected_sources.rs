@@ -0,0 +1,87 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::{ContentType, Cookie, Header};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::State;
+use rocket_csrf_token::{csrf_detected_sources, CsrfConfig, TokenSource};
+
+struct Sources(Vec<TokenSource>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Sources {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        request
+            .guard::<&State<CsrfConfig>>()
+            .await
+            .map(|config| Sources(csrf_detected_sources(request, config)))
+    }
+}
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![submit])
+}
+
+#[post("/", data = "<_body>")]
+fn submit(sources: Sources, _body: String) -> String {
+    format!("{:?}", sources.0)
+}
+
+#[test]
+fn report_every_source_carrying_a_token() {
+    let body = client()
+        .post("/")
+        .header(ContentType::Form)
+        .header(Header::new("X-CSRF-Token", "from-header"))
+        .body("authenticity_token=from-form&comment=hi")
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(
+        body,
+        format!("{:?}", [TokenSource::Header, TokenSource::Form])
+    );
+}
+
+#[test]
+fn report_query_json_and_cookie_sources() {
+    let body = client()
+        .post("/?authenticity_token=from-query")
+        .header(ContentType::JSON)
+        .private_cookie(Cookie::new("csrf_token", "session token"))
+        .body(r#"{"authenticity_token": "from-json"}"#)
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(
+        body,
+        format!(
+            "{:?}",
+            [TokenSource::Query, TokenSource::Json, TokenSource::Cookie]
+        )
+    );
+}
+
+#[test]
+fn ignore_empty_values() {
+    let body = client()
+        .post("/?authenticity_token=")
+        .header(ContentType::Form)
+        .header(Header::new("X-CSRF-Token", ""))
+        .body("authenticity_token=")
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(body, "[]");
+}
@@ -0,0 +1,40 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::SameSite;
+use rocket::time::{Duration, OffsetDateTime};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            rocket_csrf_token::CsrfConfig::development(),
+        ))
+        .mount("/", routes![index])
+}
+
+#[get("/")]
+fn index() {}
+
+#[test]
+fn development_preset_relaxes_cookie_attributes() {
+    let client = client();
+    let response = client.get("/").dispatch();
+
+    let csrf_cookie = response
+        .cookies()
+        .iter()
+        .find(|cookie| cookie.name() == "csrf_token")
+        .cloned()
+        .expect("CSRF token cookie should exist");
+
+    assert_eq!(csrf_cookie.same_site(), Some(SameSite::Lax));
+    assert_ne!(csrf_cookie.secure(), Some(true));
+
+    let expires = csrf_cookie.expires_datetime().unwrap();
+    assert!(expires <= OffsetDateTime::now_utc() + Duration::hours(1));
+    assert!(expires > OffsetDateTime::now_utc() + Duration::minutes(55));
+}
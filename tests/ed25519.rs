@@ -0,0 +1,85 @@
+#![cfg(feature = "ed25519")]
+
+#[macro_use]
+extern crate rocket;
+
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::{Signer, SigningKey};
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, Fairing, TokenStrategy};
+
+const AUTH_SERVICE_KEY: [u8; 32] = [7; 32];
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    let public_key = SigningKey::from_bytes(&AUTH_SERVICE_KEY)
+        .verifying_key()
+        .to_bytes();
+
+    rocket::build()
+        .attach(Fairing::new(
+            CsrfConfig::default().with_strategy(TokenStrategy::Ed25519 { public_key }),
+        ))
+        .mount("/", routes![verify])
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(CsrfError::Malformed) => "malformed",
+        Err(CsrfError::Mismatch) => "mismatch",
+        Err(_) => "other",
+    }
+}
+
+/// Mints a token the way the auth service does.
+fn sign(key: &SigningKey, payload: &[u8]) -> String {
+    format!(
+        "{}.{}",
+        general_purpose::URL_SAFE_NO_PAD.encode(payload),
+        general_purpose::URL_SAFE_NO_PAD.encode(key.sign(payload).to_bytes())
+    )
+}
+
+fn verify_token(token: &str) -> String {
+    client()
+        .get(format!("/verify?token={}", token))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn accept_validly_signed_token() {
+    let token = sign(&SigningKey::from_bytes(&AUTH_SERVICE_KEY), b"session 42");
+
+    assert_eq!(verify_token(&token), "ok");
+}
+
+#[test]
+fn reject_tampered_token() {
+    let token = sign(&SigningKey::from_bytes(&AUTH_SERVICE_KEY), b"session 42");
+    let (_, signature) = token.split_once('.').unwrap();
+    let tampered = format!(
+        "{}.{}",
+        general_purpose::URL_SAFE_NO_PAD.encode(b"session 43"),
+        signature
+    );
+
+    assert_eq!(verify_token(&tampered), "mismatch");
+}
+
+#[test]
+fn reject_token_signed_by_another_key() {
+    let token = sign(&SigningKey::from_bytes(&[8; 32]), b"session 42");
+
+    assert_eq!(verify_token(&token), "mismatch");
+}
+
+#[test]
+fn reject_unsigned_token() {
+    assert_eq!(verify_token("c2Vzc2lvbiA0Mg"), "malformed");
+}
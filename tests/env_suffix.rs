@@ -0,0 +1,31 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_env_suffix("staging"),
+        ))
+        .mount("/", routes![index])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.cookie_name().to_string()
+}
+
+#[test]
+fn apply_env_suffix_to_cookie_name() {
+    let client = client();
+    let response = client.get("/").dispatch();
+
+    assert!(response.cookies().get("csrf_token_staging").is_some());
+    assert!(response.cookies().get("csrf_token").is_none());
+    assert_eq!(response.into_string().unwrap(), "csrf_token_staging");
+}
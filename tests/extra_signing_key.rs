@@ -0,0 +1,122 @@
+#[macro_use]
+extern crate rocket;
+
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket::local::blocking::Client;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+use sha2::Sha256;
+
+const EXTRA_KEY: [u8; 32] = [7; 32];
+
+fn client() -> Client {
+    Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    let config = CsrfConfig::default().with_extra_signing_key(EXTRA_KEY);
+
+    rocket::build()
+        .attach(Fairing::new(config))
+        .mount("/", routes![index, check])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(_) => "rejected",
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+/// Signs a cookie value the way the extra signing key does.
+fn sign(key: &[u8], value: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+    mac.update(b"csrf_token");
+    mac.update(&[0]);
+    mac.update(value.as_bytes());
+
+    format!(
+        "{}.{}",
+        value,
+        general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+    )
+}
+
+/// Submits a token minted from `session` along with a private cookie holding `cookie_value`.
+fn submit(session: &str, cookie_value: String) -> String {
+    let token = bcrypt::hash(session, 4)
+        .unwrap()
+        .replace('$', "%24")
+        .replace('/', "%2F");
+    client()
+        .get(format!("/check?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", cookie_value))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn round_trip_issued_cookie() {
+    let client = client();
+    let token = client.get("/").dispatch().into_string().unwrap();
+
+    let response = client.get(format!("/check?token={}", token)).dispatch();
+    assert_eq!(response.into_string().unwrap(), "ok");
+}
+
+#[test]
+fn accept_cookie_signed_with_extra_key() {
+    let session = session_token();
+
+    assert_eq!(submit(&session, sign(&EXTRA_KEY, &session)), "ok");
+}
+
+#[test]
+fn reject_unsigned_cookie() {
+    let session = session_token();
+
+    assert_eq!(submit(&session, session.clone()), "rejected");
+}
+
+#[test]
+fn reject_tampered_cookie() {
+    let session = session_token();
+    let signed = sign(&EXTRA_KEY, &session_token());
+    let (_, tag) = signed.rsplit_once('.').unwrap();
+
+    assert_eq!(submit(&session, format!("{}.{}", session, tag)), "rejected");
+}
+
+#[test]
+fn reject_cookie_signed_with_wrong_key() {
+    let session = session_token();
+
+    assert_eq!(submit(&session, sign(&[8; 32], &session)), "rejected");
+}
+
+#[test]
+fn report_extra_signing_key_in_summary() {
+    assert!(
+        CsrfConfig::default()
+            .with_extra_signing_key(EXTRA_KEY)
+            .summary()
+            .extra_signing_key
+    );
+    assert!(!CsrfConfig::default().summary().extra_signing_key);
+}
@@ -0,0 +1,62 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::Status;
+use rocket::request::FlashMessage;
+use rocket::response::{Flash, Redirect};
+use rocket_csrf_token::{flashed_authenticity_token, CsrfToken};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![form, submit, check])
+}
+
+#[get("/form")]
+fn form(flash: Option<FlashMessage>) -> String {
+    flash
+        .as_ref()
+        .and_then(flashed_authenticity_token)
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[post("/submit")]
+fn submit(csrf_token: CsrfToken) -> Flash<Redirect> {
+    csrf_token.flash(Redirect::to(uri!(form))).unwrap()
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+#[test]
+fn carry_token_through_post_redirect_get() {
+    let client = client();
+    // Establish the session.
+    assert_eq!(client.get("/form").dispatch().into_string().unwrap(), "");
+
+    let response = client.post("/submit").dispatch();
+    assert_eq!(response.status(), Status::SeeOther);
+    assert_eq!(response.headers().get_one("Location"), Some("/form"));
+
+    let token = client.get("/form").dispatch().into_string().unwrap();
+    assert!(CsrfToken::is_well_formed(&token));
+
+    let status = client
+        .get(format!("/check?token={}", token))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+
+    // The flash is consumed by the redirected GET.
+    assert_eq!(client.get("/form").dispatch().into_string().unwrap(), "");
+}
@@ -0,0 +1,119 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{ContentType, Cookie};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, TokenSource};
+
+use base64::{engine::general_purpose, Engine as _};
+
+const LEGACY_FORM: &str = "application/x-legacy-form";
+
+struct Verified(Result<TokenSource, CsrfError>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Verified {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        request
+            .guard::<CsrfToken>()
+            .await
+            .map(|csrf_token| Verified(csrf_token.verify_request(request)))
+    }
+}
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![submit]);
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[post("/", data = "<_body>")]
+fn submit(verified: Verified, _body: String) -> String {
+    format!("{:?}", verified.0)
+}
+
+fn submit_with(
+    config: CsrfConfig,
+    content_type: ContentType,
+    body: impl Fn(&str) -> String,
+) -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+
+    let client = client(config);
+    let response = client
+        .post("/")
+        .header(content_type)
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .body(body(&token))
+        .dispatch();
+
+    response.into_string().unwrap()
+}
+
+fn urlencoded(token: &str) -> String {
+    format!(
+        "authenticity_token={}&comment=hi",
+        token.replace('$', "%24").replace('/', "%2F")
+    )
+}
+
+#[test]
+fn recognize_custom_form_content_type() {
+    let content_type = ContentType::parse_flexible(LEGACY_FORM).unwrap();
+    let config = CsrfConfig::default().with_form_content_types(vec![LEGACY_FORM.to_string()]);
+
+    assert_eq!(
+        submit_with(config, content_type, urlencoded),
+        format!("{:?}", Ok::<_, CsrfError>(TokenSource::Form))
+    );
+}
+
+#[test]
+fn ignore_unlisted_content_types() {
+    let content_type = ContentType::parse_flexible(LEGACY_FORM).unwrap();
+
+    assert_eq!(
+        submit_with(CsrfConfig::default(), content_type, urlencoded),
+        format!("{:?}", Err::<TokenSource, _>(CsrfError::Missing))
+    );
+}
+
+#[test]
+fn read_multipart_forms_by_default() {
+    let content_type =
+        ContentType::parse_flexible("multipart/form-data; boundary=X-BOUNDARY").unwrap();
+    let multipart = |token: &str| {
+        format!(
+            "--X-BOUNDARY\r\n\
+             Content-Disposition: form-data; name=\"authenticity_token\"\r\n\r\n\
+             {}\r\n\
+             --X-BOUNDARY\r\n\
+             Content-Disposition: form-data; name=\"comment\"\r\n\r\n\
+             hi\r\n\
+             --X-BOUNDARY--\r\n",
+            token
+        )
+    };
+
+    assert_eq!(
+        submit_with(CsrfConfig::default(), content_type, multipart),
+        format!("{:?}", Ok::<_, CsrfError>(TokenSource::Form))
+    );
+}
+
+#[test]
+fn report_form_content_types_in_summary() {
+    assert_eq!(
+        CsrfConfig::default().summary().form_content_types,
+        ["application/x-www-form-urlencoded", "multipart/form-data"]
+    );
+}
@@ -0,0 +1,91 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::form::{Contextual, Form};
+use rocket::http::{ContentType, Cookie, Status};
+use rocket_csrf_token::CsrfToken;
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![create])
+}
+
+#[derive(FromForm)]
+struct Comment<'r> {
+    authenticity_token: &'r str,
+    body: &'r str,
+}
+
+#[post("/", data = "<form>")]
+fn create(csrf_token: CsrfToken, mut form: Form<Contextual<'_, Comment<'_>>>) -> (Status, String) {
+    let _ = csrf_token.validate_form(&mut form);
+
+    match &form.value {
+        Some(comment) => (
+            Status::Ok,
+            format!(
+                "{} ({} chars)",
+                comment.body,
+                comment.authenticity_token.len()
+            ),
+        ),
+        None => (
+            form.context.status(),
+            form.context
+                .field_errors("authenticity_token")
+                .map(|err| err.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    }
+}
+
+fn submit(token: impl FnOnce(&str) -> String) -> (Status, String) {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = token(&encoded).replace('$', "%24").replace('/', "%2F");
+
+    let client = client();
+    let response = client
+        .post("/")
+        .header(ContentType::Form)
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .body(format!("authenticity_token={}&body=hello", token))
+        .dispatch();
+
+    (response.status(), response.into_string().unwrap())
+}
+
+#[test]
+fn accept_form_with_valid_token() {
+    let (status, body) = submit(|session| bcrypt::hash(session, 4).unwrap());
+
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body, "hello (60 chars)");
+}
+
+#[test]
+fn report_invalid_token_as_field_error() {
+    let (status, body) = submit(|_| bcrypt::hash("another session", 4).unwrap());
+
+    assert_eq!(status, Status::UnprocessableEntity);
+    assert_eq!(body, "CSRF token verification failed!");
+}
+
+#[test]
+fn report_malformed_token_as_field_error() {
+    let (status, body) = submit(|_| "garbage".to_string());
+
+    assert_eq!(status, Status::UnprocessableEntity);
+    assert!(!body.is_empty());
+}
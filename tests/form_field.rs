@@ -0,0 +1,96 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{ContentType, Cookie};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, TokenSource};
+
+use base64::{engine::general_purpose, Engine as _};
+
+struct Verified(Result<TokenSource, CsrfError>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Verified {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        request
+            .guard::<CsrfToken>()
+            .await
+            .map(|csrf_token| Verified(csrf_token.verify_request(request)))
+    }
+}
+
+fn client() -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_form_field("form[_csrf]"),
+        ))
+        .mount("/", routes![submit]);
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[post("/", data = "<_body>")]
+fn submit(verified: Verified, _body: String) -> String {
+    format!("{:?}", verified.0)
+}
+
+fn submit_form(field: &str, token: Option<&str>) -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = match token {
+        Some(token) => token.to_string(),
+        None => bcrypt::hash(&encoded, 4).unwrap(),
+    };
+
+    let client = client();
+    let response = client
+        .post("/")
+        .header(ContentType::Form)
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .body(format!(
+            "{}={}&form%5Bbody%5D=hi",
+            field,
+            token.replace('$', "%24").replace('/', "%2F")
+        ))
+        .dispatch();
+
+    response.into_string().unwrap()
+}
+
+#[test]
+fn verify_token_under_bracketed_field() {
+    let ok = format!("{:?}", Ok::<_, CsrfError>(TokenSource::Form));
+
+    assert_eq!(submit_form("form%5B_csrf%5D", None), ok);
+    assert_eq!(submit_form("form[_csrf]", None), ok);
+    assert_eq!(submit_form("form._csrf", None), ok);
+}
+
+#[test]
+fn verify_token_under_plain_field() {
+    assert_eq!(
+        submit_form("authenticity_token", None),
+        format!("{:?}", Ok::<_, CsrfError>(TokenSource::Form))
+    );
+}
+
+#[test]
+fn reject_invalid_token_under_bracketed_field() {
+    assert_eq!(
+        submit_form("form%5B_csrf%5D", Some("garbage")),
+        format!("{:?}", Err::<TokenSource, _>(CsrfError::Malformed))
+    );
+}
+
+#[test]
+fn ignore_other_bracketed_fields() {
+    assert_eq!(
+        submit_form("form%5Bcsrf%5D", None),
+        format!("{:?}", Err::<TokenSource, _>(CsrfError::Missing))
+    );
+}
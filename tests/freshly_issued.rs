@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_csrf_token::CsrfToken;
+
+struct Fresh(bool);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Fresh {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(Fresh(CsrfToken::was_freshly_issued(request)))
+    }
+}
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![index])
+}
+
+#[get("/")]
+fn index(fresh: Fresh) -> &'static str {
+    if fresh.0 {
+        "fresh"
+    } else {
+        "existing"
+    }
+}
+
+#[test]
+fn flag_only_the_request_that_issued_the_token() {
+    let client = client();
+
+    assert_eq!(client.get("/").dispatch().into_string().unwrap(), "fresh");
+    assert_eq!(
+        client.get("/").dispatch().into_string().unwrap(),
+        "existing"
+    );
+    assert_eq!(
+        client.get("/").dispatch().into_string().unwrap(),
+        "existing"
+    );
+}
@@ -0,0 +1,48 @@
+use rocket::time::Duration;
+use rocket_csrf_token::CsrfConfig;
+use std::env;
+
+const VARS: [&str; 6] = [
+    "ROCKET_CSRF_COOKIE_NAME",
+    "ROCKET_CSRF_LIFESPAN",
+    "ROCKET_CSRF_COOKIE_LEN",
+    "ROCKET_CSRF_SECRET",
+    "ROCKET_CSRF_STRATEGY",
+    "ROCKET_CSRF_STEP",
+];
+
+// Environment variables are process-wide, so every scenario runs in a single test.
+#[test]
+fn read_config_from_env() {
+    env::set_var("ROCKET_CSRF_COOKIE_NAME", "container_csrf");
+    env::set_var("ROCKET_CSRF_LIFESPAN", "600");
+    env::set_var("ROCKET_CSRF_COOKIE_LEN", "48");
+    env::set_var("ROCKET_CSRF_SECRET", "shared key");
+
+    // The secret alone signs the cookies, and tokens stay bound to the session.
+    let summary = CsrfConfig::from_env().summary();
+    assert_eq!(summary.cookie_name, "container_csrf");
+    assert_eq!(summary.lifespan, Some(Duration::seconds(600)));
+    assert_eq!(summary.cookie_len, 48);
+    assert!(summary.shared_key);
+    assert_eq!(summary.strategy, "bcrypt");
+
+    env::set_var("ROCKET_CSRF_STRATEGY", "time-window");
+    env::set_var("ROCKET_CSRF_STEP", "60");
+    assert_eq!(CsrfConfig::from_env().summary().strategy, "time-window");
+
+    // The length falls back to the default of the time-window strategy.
+    env::set_var("ROCKET_CSRF_COOKIE_LEN", "not a number");
+    assert_eq!(CsrfConfig::from_env().summary().cookie_len, 16);
+
+    env::remove_var("ROCKET_CSRF_SECRET");
+    assert_eq!(CsrfConfig::from_env().summary().strategy, "bcrypt");
+
+    for var in VARS {
+        env::remove_var(var);
+    }
+    assert_eq!(
+        CsrfConfig::from_env().summary(),
+        CsrfConfig::default().summary()
+    );
+}
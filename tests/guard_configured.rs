@@ -5,6 +5,7 @@ use bcrypt::verify;
 use rand::RngCore;
 use rocket::http::Cookie;
 use rocket_csrf_token::CsrfToken;
+use sha2::{Digest, Sha256};
 
 use base64::{engine::general_purpose, Engine as _};
 
@@ -23,7 +24,7 @@ fn rocket() -> rocket::Rocket<rocket::Build> {
                 .with_cookie_len(COOKIE_LEN)
                 .with_lifetime(Some(rocket::time::Duration::days(3))),
         ))
-        .mount("/", routes![index])
+        .mount("/", routes![index, cookie_name])
 }
 
 #[get("/")]
@@ -31,6 +32,11 @@ fn index(csrf_token: CsrfToken) -> String {
     csrf_token.authenticity_token().unwrap().to_string()
 }
 
+#[get("/cookie-name")]
+fn cookie_name(csrf_token: CsrfToken) -> String {
+    csrf_token.cookie_name().to_string()
+}
+
 #[test]
 fn respond_with_valid_authenticity_token() {
     let mut raw = [0u8; COOKIE_LEN];
@@ -45,5 +51,24 @@ fn respond_with_valid_authenticity_token() {
         .into_string()
         .unwrap();
 
-    assert!(verify(&encoded, &body).unwrap());
+    // Encoded session tokens past bcrypt's 72-byte limit are pre-hashed.
+    let secret = general_purpose::STANDARD.encode(Sha256::digest(&encoded));
+    assert!(verify(&secret, &body).unwrap());
+}
+
+#[test]
+fn report_configured_cookie_name() {
+    let mut raw = [0u8; COOKIE_LEN];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+
+    let body = client()
+        .get("/cookie-name")
+        .private_cookie(Cookie::new(COOKIE_NAME, encoded))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(body, COOKIE_NAME);
 }
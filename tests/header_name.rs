@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Header};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, TokenSource};
+
+use base64::{engine::general_purpose, Engine as _};
+
+const ANGULAR_HEADER: &str = "X-XSRF-TOKEN";
+
+struct Verified(Result<TokenSource, CsrfError>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Verified {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        request
+            .guard::<CsrfToken>()
+            .await
+            .map(|csrf_token| Verified(csrf_token.verify_request(request)))
+    }
+}
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![submit]);
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[post("/", data = "<_body>")]
+fn submit(verified: Verified, _body: String) -> String {
+    format!("{:?}", verified.0)
+}
+
+fn submit_under(config: CsrfConfig, header_name: &'static str) -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+
+    let client = client(config);
+    let response = client
+        .post("/")
+        .header(Header::new(header_name, token))
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .body("")
+        .dispatch();
+
+    response.into_string().unwrap()
+}
+
+#[test]
+fn accept_token_under_configured_header() {
+    let config = CsrfConfig::default().with_header_name(ANGULAR_HEADER);
+
+    assert_eq!(
+        submit_under(config, ANGULAR_HEADER),
+        format!("{:?}", Ok::<_, CsrfError>(TokenSource::Header))
+    );
+}
+
+#[test]
+fn ignore_token_under_default_header_once_renamed() {
+    let config = CsrfConfig::default().with_header_name(ANGULAR_HEADER);
+
+    assert_eq!(
+        submit_under(config, "X-CSRF-Token"),
+        format!("{:?}", Err::<TokenSource, _>(CsrfError::Missing))
+    );
+}
+
+#[test]
+fn read_default_header_name() {
+    assert_eq!(
+        submit_under(CsrfConfig::default(), "X-CSRF-Token"),
+        format!("{:?}", Ok::<_, CsrfError>(TokenSource::Header))
+    );
+    assert_eq!(CsrfConfig::default().summary().header_name, "X-CSRF-Token");
+}
@@ -0,0 +1,57 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::Header;
+use rocket_csrf_token::CsrfConfig;
+
+fn client(https_only: bool) -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_https_only(https_only),
+        ))
+        .mount("/", routes![index]);
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[get("/")]
+fn index() -> &'static str {
+    "ok"
+}
+
+fn issues_cookie(https_only: bool, header: Option<Header<'static>>) -> bool {
+    let client = client(https_only);
+    let mut request = client.get("/");
+    if let Some(header) = header {
+        request = request.header(header);
+    }
+
+    let response = request.dispatch();
+    response.cookies().get_private("csrf_token").is_some()
+}
+
+#[test]
+fn refuse_cookie_over_plain_http() {
+    assert!(!issues_cookie(true, None));
+    assert!(!issues_cookie(
+        true,
+        Some(Header::new("X-Forwarded-Proto", "http"))
+    ));
+}
+
+#[test]
+fn issue_cookie_behind_tls_terminating_proxy() {
+    assert!(issues_cookie(
+        true,
+        Some(Header::new("X-Forwarded-Proto", "https"))
+    ));
+    assert!(issues_cookie(
+        true,
+        Some(Header::new("Forwarded", "for=192.0.2.60;proto=https"))
+    ));
+}
+
+#[test]
+fn issue_cookie_over_plain_http_by_default() {
+    assert!(issues_cookie(false, None));
+}
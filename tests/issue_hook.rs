@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket_csrf_token::IssueHook;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+fn client(hook: IssueHook) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(hook)).unwrap()
+}
+
+fn rocket(hook: IssueHook) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default().with_on_issue(hook))
+        .mount("/", routes![index])
+}
+
+#[get("/")]
+fn index() {}
+
+#[test]
+fn fire_hook_once_per_fresh_issuance() {
+    let issued = Arc::new(AtomicUsize::new(0));
+    let counter = issued.clone();
+
+    let client = client(Arc::new(move |event| {
+        assert_eq!(event.path, "/");
+        assert_eq!(event.token_len, 32);
+        counter.fetch_add(1, Ordering::SeqCst);
+    }));
+
+    client.get("/").dispatch();
+    assert_eq!(issued.load(Ordering::SeqCst), 1);
+
+    // The tracked client now sends the issued cookie back, so no new token is minted.
+    client.get("/").dispatch();
+    assert_eq!(issued.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn panicking_hook_does_not_fail_the_request() {
+    let client = client(Arc::new(|_| panic!("hook failure")));
+
+    let response = client.get("/").dispatch();
+
+    assert_eq!(response.status(), rocket::http::Status::Ok);
+    assert!(response.cookies().get("csrf_token").is_some());
+}
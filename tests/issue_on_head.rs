@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::local::blocking::{Client, LocalResponse};
+use rocket_csrf_token::{CsrfConfig, Fairing};
+
+fn client(config: CsrfConfig) -> Client {
+    Client::tracked(rocket(config)).unwrap()
+}
+
+fn rocket(config: CsrfConfig) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(Fairing::new(config))
+        .mount("/", routes![index])
+}
+
+#[get("/")]
+fn index() -> &'static str {
+    "hello"
+}
+
+fn issues_cookie(response: LocalResponse<'_>) -> bool {
+    response.cookies().get_private("csrf_token").is_some()
+}
+
+#[test]
+fn issue_on_head_like_get_by_default() {
+    assert!(issues_cookie(
+        client(CsrfConfig::default()).get("/").dispatch()
+    ));
+    assert!(issues_cookie(
+        client(CsrfConfig::default()).head("/").dispatch()
+    ));
+}
+
+#[test]
+fn skip_head_requests_when_disabled() {
+    let config = || CsrfConfig::default().with_issue_on_head(false);
+
+    assert!(issues_cookie(client(config()).get("/").dispatch()));
+    assert!(!issues_cookie(client(config()).head("/").dispatch()));
+}
+
+#[test]
+fn report_issue_on_head_in_summary() {
+    assert!(CsrfConfig::default().summary().issue_on_head);
+    assert!(
+        !CsrfConfig::default()
+            .with_issue_on_head(false)
+            .summary()
+            .issue_on_head
+    );
+}
@@ -0,0 +1,69 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::Status;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(Fairing::new(
+            CsrfConfig::default().with_issue_on_success_only(true),
+        ))
+        .mount("/", routes![index, gone, check])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/gone")]
+fn gone(_csrf_token: CsrfToken) -> (Status, &'static str) {
+    (Status::NotFound, "gone")
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+#[test]
+fn withhold_cookie_from_not_found_response() {
+    let client = client();
+    let response = client.get("/missing").dispatch();
+
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.cookies().get("csrf_token").is_none());
+}
+
+#[test]
+fn withhold_cookie_from_error_status_set_by_handler() {
+    let client = client();
+    let response = client.get("/gone").dispatch();
+
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.cookies().get("csrf_token").is_none());
+}
+
+#[test]
+fn set_cookie_on_successful_response() {
+    let client = client();
+    let response = client.get("/").dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.cookies().get("csrf_token").is_some());
+
+    let token = response.into_string().unwrap();
+    let status = client
+        .get(format!("/check?token={}", token))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
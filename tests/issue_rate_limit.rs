@@ -0,0 +1,86 @@
+#[macro_use]
+extern crate rocket;
+
+use std::net::SocketAddr;
+
+use rocket::time::Duration;
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    // Untracked, so every request comes without a cookie like a client discarding them.
+    rocket::local::blocking::Client::untracked(rocket(config)).unwrap()
+}
+
+fn rocket(config: CsrfConfig) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![index])
+}
+
+#[get("/")]
+fn index(_csrf_token: Option<CsrfToken>) -> &'static str {
+    "ok"
+}
+
+fn issued(client: &rocket::local::blocking::Client, remote: &str) -> bool {
+    let remote: SocketAddr = remote.parse().unwrap();
+
+    client
+        .get("/")
+        .remote(remote)
+        .dispatch()
+        .cookies()
+        .get_private("csrf_token")
+        .is_some()
+}
+
+#[test]
+fn throttle_issuance_past_limit() {
+    let client = client(CsrfConfig::default().with_issue_rate_limit(3, Duration::minutes(1)));
+
+    let issued: Vec<_> = (0..6).map(|_| issued(&client, "192.0.2.1:4000")).collect();
+
+    assert_eq!(issued, [true, true, true, false, false, false]);
+}
+
+#[test]
+fn limit_each_client_separately() {
+    let client = client(CsrfConfig::default().with_issue_rate_limit(1, Duration::minutes(1)));
+
+    assert!(issued(&client, "192.0.2.1:4000"));
+    assert!(!issued(&client, "192.0.2.1:4001"));
+    assert!(issued(&client, "192.0.2.2:4000"));
+}
+
+#[test]
+fn issue_again_once_window_elapses() {
+    let client = client(CsrfConfig::default().with_issue_rate_limit(1, Duration::seconds(1)));
+
+    assert!(issued(&client, "192.0.2.1:4000"));
+    assert!(!issued(&client, "192.0.2.1:4000"));
+
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+
+    assert!(issued(&client, "192.0.2.1:4000"));
+}
+
+#[test]
+fn keep_counting_across_many_clients() {
+    let client = client(CsrfConfig::default().with_issue_rate_limit(1, Duration::minutes(1)));
+
+    assert!(issued(&client, "192.0.2.1:4000"));
+    // Enough clients to trigger pruning, which keeps the windows still running.
+    for i in 0..1500u32 {
+        let [_, _, high, low] = i.to_be_bytes();
+        assert!(issued(&client, &format!("198.51.{}.{}:4000", high, low)));
+    }
+    assert!(!issued(&client, "192.0.2.1:4000"));
+}
+
+#[test]
+fn issue_without_limit_by_default() {
+    let client = client(CsrfConfig::default());
+
+    assert!((0..10).all(|_| issued(&client, "192.0.2.1:4000")));
+    assert_eq!(CsrfConfig::default().summary().issue_rate_limit, None);
+}
@@ -0,0 +1,77 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::{Cookie, SameSite};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::time::{Duration, OffsetDateTime};
+use rocket_csrf_token::{CsrfConfig, CsrfRequestExt, IssuedCookie};
+
+struct Issued(Option<IssuedCookie>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Issued {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(Issued(request.csrf_issued_cookie()))
+    }
+}
+
+fn client() -> rocket::local::blocking::Client {
+    let config = CsrfConfig::default()
+        .with_cookie_name("issued_csrf")
+        .with_lifetime(Some(Duration::hours(2)));
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![index]);
+
+    rocket::local::blocking::Client::untracked(rocket).unwrap()
+}
+
+#[get("/")]
+fn index(issued: Issued) -> String {
+    match issued.0 {
+        Some(cookie) => {
+            let expires_in = cookie.expires.unwrap() - OffsetDateTime::now_utc();
+            format!(
+                "{} {:?} {:?} {:?} {:?} {:?} {}",
+                cookie.name,
+                cookie.same_site,
+                cookie.secure,
+                cookie.http_only,
+                cookie.path,
+                cookie.domain,
+                (Duration::minutes(119)..=Duration::hours(2)).contains(&expires_in)
+            )
+        }
+        None => "none".to_string(),
+    }
+}
+
+#[test]
+fn record_attributes_of_issued_cookie() {
+    let body = client().get("/").dispatch().into_string().unwrap();
+
+    assert_eq!(
+        body,
+        format!(
+            "issued_csrf {:?} Some(true) Some(true) Some(\"/\") None true",
+            Some(SameSite::Strict)
+        )
+    );
+}
+
+#[test]
+fn record_nothing_when_session_is_valid() {
+    let body = client()
+        .get("/")
+        .private_cookie(Cookie::new(
+            "issued_csrf",
+            "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+        ))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(body, "none");
+}
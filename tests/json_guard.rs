@@ -0,0 +1,85 @@
+#![cfg(feature = "json")]
+
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::{ContentType, Header, Status};
+use rocket::serde::Deserialize;
+use rocket_csrf_token::{CsrfJson, CsrfToken};
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct Comment {
+    body: String,
+}
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![token, preview, create])
+}
+
+#[get("/token")]
+fn token(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/comments", data = "<comment>")]
+fn preview(comment: CsrfJson<Comment>) -> String {
+    format!("preview: {}", comment.body)
+}
+
+#[post("/comments", data = "<comment>")]
+fn create(comment: CsrfJson<Comment>) -> String {
+    format!("created: {}", comment.into_inner().body)
+}
+
+const COMMENT: &str = r#"{"body": "hi"}"#;
+
+#[test]
+fn exempt_safe_methods() {
+    let client = client();
+    let response = client
+        .get("/comments")
+        .header(ContentType::JSON)
+        .body(COMMENT)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "preview: hi");
+}
+
+#[test]
+fn accept_post_with_valid_header_token() {
+    let client = client();
+    let token = client.get("/token").dispatch().into_string().unwrap();
+
+    let response = client
+        .post("/comments")
+        .header(ContentType::JSON)
+        .header(Header::new("X-CSRF-Token", token))
+        .body(COMMENT)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "created: hi");
+}
+
+#[test]
+fn reject_post_without_header_token() {
+    let client = client();
+    client.get("/token").dispatch();
+
+    let status = client
+        .post("/comments")
+        .header(ContentType::JSON)
+        .body(COMMENT)
+        .dispatch()
+        .status();
+
+    assert_eq!(status, Status::Forbidden);
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "json")]
+
+#[macro_use]
+extern crate rocket;
+
+use bcrypt::hash;
+use rand::RngCore;
+use rocket::http::{ContentType, Cookie};
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken};
+use serde_json::{json, Value};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![index])
+}
+
+#[post("/", data = "<body>")]
+fn index(csrf_token: CsrfToken, body: &str) -> &'static str {
+    let body: Value = serde_json::from_str(body).unwrap();
+
+    match csrf_token.verify_json(&body, "authenticity_token") {
+        Ok(()) => "ok",
+        Err(CsrfError::Missing) => "missing",
+        Err(CsrfError::Malformed) => "malformed",
+        Err(_) => "other",
+    }
+}
+
+fn submit(body: impl FnOnce(String) -> Value) -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = hash(&encoded, 4).unwrap();
+
+    client()
+        .post("/")
+        .header(ContentType::JSON)
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .body(body(token).to_string())
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn accept_token_as_string() {
+    assert_eq!(submit(|token| json!({ "authenticity_token": token })), "ok");
+}
+
+#[test]
+fn accept_token_as_single_element_array() {
+    assert_eq!(
+        submit(|token| json!({ "authenticity_token": [token] })),
+        "ok"
+    );
+}
+
+#[test]
+fn reject_token_as_multi_element_array() {
+    assert_eq!(
+        submit(|token| json!({ "authenticity_token": [token.clone(), token] })),
+        "malformed"
+    );
+}
+
+#[test]
+fn reject_missing_token_field() {
+    assert_eq!(submit(|_| json!({ "text": "hello" })), "missing");
+}
+
+#[post("/configured", data = "<body>")]
+fn configured(
+    csrf_token: CsrfToken,
+    config: &rocket::State<CsrfConfig>,
+    body: &str,
+) -> &'static str {
+    let body: Value = serde_json::from_str(body).unwrap();
+
+    match csrf_token.verify_json(&body, config.param_name()) {
+        Ok(()) => "ok",
+        Err(CsrfError::Missing) => "missing",
+        Err(_) => "other",
+    }
+}
+
+fn submit_configured(body: impl FnOnce(String) -> Value) -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = hash(&encoded, 4).unwrap();
+
+    let client = rocket::local::blocking::Client::tracked(
+        rocket::build()
+            .attach(rocket_csrf_token::Fairing::new(
+                CsrfConfig::default().with_param_name("_csrf"),
+            ))
+            .mount("/", routes![configured]),
+    )
+    .unwrap();
+    let response = client
+        .post("/configured")
+        .header(ContentType::JSON)
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .body(body(token).to_string())
+        .dispatch();
+
+    response.into_string().unwrap()
+}
+
+#[test]
+fn read_token_under_configured_param_name() {
+    assert_eq!(submit_configured(|token| json!({ "_csrf": token })), "ok");
+}
+
+#[test]
+fn reject_token_under_default_name_once_renamed() {
+    assert_eq!(
+        submit_configured(|token| json!({ "authenticity_token": token })),
+        "missing"
+    );
+}
@@ -0,0 +1,106 @@
+#[macro_use]
+extern crate rocket;
+
+use bcrypt::hash;
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+const LEGACY_COOKIE_NAME: &str = "legacy_csrf";
+
+fn client(legacy_configs: Vec<CsrfConfig>) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(legacy_configs)).unwrap()
+}
+
+fn rocket(legacy_configs: Vec<CsrfConfig>) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_legacy_configs(legacy_configs),
+        ))
+        .mount("/", routes![index])
+}
+
+#[get("/?<token>")]
+fn index(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn verify_token_issued_under_legacy_config() {
+    let legacy_token = session_token();
+    let authenticity_token = hash(&legacy_token, 4).unwrap();
+    let legacy = CsrfConfig::default().with_cookie_name(LEGACY_COOKIE_NAME);
+
+    let status = client(vec![legacy])
+        .get(format!("/?token={}", authenticity_token))
+        .private_cookie(Cookie::new("csrf_token", session_token()))
+        .private_cookie(Cookie::new(LEGACY_COOKIE_NAME, legacy_token))
+        .dispatch()
+        .status();
+
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn reject_token_issued_under_unlisted_config() {
+    let legacy_token = session_token();
+    let authenticity_token = hash(&legacy_token, 4).unwrap();
+
+    let status = client(Vec::new())
+        .get(format!("/?token={}", authenticity_token))
+        .private_cookie(Cookie::new("csrf_token", session_token()))
+        .private_cookie(Cookie::new(LEGACY_COOKIE_NAME, legacy_token))
+        .dispatch()
+        .status();
+
+    assert_eq!(status, Status::Forbidden);
+}
+
+#[test]
+fn stop_verifying_after_max_attempts() {
+    let legacy_token = session_token();
+    let authenticity_token = hash(&legacy_token, 4).unwrap();
+    let legacy_configs = vec![
+        CsrfConfig::default().with_cookie_name("legacy_1"),
+        CsrfConfig::default().with_cookie_name("legacy_2"),
+        CsrfConfig::default().with_cookie_name(LEGACY_COOKIE_NAME),
+    ];
+
+    let status_with_cap = |attempts: usize| {
+        let rocket = rocket::build()
+            .attach(rocket_csrf_token::Fairing::new(
+                CsrfConfig::default()
+                    .with_legacy_configs(legacy_configs.clone())
+                    .with_max_verify_attempts(attempts),
+            ))
+            .mount("/", routes![index]);
+        let client = rocket::local::blocking::Client::tracked(rocket).unwrap();
+
+        let status = client
+            .get(format!("/?token={}", authenticity_token))
+            .private_cookie(Cookie::new("csrf_token", session_token()))
+            .private_cookie(Cookie::new("legacy_1", session_token()))
+            .private_cookie(Cookie::new("legacy_2", session_token()))
+            .private_cookie(Cookie::new(LEGACY_COOKIE_NAME, legacy_token.clone()))
+            .dispatch()
+            .status();
+
+        status
+    };
+
+    // The matching token is the fourth candidate.
+    assert_eq!(status_with_cap(3), Status::Forbidden);
+    assert_eq!(status_with_cap(4), Status::Ok);
+}
@@ -0,0 +1,55 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(lenient: bool) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(lenient)).unwrap()
+}
+
+fn rocket(lenient: bool) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(Fairing::new(
+            CsrfConfig::default().with_lenient_whitespace(lenient),
+        ))
+        .mount("/", routes![index])
+}
+
+#[get("/?<token>")]
+fn index(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(_) => "rejected",
+    }
+}
+
+/// Verifies a token with a newline embedded in both the session cookie and the submitted token.
+fn verify_with_newlines(lenient: bool) -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+    let (head, tail) = encoded.split_at(20);
+
+    client(lenient)
+        .get(format!("/?token={}%0A{}", &token[..30], &token[30..]))
+        .private_cookie(Cookie::new("csrf_token", format!("{}\n{}", head, tail)))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn accept_tokens_with_embedded_newline_when_lenient() {
+    assert_eq!(verify_with_newlines(true), "ok");
+}
+
+#[test]
+fn reject_tokens_with_embedded_newline_by_default() {
+    assert_eq!(verify_with_newlines(false), "rejected");
+}
@@ -0,0 +1,87 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, CookieJar};
+use rocket_csrf_token::CsrfConfig;
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_logout_path(Some("/logout".to_string())),
+        ))
+        .mount("/", routes![index, logout])
+}
+
+#[get("/")]
+fn index() {}
+
+#[post("/logout")]
+fn logout() {}
+
+#[post("/logout?reissue")]
+fn reissue(cookies: &CookieJar<'_>) {
+    cookies.add_private(Cookie::new("csrf_token", "reissued"));
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn clear_cookie_on_logout_path() {
+    let client = client();
+    let response = client
+        .post("/logout")
+        .private_cookie(Cookie::new("csrf_token", session_token()))
+        .dispatch();
+
+    let removal = response.cookies().get("csrf_token").unwrap();
+    assert_eq!(removal.value(), "");
+    assert_eq!(removal.max_age(), Some(rocket::time::Duration::ZERO));
+}
+
+#[test]
+fn override_cookie_issued_by_logout_handler() {
+    let client = rocket::local::blocking::Client::tracked(
+        rocket::build()
+            .attach(rocket_csrf_token::Fairing::new(
+                CsrfConfig::default().with_logout_path(Some("/logout".to_string())),
+            ))
+            .mount("/", routes![reissue]),
+    )
+    .unwrap();
+    let response = client
+        .post("/logout?reissue")
+        .private_cookie(Cookie::new("csrf_token", session_token()))
+        .dispatch();
+
+    let cookies: Vec<_> = response.headers().get("Set-Cookie").collect();
+    assert_eq!(cookies.len(), 1);
+    assert_eq!(response.cookies().get("csrf_token").unwrap().value(), "");
+}
+
+#[test]
+fn issue_no_cookie_on_logout_path() {
+    let client = client();
+    let response = client.post("/logout").dispatch();
+
+    assert!(response.cookies().get("csrf_token").is_none());
+}
+
+#[test]
+fn keep_issuing_cookies_elsewhere() {
+    let client = client();
+    let response = client.get("/").dispatch();
+
+    assert!(response.cookies().get_private("csrf_token").is_some());
+}
@@ -0,0 +1,86 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+const COOKIE_LEN: usize = 96;
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_cookie_len(COOKIE_LEN),
+        ))
+        .mount("/", routes![index, verify])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+#[test]
+fn verify_tokens_of_long_session_tokens() {
+    let mut raw = [0u8; COOKIE_LEN];
+    rand::thread_rng().fill_bytes(&mut raw);
+    let encoded = general_purpose::STANDARD.encode(raw);
+    assert!(encoded.len() > 72);
+
+    let client = client();
+    let token = client
+        .get("/")
+        .private_cookie(Cookie::new("csrf_token", encoded.clone()))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    let status = client
+        .get(format!("/verify?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn bind_tokens_to_the_tail_of_long_session_tokens() {
+    let mut raw = [0u8; COOKIE_LEN];
+    rand::thread_rng().fill_bytes(&mut raw);
+    let mut other = raw;
+    // The first 72 characters of the encoded tokens stay equal.
+    other[COOKIE_LEN - 1] ^= 0xff;
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let other_encoded = general_purpose::STANDARD.encode(other);
+    assert_eq!(encoded[..72], other_encoded[..72]);
+
+    let client = client();
+    let token = client
+        .get("/")
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    let status = client
+        .get(format!("/verify?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", other_encoded))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Forbidden);
+}
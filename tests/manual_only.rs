@@ -0,0 +1,60 @@
+#[macro_use]
+extern crate rocket;
+
+mod common;
+
+use common::LOGGER;
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![index]);
+
+    rocket::local::blocking::Client::untracked(rocket).unwrap()
+}
+
+#[get("/?<token>")]
+fn index(csrf_token: CsrfToken, token: Option<&str>) -> Status {
+    match token.map(|token| csrf_token.verify(token)) {
+        Some(Ok(())) => Status::Ok,
+        Some(Err(_)) => Status::Forbidden,
+        None => Status::NoContent,
+    }
+}
+
+fn logged_global_verification(config: CsrfConfig) -> bool {
+    let client = client(config);
+    LOGGER.clear();
+    client.post("/").dispatch();
+
+    LOGGER.captured("X-CSRF-Token")
+}
+
+// A single test, since the logger is global to the test binary.
+#[test]
+fn skip_global_verification_in_manual_only_mode() {
+    LOGGER.install();
+
+    assert!(logged_global_verification(CsrfConfig::default()));
+    assert!(!logged_global_verification(
+        CsrfConfig::default().manual_only()
+    ));
+
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+
+    let status = client(CsrfConfig::default().manual_only())
+        .get(format!("/?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+    assert!(CsrfConfig::default().manual_only().summary().manual_only);
+}
@@ -0,0 +1,114 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Header};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, TokenSource};
+
+use base64::{engine::general_purpose, Engine as _};
+
+struct Verified(Result<TokenSource, CsrfError>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Verified {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        request
+            .guard::<CsrfToken>()
+            .await
+            .map(|csrf_token| Verified(csrf_token.verify_request(request)))
+    }
+}
+
+struct HeaderTokens(Vec<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for HeaderTokens {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let config = request.rocket().state::<CsrfConfig>().unwrap();
+        let tokens = rocket_csrf_token::csrf_header_tokens(request, config);
+
+        Outcome::Success(HeaderTokens(tokens.into_iter().map(String::from).collect()))
+    }
+}
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![submit, tokens])
+}
+
+#[post("/")]
+fn submit(verified: Verified) -> String {
+    format!("{:?}", verified.0)
+}
+
+#[get("/tokens")]
+fn tokens(tokens: HeaderTokens) -> String {
+    tokens.0.join("|")
+}
+
+fn new_session() -> (String, String) {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+    (encoded, token)
+}
+
+fn submit_header(session: String, value: String) -> String {
+    client()
+        .post("/")
+        .header(Header::new("X-CSRF-Token", value))
+        .private_cookie(Cookie::new("csrf_token", session))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn accept_valid_token_in_merged_header() {
+    let (session, token) = new_session();
+    let (_, stale) = new_session();
+
+    assert_eq!(
+        submit_header(session, format!("{}, {}", stale, token)),
+        format!("{:?}", Ok::<_, CsrfError>(TokenSource::Header))
+    );
+}
+
+#[test]
+fn reject_merged_header_without_valid_token() {
+    let (session, _) = new_session();
+    let (_, stale) = new_session();
+    let (_, other) = new_session();
+
+    assert_eq!(
+        submit_header(session, format!("{},{}", stale, other)),
+        format!("{:?}", Err::<TokenSource, _>(CsrfError::Mismatch))
+    );
+}
+
+#[test]
+fn list_each_merged_header_value() {
+    let client = client();
+    let body = client
+        .get("/tokens")
+        .header(Header::new("X-CSRF-Token", "a, b,,c"))
+        .header(Header::new("X-CSRF-Token", "d, e"))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    // Only the first four values are tried.
+    assert_eq!(body, "a|b|c|d");
+}
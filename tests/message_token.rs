@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket_csrf_token::{verify_message_token, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![handshake])
+}
+
+/// Stands in for a WebSocket handshake: the connection keeps the guard's token around and
+/// checks each message against it.
+#[get("/handshake")]
+fn handshake(csrf_token: CsrfToken) -> String {
+    let connection_secret = csrf_token.clone();
+
+    let message_token = csrf_token.message_token().unwrap();
+    let form_token = csrf_token.authenticity_token().unwrap();
+
+    format!(
+        "{} {}",
+        verify_message_token(&connection_secret, &message_token).is_ok(),
+        verify_message_token(&connection_secret, &form_token).is_ok()
+    )
+}
+
+#[test]
+fn verify_message_token_against_connection_secret() {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let body = client()
+        .get("/handshake")
+        .private_cookie(Cookie::new(
+            "csrf_token",
+            general_purpose::STANDARD.encode(raw),
+        ))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    // The message token verifies, while a form authenticity token is not accepted as one.
+    assert_eq!(body, "true false");
+}
@@ -0,0 +1,204 @@
+#[macro_use]
+extern crate rocket;
+
+use std::sync::Mutex;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket::response::content::RawHtml;
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_meta_tag_injection(true),
+        ))
+        .mount("/", routes![page, check])
+}
+
+#[get("/")]
+fn page() -> RawHtml<&'static str> {
+    RawHtml("<html><head><title>Comments</title></head><body></body></html>")
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn meta_content<'a>(body: &'a str, name: &str) -> &'a str {
+    let marker = format!(r#"<meta name="{}" content=""#, name);
+    let start = body.find(&marker).expect("meta tag should be present") + marker.len();
+    let end = start + body[start..].find('"').unwrap();
+    &body[start..end]
+}
+
+#[test]
+fn emit_cookie_and_consistent_meta_tags() {
+    let client = client();
+    let response = client.get("/").dispatch();
+
+    assert!(response.cookies().get("csrf_token").is_some());
+
+    let body = response.into_string().unwrap();
+    assert!(body.ends_with("</head><body></body></html>"));
+    assert_eq!(meta_content(&body, "csrf-param"), "authenticity_token");
+
+    let token = meta_content(&body, "csrf-token");
+    let status = client
+        .get(format!("/check?token={}", token))
+        .dispatch()
+        .status();
+
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn skip_response_hook_when_injection_is_disabled() {
+    use rocket::fairing::{Fairing as _, Kind};
+
+    let disabled = rocket_csrf_token::Fairing::default().info();
+    assert!(!disabled.kind.is(Kind::Response));
+
+    let enabled =
+        rocket_csrf_token::Fairing::new(CsrfConfig::default().with_meta_tag_injection(true)).info();
+    assert!(enabled.kind.is(Kind::Response));
+}
+
+#[test]
+fn name_configured_param_in_meta_tags() {
+    let client = rocket::local::blocking::Client::tracked(
+        rocket::build()
+            .attach(rocket_csrf_token::Fairing::new(
+                CsrfConfig::default()
+                    .with_meta_tag_injection(true)
+                    .with_param_name("_csrf"),
+            ))
+            .mount("/", routes![page]),
+    )
+    .unwrap();
+
+    let body = client.get("/").dispatch().into_string().unwrap();
+
+    assert!(body.starts_with("<html><head><title>Comments</title><meta"));
+    assert_eq!(meta_content(&body, "csrf-param"), "_csrf");
+}
+
+#[test]
+fn escape_param_name_in_meta_tags() {
+    let client = rocket::local::blocking::Client::tracked(
+        rocket::build()
+            .attach(rocket_csrf_token::Fairing::new(
+                CsrfConfig::default()
+                    .with_meta_tag_injection(true)
+                    .with_param_name(r#"x"><script>"#),
+            ))
+            .mount("/", routes![page]),
+    )
+    .unwrap();
+
+    let body = client.get("/").dispatch().into_string().unwrap();
+
+    assert!(!body.contains("<script>"));
+    assert_eq!(
+        meta_content(&body, "csrf-param"),
+        "x&quot;&gt;&lt;script&gt;"
+    );
+}
+
+/// A Latin-1 encoded document, which isn't valid UTF-8.
+const LATIN1_PAGE: &[u8] = b"<html><head><title>Caf\xe9</title></head><body></body></html>";
+
+#[get("/latin1")]
+fn latin1() -> RawHtml<&'static [u8]> {
+    RawHtml(LATIN1_PAGE)
+}
+
+#[test]
+fn leave_non_utf8_documents_untouched() {
+    let client =
+        rocket::local::blocking::Client::tracked(rocket().mount("/", routes![latin1])).unwrap();
+
+    let body = client.get("/latin1").dispatch().into_bytes().unwrap();
+
+    assert_eq!(body, LATIN1_PAGE);
+}
+
+#[get("/fragment")]
+fn fragment() -> RawHtml<&'static str> {
+    RawHtml("<p>No head here</p>")
+}
+
+#[test]
+fn leave_documents_without_head_untouched() {
+    let client =
+        rocket::local::blocking::Client::tracked(rocket().mount("/", routes![fragment])).unwrap();
+
+    let body = client.get("/fragment").dispatch().into_string().unwrap();
+
+    assert_eq!(body, "<p>No head here</p>");
+}
+
+/// The `CsrfToken` guards captured by `capture`, to be attached as fairings.
+static CAPTURED: Mutex<Vec<CsrfToken>> = Mutex::new(Vec::new());
+
+#[get("/capture")]
+fn capture(csrf_token: CsrfToken) {
+    CAPTURED.lock().unwrap().push(csrf_token);
+}
+
+/// Returns a `CsrfToken` fairing configured for meta tag injection.
+fn token_fairing() -> CsrfToken {
+    let client =
+        rocket::local::blocking::Client::untracked(rocket().mount("/", routes![capture])).unwrap();
+    client.get("/capture").dispatch();
+
+    CAPTURED.lock().unwrap().pop().unwrap()
+}
+
+fn new_session() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn inject_meta_tags_from_token_fairing() {
+    let rocket = rocket::build()
+        .attach(token_fairing())
+        .mount("/", routes![page]);
+    let client = rocket::local::blocking::Client::untracked(rocket).unwrap();
+    let session = new_session();
+
+    let body = client
+        .get("/")
+        .private_cookie(Cookie::new("csrf_token", session.clone()))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert!(body.ends_with("</head><body></body></html>"));
+    assert_eq!(meta_content(&body, "csrf-param"), "authenticity_token");
+    assert!(bcrypt::verify(&session, meta_content(&body, "csrf-token")).unwrap());
+}
+
+#[test]
+fn inject_meta_tags_once_from_both_fairings() {
+    let client =
+        rocket::local::blocking::Client::tracked(rocket().attach(token_fairing())).unwrap();
+
+    let body = client.get("/").dispatch().into_string().unwrap();
+
+    assert_eq!(body.matches(r#"<meta name="csrf-token""#).count(), 1);
+    assert_eq!(body.matches(r#"<meta name="csrf-param""#).count(), 1);
+}
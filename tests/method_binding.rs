@@ -0,0 +1,122 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Method, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_method_binding(true),
+        ))
+        .mount(
+            "/",
+            routes![preview, delete_form, verify_get, verify_delete],
+        )
+}
+
+#[get("/preview")]
+fn preview(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/delete-form")]
+fn delete_form(csrf_token: CsrfToken) -> String {
+    csrf_token
+        .authenticity_token_for_method(Method::Delete)
+        .unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify_get(csrf_token: CsrfToken, token: &str) -> Status {
+    status(csrf_token.verify(token))
+}
+
+#[delete("/verify?<token>")]
+fn verify_delete(csrf_token: CsrfToken, token: &str) -> Status {
+    status(csrf_token.verify(token))
+}
+
+fn status<E>(result: Result<(), E>) -> Status {
+    match result {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+fn dispatch<'c>(
+    client: &'c rocket::local::blocking::Client,
+    method: Method,
+    uri: String,
+    session: &str,
+) -> rocket::local::blocking::LocalResponse<'c> {
+    client
+        .req(method, uri)
+        .private_cookie(Cookie::new("csrf_token", session.to_string()))
+        .dispatch()
+}
+
+#[test]
+fn reject_token_minted_for_another_method() {
+    let client = client();
+    let session = session_token();
+    let token = dispatch(&client, Method::Get, "/preview".into(), &session)
+        .into_string()
+        .unwrap();
+
+    let uri = format!("/verify?token={}", token);
+    assert_eq!(
+        dispatch(&client, Method::Get, uri.clone(), &session).status(),
+        Status::Ok
+    );
+    assert_eq!(
+        dispatch(&client, Method::Delete, uri, &session).status(),
+        Status::Forbidden
+    );
+}
+
+#[test]
+fn accept_token_minted_for_the_request_method() {
+    let client = client();
+    let session = session_token();
+    let token = dispatch(&client, Method::Get, "/delete-form".into(), &session)
+        .into_string()
+        .unwrap();
+
+    let uri = format!("/verify?token={}", token);
+    assert_eq!(
+        dispatch(&client, Method::Delete, uri.clone(), &session).status(),
+        Status::Ok
+    );
+    assert_eq!(
+        dispatch(&client, Method::Get, uri, &session).status(),
+        Status::Forbidden
+    );
+}
+
+#[test]
+fn reject_unbound_token() {
+    let client = client();
+    let session = session_token();
+    let token = bcrypt::hash(&session, 4).unwrap();
+
+    let uri = format!("/verify?token={}", token);
+    assert_eq!(
+        dispatch(&client, Method::Delete, uri, &session).status(),
+        Status::Forbidden
+    );
+}
@@ -0,0 +1,82 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::Status;
+use rocket::State;
+use rocket_csrf_token::{CsrfToken, Fairing};
+
+fn client(fairing: Fairing) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(fairing)).unwrap()
+}
+
+fn rocket(fairing: Fairing) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .manage(fairing.clone())
+        .attach(fairing)
+        .mount("/", routes![index, check, metrics])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+#[get("/metrics")]
+fn metrics(fairing: &State<Fairing>) -> String {
+    let metrics = fairing.metrics();
+    format!(
+        "issued={} verified={} rejected={}",
+        metrics.issued, metrics.verified, metrics.rejected
+    )
+}
+
+#[test]
+fn count_issued_verified_and_rejected_tokens() {
+    let fairing = Fairing::default();
+    let handle = fairing.clone();
+    let client = client(fairing);
+
+    let token = client.get("/").dispatch().into_string().unwrap();
+    for _ in 0..2 {
+        let status = client
+            .get(format!("/check?token={}", token))
+            .dispatch()
+            .status();
+        assert_eq!(status, Status::Ok);
+    }
+    let status = client.get("/check?token=garbage").dispatch().status();
+    assert_eq!(status, Status::Forbidden);
+
+    let metrics = handle.metrics();
+    assert_eq!(metrics.issued, 1);
+    assert_eq!(metrics.verified, 2);
+    assert_eq!(metrics.rejected, 1);
+
+    assert_eq!(
+        client.get("/metrics").dispatch().into_string().unwrap(),
+        "issued=1 verified=2 rejected=1"
+    );
+}
+
+#[test]
+fn count_a_cookie_issued_to_each_new_client() {
+    let fairing = Fairing::default();
+    let handle = fairing.clone();
+    let client = client(fairing);
+
+    client.get("/").dispatch();
+    client.get("/").dispatch();
+    assert_eq!(handle.metrics().issued, 1);
+
+    let other = rocket::local::blocking::Client::tracked(rocket(handle.clone())).unwrap();
+    other.get("/").dispatch();
+    assert_eq!(handle.metrics().issued, 2);
+}
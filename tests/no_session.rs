@@ -0,0 +1,138 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket::Request;
+use rocket_csrf_token::{
+    AuthenticityToken, CsrfConfig, CsrfContext, CsrfError, CsrfRequestExt, CsrfToken,
+};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(config)).unwrap()
+}
+
+fn rocket(config: CsrfConfig) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![describe, submit, form, context])
+        .register("/", catchers![rejected])
+}
+
+#[post("/describe?<token>")]
+fn describe(csrf_token: CsrfToken, token: &str) -> String {
+    format!("{:?}", csrf_token.verify(token))
+}
+
+#[post("/?<token>")]
+fn submit(csrf_token: CsrfToken, token: &str) -> Result<&'static str, CsrfError> {
+    csrf_token.verify(token)?;
+
+    Ok("ok")
+}
+
+#[get("/form")]
+fn form(token: AuthenticityToken) -> String {
+    token.into_inner()
+}
+
+#[get("/context")]
+fn context(csrf_token: CsrfContext<'_>) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[catch(default)]
+fn rejected(request: &Request) -> String {
+    format!("{:?}", request.csrf_error())
+}
+
+/// A well-formed token minted for a session the server never issued.
+fn foreign_token() -> String {
+    bcrypt::hash("another session", 4)
+        .unwrap()
+        .replace('$', "%24")
+        .replace('/', "%2F")
+}
+
+#[test]
+fn report_no_session_for_empty_cookie_jar() {
+    let client = client(CsrfConfig::default());
+    let body = client
+        .post(format!("/describe?token={}", foreign_token()))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(body, format!("{:?}", Err::<(), _>(CsrfError::NoSession)));
+}
+
+#[test]
+fn report_mismatch_when_session_exists() {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let client = client(CsrfConfig::default());
+    let body = client
+        .post(format!("/describe?token={}", foreign_token()))
+        .private_cookie(Cookie::new(
+            "csrf_token",
+            general_purpose::STANDARD.encode(raw),
+        ))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(body, format!("{:?}", Err::<(), _>(CsrfError::Mismatch)));
+}
+
+#[test]
+fn respond_with_configured_status_and_hint() {
+    let client = client(
+        CsrfConfig::default()
+            .with_no_session_status(Status::PreconditionRequired)
+            .with_refresh_hint(Some("/csrf".to_string())),
+    );
+    let response = client
+        .post(format!("/?token={}", foreign_token()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::PreconditionRequired);
+    assert_eq!(response.headers().get_one("X-CSRF-Refresh"), Some("/csrf"));
+}
+
+#[test]
+fn respond_forbidden_without_session_by_default() {
+    let client = client(CsrfConfig::default());
+    let response = client
+        .post(format!("/?token={}", foreign_token()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Forbidden);
+    assert_eq!(
+        CsrfConfig::default().summary().no_session_status,
+        Status::Forbidden.code
+    );
+}
+
+#[test]
+fn report_no_session_from_token_guards() {
+    // Without the session cookie, a derived session token can't be had.
+    let client = client(
+        CsrfConfig::default()
+            .derive_from_session("session_id")
+            .with_no_session_status(Status::PreconditionRequired),
+    );
+
+    for path in ["/form", "/context"] {
+        let response = client.get(path).dispatch();
+        assert_eq!(response.status(), Status::PreconditionRequired, "{}", path);
+        assert_eq!(
+            response.into_string().unwrap(),
+            format!("{:?}", Some(CsrfError::NoSession)),
+            "{}",
+            path
+        );
+    }
+}
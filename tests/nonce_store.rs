@@ -0,0 +1,123 @@
+#[macro_use]
+extern crate rocket;
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket::local::blocking::Client;
+use rocket::time::Duration;
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, Fairing, MemoryNonceStore, NonceStore};
+
+use base64::{engine::general_purpose, Engine as _};
+
+/// A store shared between nodes, standing in for e.g. Redis.
+#[derive(Default)]
+struct SharedStore(Mutex<HashSet<String>>);
+
+impl NonceStore for SharedStore {
+    fn insert_if_absent(&self, nonce: &str, _ttl: Duration) -> bool {
+        self.0.lock().unwrap().insert(nonce.to_string())
+    }
+}
+
+fn node(config: CsrfConfig) -> Client {
+    let rocket = rocket::build()
+        .attach(Fairing::new(config.with_single_use(true)))
+        .mount("/", routes![check]);
+
+    Client::tracked(rocket).unwrap()
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(CsrfError::Replayed) => "replayed",
+        Err(_) => "other",
+    }
+}
+
+fn new_session() -> (String, String) {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+    (encoded, token)
+}
+
+fn submit(node: &Client, session: &str, token: &str) -> String {
+    node.get(format!(
+        "/check?token={}",
+        token.replace('$', "%24").replace('/', "%2F")
+    ))
+    .private_cookie(Cookie::new("csrf_token", session.to_string()))
+    .dispatch()
+    .into_string()
+    .unwrap()
+}
+
+#[test]
+fn reject_replays_across_nodes_sharing_a_store() {
+    let store = Arc::new(SharedStore::default());
+    let first = node(CsrfConfig::default().with_nonce_store(store.clone()));
+    let second = node(CsrfConfig::default().with_nonce_store(store));
+    let (session, token) = new_session();
+
+    assert_eq!(submit(&first, &session, &token), "ok");
+    assert_eq!(submit(&second, &session, &token), "replayed");
+    assert_eq!(submit(&first, &session, &token), "replayed");
+
+    // Another token minted from the same session is still accepted once.
+    let another = bcrypt::hash(&session, 4).unwrap();
+    assert_eq!(submit(&second, &session, &another), "ok");
+}
+
+#[test]
+fn reject_replays_with_in_memory_store() {
+    let node = node(CsrfConfig::default());
+    let (session, token) = new_session();
+
+    assert_eq!(submit(&node, &session, &token), "ok");
+    assert_eq!(submit(&node, &session, &token), "replayed");
+}
+
+#[test]
+fn accept_reused_tokens_by_default() {
+    let rocket = rocket::build()
+        .attach(Fairing::default())
+        .mount("/", routes![check]);
+    let node = Client::tracked(rocket).unwrap();
+    let (session, token) = new_session();
+
+    assert_eq!(submit(&node, &session, &token), "ok");
+    assert_eq!(submit(&node, &session, &token), "ok");
+}
+
+#[test]
+fn report_single_use_in_summary() {
+    assert!(
+        CsrfConfig::default()
+            .with_single_use(true)
+            .summary()
+            .single_use
+    );
+    assert!(!CsrfConfig::default().summary().single_use);
+}
+
+#[test]
+fn forget_expired_nonces_in_memory() {
+    let store = MemoryNonceStore::default();
+
+    assert!(store.insert_if_absent("kept", Duration::minutes(5)));
+    assert!(store.insert_if_absent("expired", Duration::ZERO));
+    assert!(store.insert_if_absent("expired", Duration::ZERO));
+
+    // Enough expired nonces to trigger pruning, which keeps the live ones.
+    for i in 0..5000 {
+        assert!(store.insert_if_absent(&i.to_string(), Duration::ZERO));
+    }
+    assert!(!store.insert_if_absent("kept", Duration::minutes(5)));
+}
@@ -0,0 +1,96 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{ContentType, Cookie};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, TokenSource};
+
+use base64::{engine::general_purpose, Engine as _};
+
+struct Verified(Result<TokenSource, CsrfError>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Verified {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        request
+            .guard::<CsrfToken>()
+            .await
+            .map(|csrf_token| Verified(csrf_token.verify_request(request)))
+    }
+}
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![submit]);
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[post("/", data = "<_body>")]
+fn submit(verified: Verified, _body: String) -> String {
+    format!("{:?}", verified.0)
+}
+
+fn submit_field(config: CsrfConfig, field: &str) -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+
+    let client = client(config);
+    let response = client
+        .post("/")
+        .header(ContentType::Form)
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .body(format!(
+            "{}={}&comment=hi",
+            field,
+            token.replace('$', "%24").replace('/', "%2F")
+        ))
+        .dispatch();
+
+    response.into_string().unwrap()
+}
+
+#[test]
+fn default_param_name() {
+    assert_eq!(CsrfConfig::default().param_name(), "authenticity_token");
+    assert_eq!(
+        CsrfConfig::default().summary().param_name,
+        "authenticity_token"
+    );
+}
+
+#[test]
+fn return_configured_param_name() {
+    let config = CsrfConfig::default().with_param_name("_csrf");
+
+    assert_eq!(config.param_name(), "_csrf");
+    assert_eq!(config.summary().param_name, "_csrf");
+    assert_eq!(config.summary().form_field, "_csrf");
+}
+
+#[test]
+fn read_form_field_under_configured_param_name() {
+    let config = CsrfConfig::default().with_param_name("_csrf");
+
+    assert_eq!(
+        submit_field(config, "_csrf"),
+        format!("{:?}", Ok::<_, CsrfError>(TokenSource::Form))
+    );
+}
+
+#[test]
+fn ignore_default_field_once_renamed() {
+    let config = CsrfConfig::default().with_param_name("_csrf");
+
+    assert_eq!(
+        submit_field(config, "authenticity_token"),
+        format!("{:?}", Err::<TokenSource, _>(CsrfError::Missing))
+    );
+}
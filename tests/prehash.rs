@@ -0,0 +1,97 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken, PrehashAlgo};
+use sha2::{Digest, Sha256, Sha512};
+
+use base64::{engine::general_purpose, Engine as _};
+
+const COOKIE_LEN: usize = 96;
+
+fn client(algo: PrehashAlgo) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(algo)).unwrap()
+}
+
+fn rocket(algo: PrehashAlgo) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default()
+                .with_cookie_len(COOKIE_LEN)
+                .with_prehash(algo),
+        ))
+        .mount("/", routes![index, verify])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn prehashed(algo: PrehashAlgo, session: &str) -> String {
+    match algo {
+        PrehashAlgo::Sha256 => general_purpose::STANDARD.encode(Sha256::digest(session)),
+        PrehashAlgo::Sha512 => general_purpose::STANDARD.encode(Sha512::digest(session)),
+        _ => session.to_string(),
+    }
+}
+
+fn assert_consistent(algo: PrehashAlgo) {
+    let mut raw = [0u8; COOKIE_LEN];
+    rand::thread_rng().fill_bytes(&mut raw);
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let secret = prehashed(algo, &encoded);
+
+    let client = client(algo);
+    let minted = client
+        .get("/")
+        .private_cookie(Cookie::new("csrf_token", encoded.clone()))
+        .dispatch()
+        .into_string()
+        .unwrap();
+    assert!(bcrypt::verify(&secret, &minted).unwrap());
+
+    let token = bcrypt::hash(&secret, 4).unwrap();
+    let status = client
+        .get(format!("/verify?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn verify_tokens_prehashed_with_sha256() {
+    assert_consistent(PrehashAlgo::Sha256);
+}
+
+#[test]
+fn verify_tokens_prehashed_with_sha512() {
+    assert_consistent(PrehashAlgo::Sha512);
+}
+
+#[test]
+fn verify_tokens_without_prehash() {
+    assert_consistent(PrehashAlgo::None);
+}
+
+#[test]
+fn report_prehash_in_summary() {
+    assert_eq!(CsrfConfig::default().summary().prehash, PrehashAlgo::Sha256);
+    assert_eq!(
+        CsrfConfig::default()
+            .with_prehash(PrehashAlgo::Sha512)
+            .summary()
+            .prehash,
+        PrehashAlgo::Sha512
+    );
+}
@@ -0,0 +1,148 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_csrf_token::{CsrfConfig, CsrfRequestExt};
+
+/// Reports whether the request must carry a token.
+struct Required(bool);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Required {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(Required(request.csrf_token_required()))
+    }
+}
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    let config = CsrfConfig::default().with_protected_get_paths(vec!["/items/delete".to_string()]);
+
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![delete, list, create])
+}
+
+#[get("/items/delete")]
+fn delete(required: Required) -> String {
+    required.0.to_string()
+}
+
+#[get("/items")]
+fn list(required: Required) -> String {
+    required.0.to_string()
+}
+
+#[post("/items")]
+fn create(required: Required) -> String {
+    required.0.to_string()
+}
+
+#[test]
+fn require_token_on_protected_get_path() {
+    let client = client();
+
+    assert_eq!(
+        client
+            .get("/items/delete")
+            .dispatch()
+            .into_string()
+            .unwrap(),
+        "true"
+    );
+    assert_eq!(
+        client.get("/items").dispatch().into_string().unwrap(),
+        "false"
+    );
+    assert_eq!(
+        client.post("/items").dispatch().into_string().unwrap(),
+        "true"
+    );
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use rand::RngCore;
+    use rocket::http::{ContentType, Cookie, Header, Status};
+    use rocket_csrf_token::{CsrfConfig, CsrfJson};
+
+    use base64::{engine::general_purpose, Engine as _};
+
+    fn client() -> rocket::local::blocking::Client {
+        let config =
+            CsrfConfig::default().with_protected_get_paths(vec!["/items/delete".to_string()]);
+        let rocket = rocket::build()
+            .attach(rocket_csrf_token::Fairing::new(config))
+            .mount("/", routes![delete, list]);
+
+        rocket::local::blocking::Client::tracked(rocket).unwrap()
+    }
+
+    #[get("/items/delete", data = "<ids>")]
+    fn delete(ids: CsrfJson<Vec<u32>>) -> String {
+        format!("deleted {}", ids.len())
+    }
+
+    #[get("/items", data = "<ids>")]
+    fn list(ids: CsrfJson<Vec<u32>>) -> String {
+        format!("listed {}", ids.len())
+    }
+
+    fn session() -> (String, String) {
+        let mut raw = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut raw);
+        let encoded = general_purpose::STANDARD.encode(raw);
+        let token = bcrypt::hash(&encoded, 4).unwrap();
+
+        (encoded, token)
+    }
+
+    #[test]
+    fn enforce_token_on_protected_get_path() {
+        let client = client();
+        let (encoded, token) = session();
+
+        let rejected = client
+            .get("/items/delete")
+            .header(ContentType::JSON)
+            .private_cookie(Cookie::new("csrf_token", encoded.clone()))
+            .body("[1, 2]")
+            .dispatch();
+        assert_eq!(rejected.status(), Status::Forbidden);
+
+        let accepted = client
+            .get("/items/delete")
+            .header(ContentType::JSON)
+            .header(Header::new("X-CSRF-Token", token))
+            .private_cookie(Cookie::new("csrf_token", encoded))
+            .body("[1, 2]")
+            .dispatch();
+        assert_eq!(accepted.into_string().unwrap(), "deleted 2");
+    }
+
+    #[test]
+    fn exempt_other_get_paths() {
+        let client = client();
+        let response = client
+            .get("/items")
+            .header(ContentType::JSON)
+            .body("[1, 2, 3]")
+            .dispatch();
+
+        assert_eq!(response.into_string().unwrap(), "listed 3");
+    }
+}
+
+#[test]
+fn report_protected_get_paths_in_summary() {
+    let summary = CsrfConfig::default()
+        .with_protected_get_paths(vec!["/items/delete".to_string()])
+        .summary();
+
+    assert_eq!(summary.protected_get_paths, ["/items/delete"]);
+}
@@ -0,0 +1,68 @@
+#[macro_use]
+extern crate rocket;
+
+mod common;
+
+use common::LOGGER;
+use rocket::http::Method;
+use rocket_csrf_token::CsrfConfig;
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![show, create, update]);
+
+    rocket::local::blocking::Client::untracked(rocket).unwrap()
+}
+
+#[get("/")]
+fn show() -> &'static str {
+    "show"
+}
+
+#[post("/")]
+fn create() -> &'static str {
+    "create"
+}
+
+#[put("/")]
+fn update() -> &'static str {
+    "update"
+}
+
+fn logged_failure(config: CsrfConfig, method: Method) -> bool {
+    let client = client(config);
+    LOGGER.clear();
+    client.req(method, "/").dispatch();
+
+    LOGGER.captured("X-CSRF-Token")
+}
+
+// A single test, since the logger is global to the test binary.
+#[test]
+fn verify_only_protected_methods() {
+    LOGGER.install();
+
+    for method in [Method::Get, Method::Head, Method::Options] {
+        assert!(!logged_failure(CsrfConfig::default(), method));
+    }
+    for method in [Method::Post, Method::Put] {
+        assert!(logged_failure(CsrfConfig::default(), method));
+    }
+
+    let put_only = || CsrfConfig::default().with_protected_methods(vec![Method::Put]);
+    assert!(!logged_failure(put_only(), Method::Post));
+    assert!(logged_failure(put_only(), Method::Put));
+
+    assert_eq!(
+        CsrfConfig::default().summary().protected_methods,
+        [
+            Method::Post,
+            Method::Put,
+            Method::Patch,
+            Method::Delete,
+            Method::Connect
+        ]
+    );
+    assert_eq!(put_only().summary().protected_methods, [Method::Put]);
+}
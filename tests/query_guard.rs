@@ -0,0 +1,80 @@
+#[macro_use]
+extern crate rocket;
+
+use bcrypt::hash;
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfQuery};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(query_tokens: bool) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(query_tokens)).unwrap()
+}
+
+fn rocket(query_tokens: bool) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_query_tokens(query_tokens),
+        ))
+        .mount("/", routes![archive])
+}
+
+#[derive(FromForm)]
+struct Archive {
+    id: u32,
+}
+
+#[get("/archive")]
+fn archive(query: CsrfQuery<Archive>) -> String {
+    query.id.to_string()
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn accept_valid_query_token() {
+    let session = session_token();
+    let token = hash(&session, 4).unwrap();
+
+    let client = client(true);
+    let response = client
+        .get(format!("/archive?id=7&authenticity_token={}", token))
+        .private_cookie(Cookie::new("csrf_token", session))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "7");
+}
+
+#[test]
+fn reject_invalid_query_token() {
+    let token = hash(session_token(), 4).unwrap();
+
+    let status = client(true)
+        .get(format!("/archive?id=7&authenticity_token={}", token))
+        .private_cookie(Cookie::new("csrf_token", session_token()))
+        .dispatch()
+        .status();
+
+    assert_eq!(status, Status::Forbidden);
+}
+
+#[test]
+fn reject_query_token_unless_enabled() {
+    let session = session_token();
+    let token = hash(&session, 4).unwrap();
+
+    let status = client(false)
+        .get(format!("/archive?id=7&authenticity_token={}", token))
+        .private_cookie(Cookie::new("csrf_token", session))
+        .dispatch()
+        .status();
+
+    assert_eq!(status, Status::Forbidden);
+}
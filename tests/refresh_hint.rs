@@ -0,0 +1,73 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, Fairing};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(hint: Option<&str>) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(hint)).unwrap()
+}
+
+fn rocket(hint: Option<&str>) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(Fairing::new(
+            CsrfConfig::default().with_refresh_hint(hint.map(String::from)),
+        ))
+        .mount("/", routes![index])
+}
+
+#[get("/?<token>")]
+fn index(csrf_token: CsrfToken, token: &str) -> Result<&'static str, CsrfError> {
+    csrf_token.verify(token).map(|()| "ok")
+}
+
+fn session() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn include_refresh_hint_when_rejecting_expired_token() {
+    let client = client(Some("/csrf"));
+    // A token minted for a session that has since expired.
+    let stale = bcrypt::hash(session(), 4).unwrap();
+
+    let response = client
+        .get(format!("/?token={}", stale))
+        .private_cookie(Cookie::new("csrf_token", session()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Forbidden);
+    assert_eq!(response.headers().get_one("X-CSRF-Refresh"), Some("/csrf"));
+}
+
+#[test]
+fn omit_refresh_hint_for_malformed_token() {
+    let client = client(Some("/csrf"));
+    let response = client
+        .get("/?token=garbage")
+        .private_cookie(Cookie::new("csrf_token", session()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Forbidden);
+    assert_eq!(response.headers().get_one("X-CSRF-Refresh"), None);
+}
+
+#[test]
+fn omit_refresh_hint_by_default() {
+    let client = client(None);
+    let stale = bcrypt::hash(session(), 4).unwrap();
+
+    let response = client
+        .get(format!("/?token={}", stale))
+        .private_cookie(Cookie::new("csrf_token", session()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Forbidden);
+    assert_eq!(response.headers().get_one("X-CSRF-Refresh"), None);
+}
@@ -0,0 +1,120 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Header, Status};
+use rocket::Request;
+use rocket_csrf_token::{CsrfConfig, CsrfRequestExt, Fairing};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(Fairing::new(config))
+        .mount("/", routes![index, create])
+        .register("/", catchers![forbidden]);
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[get("/")]
+fn index() -> &'static str {
+    "index"
+}
+
+#[post("/comments", data = "<body>")]
+fn create(body: String) -> String {
+    format!("created {}", body)
+}
+
+#[catch(403)]
+fn forbidden(request: &Request) -> String {
+    match request.csrf_error() {
+        Some(err) => format!("rejected: {}", err),
+        None => "forbidden".to_string(),
+    }
+}
+
+fn new_session() -> (String, String) {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+    (encoded, token)
+}
+
+#[test]
+fn reject_post_without_token() {
+    let (session, _) = new_session();
+    let client = client(CsrfConfig::default().with_reject_invalid(true));
+
+    let response = client
+        .post("/comments")
+        .private_cookie(Cookie::new("csrf_token", session))
+        .body("hi")
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Forbidden);
+    assert_eq!(
+        response.into_string().unwrap(),
+        "rejected: CSRF token is missing"
+    );
+}
+
+#[test]
+fn reject_post_with_invalid_token() {
+    let (session, _) = new_session();
+    let (_, stale) = new_session();
+    let client = client(CsrfConfig::default().with_reject_invalid(true));
+
+    let response = client
+        .post("/comments")
+        .header(Header::new("X-CSRF-Token", stale))
+        .private_cookie(Cookie::new("csrf_token", session))
+        .body("hi")
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Forbidden);
+}
+
+#[test]
+fn forward_post_with_valid_token() {
+    let (session, token) = new_session();
+    let client = client(CsrfConfig::default().with_reject_invalid(true));
+
+    let response = client
+        .post("/comments")
+        .header(Header::new("X-CSRF-Token", token))
+        .private_cookie(Cookie::new("csrf_token", session))
+        .body("hi")
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "created hi");
+}
+
+#[test]
+fn leave_safe_methods_alone() {
+    let client = client(CsrfConfig::default().with_reject_invalid(true));
+
+    let response = client.get("/").dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "index");
+}
+
+#[test]
+fn let_handlers_decide_by_default() {
+    let (session, _) = new_session();
+    let client = client(CsrfConfig::default());
+
+    let response = client
+        .post("/comments")
+        .private_cookie(Cookie::new("csrf_token", session))
+        .body("hi")
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert!(!CsrfConfig::default().summary().reject_invalid);
+}
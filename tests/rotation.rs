@@ -0,0 +1,81 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::{CookieJar, Status};
+use rocket::time::Duration;
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+fn client(grace: Option<Duration>) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(grace)).unwrap()
+}
+
+fn rocket(grace: Option<Duration>) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_rotation_grace(grace),
+        ))
+        .mount("/", routes![index, token, rotate, check])
+}
+
+#[get("/")]
+fn index() {}
+
+#[get("/token")]
+fn token(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[post("/rotate")]
+fn rotate(csrf_token: CsrfToken, cookies: &CookieJar<'_>) -> String {
+    csrf_token.rotate(cookies).authenticity_token().unwrap()
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn check_status(client: &rocket::local::blocking::Client, token: &str) -> Status {
+    client
+        .get(format!("/check?token={}", token))
+        .dispatch()
+        .status()
+}
+
+#[test]
+fn accept_token_minted_before_rotation_within_grace_window() {
+    let client = client(Some(Duration::hours(1)));
+    client.get("/").dispatch();
+
+    let old_token = client.get("/token").dispatch().into_string().unwrap();
+    let new_token = client.post("/rotate").dispatch().into_string().unwrap();
+
+    assert_eq!(check_status(&client, &old_token), Status::Ok);
+    assert_eq!(check_status(&client, &new_token), Status::Ok);
+}
+
+#[test]
+fn reject_token_minted_before_rotation_after_grace_window() {
+    let client = client(Some(Duration::ZERO));
+    client.get("/").dispatch();
+
+    let old_token = client.get("/token").dispatch().into_string().unwrap();
+    let new_token = client.post("/rotate").dispatch().into_string().unwrap();
+
+    assert_eq!(check_status(&client, &old_token), Status::Forbidden);
+    assert_eq!(check_status(&client, &new_token), Status::Ok);
+}
+
+#[test]
+fn reject_token_minted_before_rotation_without_grace_window() {
+    let client = client(None);
+    client.get("/").dispatch();
+
+    let old_token = client.get("/token").dispatch().into_string().unwrap();
+    client.post("/rotate").dispatch();
+
+    assert_eq!(check_status(&client, &old_token), Status::Forbidden);
+}
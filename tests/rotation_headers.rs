@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::{CookieJar, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(config)).unwrap()
+}
+
+fn rocket(config: CsrfConfig) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(Fairing::new(config))
+        .mount("/", routes![index, token_id, renew])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/id")]
+fn token_id(csrf_token: CsrfToken) -> String {
+    csrf_token.token_id()
+}
+
+#[post("/renew?<token>")]
+fn renew(csrf_token: CsrfToken, token: &str, cookies: &CookieJar<'_>) -> Status {
+    match csrf_token.verify_and_renew(token, cookies) {
+        Ok(_) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+#[test]
+fn announce_both_tokens_after_rotation() {
+    let client = client(CsrfConfig::default().with_rotation_headers(true));
+    let token = client.get("/").dispatch().into_string().unwrap();
+    let previous_id = client.get("/id").dispatch().into_string().unwrap();
+
+    let response = client.post(format!("/renew?token={}", token)).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.headers().get_one("X-CSRF-Previous-Token-Id"),
+        Some(previous_id.as_str())
+    );
+    let current_id = response
+        .headers()
+        .get_one("X-CSRF-Token-Id")
+        .unwrap()
+        .to_string();
+    assert_ne!(current_id, previous_id);
+    assert_eq!(current_id.len(), 16);
+
+    assert_eq!(
+        client.get("/id").dispatch().into_string().unwrap(),
+        current_id
+    );
+}
+
+#[test]
+fn omit_headers_without_rotation() {
+    let client = client(CsrfConfig::default().with_rotation_headers(true));
+    // A freshly issued cookie replaces nothing.
+    let response = client.get("/").dispatch();
+    assert!(response.headers().get_one("X-CSRF-Token-Id").is_none());
+
+    let response = client.post("/renew?token=garbage").dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    assert!(response
+        .headers()
+        .get_one("X-CSRF-Previous-Token-Id")
+        .is_none());
+    assert!(response.headers().get_one("X-CSRF-Token-Id").is_none());
+}
+
+#[test]
+fn omit_headers_by_default() {
+    let client = client(CsrfConfig::default());
+    let token = client.get("/").dispatch().into_string().unwrap();
+
+    let response = client.post(format!("/renew?token={}", token)).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.headers().get_one("X-CSRF-Token-Id").is_none());
+}
@@ -0,0 +1,79 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_route_scoping(true),
+        ))
+        .mount("/", routes![transfer_form, transfer, delete])
+}
+
+#[get("/transfer")]
+fn transfer_form(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[post("/transfer?<token>")]
+fn transfer(csrf_token: CsrfToken, token: &str) -> Status {
+    verify(csrf_token, token)
+}
+
+#[post("/delete?<token>")]
+fn delete(csrf_token: CsrfToken, token: &str) -> Status {
+    verify(csrf_token, token)
+}
+
+fn verify(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+fn mint_and_submit(target: &str) -> Status {
+    let client = client();
+    let session = session_token();
+
+    let token = client
+        .get("/transfer")
+        .private_cookie(Cookie::new("csrf_token", session.clone()))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    let status = client
+        .post(format!("{}?token={}", target, token))
+        .private_cookie(Cookie::new("csrf_token", session))
+        .dispatch()
+        .status();
+
+    status
+}
+
+#[test]
+fn accept_token_on_the_route_it_was_minted_for() {
+    assert_eq!(mint_and_submit("/transfer"), Status::Ok);
+}
+
+#[test]
+fn reject_token_replayed_against_another_route() {
+    assert_eq!(mint_and_submit("/delete"), Status::Forbidden);
+}
@@ -0,0 +1,177 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Header, Method};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::time::Duration;
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, TokenSource};
+
+use base64::{engine::general_purpose, Engine as _};
+
+struct Verified(Result<TokenSource, CsrfError>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Verified {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        request
+            .guard::<CsrfToken>()
+            .await
+            .map(|csrf_token| Verified(csrf_token.verify_request(request)))
+    }
+}
+
+fn client(enabled: bool) -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default()
+                .with_same_origin_shortcut(enabled)
+                .with_step_up(Duration::minutes(5)),
+        ))
+        .mount(
+            "/",
+            routes![submit, replace, verify, verify_for, verify_step_up],
+        );
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[post("/")]
+fn submit(verified: Verified) -> String {
+    format!("{:?}", verified.0)
+}
+
+#[put("/")]
+fn replace(verified: Verified) -> String {
+    format!("{:?}", verified.0)
+}
+
+#[post("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> String {
+    format!("{:?}", csrf_token.verify(token))
+}
+
+#[post("/verify-for?<token>")]
+fn verify_for(csrf_token: CsrfToken, token: &str) -> String {
+    format!("{:?}", csrf_token.verify_for(token, "delete-account"))
+}
+
+#[post("/verify-step-up?<token>")]
+fn verify_step_up(csrf_token: CsrfToken, token: &str) -> String {
+    format!("{:?}", csrf_token.verify_step_up(token))
+}
+
+fn session() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+/// Dispatches a request without an authenticity token, carrying the given `Sec-Fetch-Site`
+/// headers.
+fn dispatch(enabled: bool, method: Method, sites: &[&'static str]) -> String {
+    let client = client(enabled);
+    let mut request = client
+        .req(method, "/")
+        .private_cookie(Cookie::new("csrf_token", session()));
+    for site in sites {
+        request.add_header(Header::new("Sec-Fetch-Site", *site));
+    }
+
+    request.dispatch().into_string().unwrap()
+}
+
+fn ok(source: TokenSource) -> String {
+    format!("{:?}", Ok::<_, CsrfError>(source))
+}
+
+fn missing() -> String {
+    format!("{:?}", Err::<TokenSource, _>(CsrfError::Missing))
+}
+
+#[test]
+fn skip_verification_for_same_origin_post() {
+    assert_eq!(
+        dispatch(true, Method::Post, &["same-origin"]),
+        ok(TokenSource::FetchMetadata)
+    );
+}
+
+/// Posts `uri` as a same-origin request of a fresh session.
+fn same_origin_post(uri: String) -> String {
+    client(true)
+        .post(uri)
+        .header(Header::new("Sec-Fetch-Site", "same-origin"))
+        .private_cookie(Cookie::new("csrf_token", session()))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn verify_given_tokens_fully_for_same_origin_request() {
+    assert_eq!(
+        same_origin_post("/verify?token=garbage".into()),
+        "Err(Malformed)"
+    );
+
+    let foreign = bcrypt::hash(session(), 4).unwrap();
+    let foreign = foreign.replace('$', "%24").replace('/', "%2F");
+    assert_eq!(
+        same_origin_post(format!("/verify-for?token={}", foreign)),
+        "Err(Mismatch)"
+    );
+
+    let now = rocket::time::OffsetDateTime::now_utc().unix_timestamp();
+    assert_eq!(
+        same_origin_post(format!("/verify-step-up?token={}.garbage", now)),
+        "Err(Malformed)"
+    );
+    assert_eq!(
+        same_origin_post(format!("/verify-step-up?token={}.{}", now, foreign)),
+        "Err(Mismatch)"
+    );
+}
+
+#[test]
+fn verify_fully_when_disabled() {
+    assert_eq!(dispatch(false, Method::Post, &["same-origin"]), missing());
+}
+
+#[test]
+fn verify_fully_for_other_sites() {
+    for site in ["same-site", "cross-site", "none", "Same-Origin"] {
+        assert_eq!(dispatch(true, Method::Post, &[site]), missing(), "{}", site);
+    }
+    assert_eq!(dispatch(true, Method::Post, &[]), missing());
+}
+
+#[test]
+fn verify_fully_for_repeated_header() {
+    assert_eq!(
+        dispatch(true, Method::Post, &["same-origin", "cross-site"]),
+        missing()
+    );
+}
+
+#[test]
+fn verify_fully_for_other_methods() {
+    assert_eq!(dispatch(true, Method::Put, &["same-origin"]), missing());
+}
+
+#[test]
+fn verify_submitted_token_fully_for_cross_site_request() {
+    let client = client(true);
+    let body = client
+        .post("/verify?token=garbage")
+        .header(Header::new("Sec-Fetch-Site", "cross-site"))
+        .private_cookie(Cookie::new("csrf_token", session()))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(body, "Err(Malformed)");
+}
@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::SameSite;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+#[get("/")]
+fn index(_csrf_token: CsrfToken) {}
+
+fn issued_cookie_header(config: CsrfConfig) -> String {
+    let client = rocket::local::blocking::Client::untracked(
+        rocket::build()
+            .attach(Fairing::new(config))
+            .mount("/", routes![index]),
+    )
+    .unwrap();
+
+    let response = client.get("/").dispatch();
+    let header = response
+        .headers()
+        .get("Set-Cookie")
+        .find(|header| header.starts_with("csrf_token="))
+        .unwrap()
+        .to_string();
+    header
+}
+
+fn same_site_attribute(header: &str) -> Option<&str> {
+    header
+        .split(';')
+        .map(str::trim)
+        .find_map(|attribute| attribute.strip_prefix("SameSite="))
+}
+
+#[test]
+fn issue_strict_cookie_by_default() {
+    let header = issued_cookie_header(CsrfConfig::default());
+
+    assert_eq!(same_site_attribute(&header), Some("Strict"));
+}
+
+#[test]
+fn issue_cookie_under_configured_policy() {
+    for (policy, attribute) in [
+        (SameSite::Strict, "Strict"),
+        (SameSite::Lax, "Lax"),
+        (SameSite::None, "None"),
+    ] {
+        let header = issued_cookie_header(CsrfConfig::default().with_same_site(policy));
+
+        assert_eq!(same_site_attribute(&header), Some(attribute), "{}", header);
+    }
+}
@@ -0,0 +1,86 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::{Header, SameSite};
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+const CHROME_60: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/60.0.3112.113 Safari/537.36";
+const IOS_12: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 12_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/12.0 Mobile/15E148 Safari/604.1";
+const UC_BROWSER_12_13_0: &str = "Mozilla/5.0 (Linux; U; Android 8.0.0; en-US) AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/57.0.2987.108 UCBrowser/12.13.0.1207 Mobile Safari/537.36";
+const CHROME_120: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+const MACOS_10_14_CHROME: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_14_6) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![index]);
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[get("/")]
+fn index(_csrf_token: CsrfToken) -> &'static str {
+    "ok"
+}
+
+/// Returns the `Set-Cookie` header of the CSRF cookie sent to the given user agent.
+fn set_cookie(config: CsrfConfig, user_agent: &'static str) -> String {
+    let client = client(config);
+    let response = client
+        .get("/")
+        .header(Header::new("User-Agent", user_agent))
+        .dispatch();
+
+    assert!(response.cookies().get_private("csrf_token").is_some());
+    let header = response
+        .headers()
+        .get("Set-Cookie")
+        .find(|value| value.starts_with("csrf_token="))
+        .unwrap()
+        .to_string();
+
+    header
+}
+
+fn fallback() -> CsrfConfig {
+    CsrfConfig::default()
+        .with_same_site(SameSite::None)
+        .with_same_site_none_fallback(true)
+}
+
+#[test]
+fn omit_same_site_for_legacy_browsers() {
+    for user_agent in [CHROME_60, IOS_12, UC_BROWSER_12_13_0] {
+        let header = set_cookie(fallback(), user_agent);
+        assert!(!header.contains("SameSite"), "{}: {}", user_agent, header);
+    }
+}
+
+#[test]
+fn keep_same_site_none_for_modern_browsers() {
+    for user_agent in [CHROME_120, MACOS_10_14_CHROME] {
+        let header = set_cookie(fallback(), user_agent);
+        assert!(
+            header.contains("SameSite=None"),
+            "{}: {}",
+            user_agent,
+            header
+        );
+    }
+}
+
+#[test]
+fn keep_same_site_none_when_fallback_is_disabled() {
+    let config = CsrfConfig::default().with_same_site(SameSite::None);
+
+    assert!(set_cookie(config, CHROME_60).contains("SameSite=None"));
+}
+
+#[test]
+fn keep_other_policies_for_legacy_browsers() {
+    let config = CsrfConfig::default()
+        .with_same_site(SameSite::Lax)
+        .with_same_site_none_fallback(true);
+
+    assert!(set_cookie(config, CHROME_60).contains("SameSite=Lax"));
+}
@@ -0,0 +1,131 @@
+#[macro_use]
+extern crate rocket;
+
+use std::sync::Arc;
+
+use rand::RngCore;
+use rocket::http::{Cookie, CookieJar, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+const SSO_COOKIE: &str = "sso_auth";
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    // The SSO cookie looks like `user=<name>&csrf=<base64 secret>`.
+    let config = CsrfConfig::default().with_secret_from_cookie(
+        SSO_COOKIE,
+        Arc::new(|value| {
+            let secret = value
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("csrf="))?;
+            general_purpose::STANDARD.decode(secret).ok()
+        }),
+    );
+
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![index, rotate, verify])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/rotate")]
+fn rotate(csrf_token: CsrfToken, cookies: &CookieJar<'_>) -> String {
+    csrf_token.rotate(cookies).authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn sso_cookie() -> (Cookie<'static>, String) {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let secret = general_purpose::STANDARD.encode(raw);
+    (
+        Cookie::new(SSO_COOKIE, format!("user=alice&csrf={}", secret)),
+        secret,
+    )
+}
+
+#[test]
+fn verify_token_derived_from_sso_cookie() {
+    let (cookie, secret) = sso_cookie();
+    let token = bcrypt::hash(&secret, 4).unwrap();
+
+    let status = client()
+        .get(format!("/verify?token={}", token))
+        .cookie(cookie)
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn mint_tokens_from_sso_cookie_without_issuing_one() {
+    let (cookie, secret) = sso_cookie();
+
+    let client = client();
+    let response = client.get("/").cookie(cookie).dispatch();
+    assert!(response.cookies().get("csrf_token").is_none());
+    let token = response.into_string().unwrap();
+
+    assert!(bcrypt::verify(&secret, &token).unwrap());
+}
+
+#[test]
+fn reject_token_of_another_sso_session() {
+    let (_, secret) = sso_cookie();
+    let (cookie, _) = sso_cookie();
+    let token = bcrypt::hash(&secret, 4).unwrap();
+
+    let status = client()
+        .get(format!("/verify?token={}", token))
+        .cookie(cookie)
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Forbidden);
+}
+
+#[test]
+fn keep_sso_secret_on_rotation() {
+    let (cookie, secret) = sso_cookie();
+
+    let client = client();
+    let response = client.get("/rotate").cookie(cookie.clone()).dispatch();
+    assert!(response.cookies().get("csrf_token").is_none());
+    let token = response.into_string().unwrap();
+
+    assert!(bcrypt::verify(&secret, &token).unwrap());
+
+    let status = client
+        .get(format!("/verify?token={}", token))
+        .cookie(cookie)
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn report_sso_cookie_in_summary() {
+    assert!(CsrfConfig::default().summary().secret_from_cookie.is_none());
+
+    let config = CsrfConfig::default().with_secret_from_cookie(SSO_COOKIE, Arc::new(|_| None));
+    assert_eq!(
+        config.summary().secret_from_cookie.as_deref(),
+        Some(SSO_COOKIE)
+    );
+}
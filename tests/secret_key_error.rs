@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket_csrf_token::{CsrfError, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![index])
+}
+
+#[get("/?<token>")]
+fn index(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(CsrfError::Mismatch) => "mismatch",
+        Err(CsrfError::SecretKeyError) => "secret key",
+        Err(_) => "other",
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn report_undecryptable_cookie() {
+    let encoded = session_token();
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+
+    // A cookie that wasn't sealed with the current secret key, as after a key change.
+    let body = client()
+        .get(format!("/?token={}", token))
+        .cookie(Cookie::new("csrf_token", encoded))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(body, "secret key");
+}
+
+#[test]
+fn report_mismatch_for_decryptable_cookie() {
+    let token = bcrypt::hash(session_token(), 4).unwrap();
+
+    let body = client()
+        .get(format!("/?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", session_token()))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(body, "mismatch");
+}
@@ -0,0 +1,110 @@
+#[macro_use]
+extern crate rocket;
+
+use hkdf::Hkdf;
+use rocket::http::{Cookie, CookieJar, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+use sha2::Sha256;
+
+use base64::{engine::general_purpose, Engine as _};
+
+const SESSION_COOKIE: &str = "session_id";
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().derive_from_session(SESSION_COOKIE),
+        ))
+        .mount("/", routes![index, rotate, verify])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/rotate")]
+fn rotate(csrf_token: CsrfToken, cookies: &CookieJar<'_>) -> String {
+    csrf_token.rotate(cookies).authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn derived_secret(session_id: &str) -> String {
+    let mut derived = [0u8; 32];
+    Hkdf::<Sha256>::new(None, session_id.as_bytes())
+        .expand(b"rocket_csrf_token:csrf_token", &mut derived)
+        .unwrap();
+
+    general_purpose::STANDARD.encode(derived)
+}
+
+#[test]
+fn round_trip_token_derived_from_session_id() {
+    let client = client();
+    let response = client
+        .get("/")
+        .cookie(Cookie::new(SESSION_COOKIE, "a-session-id"))
+        .dispatch();
+    assert!(response.cookies().get("csrf_token").is_none());
+    let token = response.into_string().unwrap();
+
+    assert!(bcrypt::verify(derived_secret("a-session-id"), &token).unwrap());
+
+    let status = client
+        .get(format!("/verify?token={}", token))
+        .cookie(Cookie::new(SESSION_COOKIE, "a-session-id"))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn reject_token_derived_from_another_session_id() {
+    let token = bcrypt::hash(derived_secret("a-session-id"), 4).unwrap();
+
+    let status = client()
+        .get(format!("/verify?token={}", token))
+        .cookie(Cookie::new(SESSION_COOKIE, "another-session-id"))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Forbidden);
+}
+
+#[test]
+fn issue_no_cookie_without_session() {
+    let client = client();
+    let response = client.get("/verify?token=x").dispatch();
+
+    assert!(response.cookies().get("csrf_token").is_none());
+}
+
+#[test]
+fn keep_derived_token_on_rotation() {
+    let client = client();
+    let response = client
+        .get("/rotate")
+        .cookie(Cookie::new(SESSION_COOKIE, "a-session-id"))
+        .dispatch();
+    assert!(response.cookies().get("csrf_token").is_none());
+    let token = response.into_string().unwrap();
+
+    assert!(bcrypt::verify(derived_secret("a-session-id"), &token).unwrap());
+
+    let status = client
+        .get(format!("/verify?token={}", token))
+        .cookie(Cookie::new(SESSION_COOKIE, "a-session-id"))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
@@ -0,0 +1,121 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{Cookie, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+const SHARED_KEY: [u8; 32] = [42; 32];
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().migrate_to_shared_key(SHARED_KEY),
+        ))
+        .mount("/", routes![index, verify])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn issue_signed_cookies_to_new_clients() {
+    let client = client();
+    let response = client.get("/").dispatch();
+
+    let cookie = response.cookies().get("csrf_token").unwrap();
+    let (encoded, _signature) = cookie.value().rsplit_once('.').unwrap();
+    assert_eq!(general_purpose::STANDARD.decode(encoded).unwrap().len(), 32);
+    assert!(response.cookies().get_private("csrf_token").is_none());
+
+    let token = response.into_string().unwrap();
+    let status = client
+        .get(format!("/verify?token={}", token))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn verify_tokens_of_private_cookies_during_migration() {
+    let session = session_token();
+    let token = bcrypt::hash(&session, 4).unwrap();
+    let client = client();
+
+    let response = client
+        .get(format!("/verify?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", session.clone()))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    // The private cookie is re-issued in signed form, keeping the session token.
+    let cookie = response.cookies().get("csrf_token").unwrap();
+    assert!(cookie.value().starts_with(&format!("{}.", session)));
+
+    let status = client
+        .get(format!("/verify?token={}", token))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn reject_cookies_signed_with_another_key() {
+    let header = CsrfConfig::default()
+        .migrate_to_shared_key([7; 32])
+        .build_cookie_header(&[1; 32])
+        .unwrap();
+    let value = header.split("; ").next().unwrap();
+    let (name, value) = value.split_once('=').unwrap();
+    let session = general_purpose::STANDARD.encode([1u8; 32]);
+    let token = bcrypt::hash(&session, 4).unwrap();
+
+    let status = client()
+        .get(format!("/verify?token={}", token))
+        .cookie(Cookie::new(name.to_string(), value.to_string()))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Forbidden);
+}
+
+#[test]
+fn accept_cookies_built_for_external_use() {
+    let header = CsrfConfig::default()
+        .migrate_to_shared_key(SHARED_KEY)
+        .build_cookie_header(&[1; 32])
+        .unwrap();
+    let value = header.split("; ").next().unwrap();
+    let (name, value) = value.split_once('=').unwrap();
+    let session = general_purpose::STANDARD.encode([1u8; 32]);
+    let token = bcrypt::hash(&session, 4).unwrap();
+
+    let status = client()
+        .get(format!("/verify?token={}", token))
+        .cookie(Cookie::new(name.to_string(), value.to_string()))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
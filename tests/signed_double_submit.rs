@@ -0,0 +1,125 @@
+#[macro_use]
+extern crate rocket;
+
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use rocket::http::{Cookie, Header};
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, Fairing, TokenStrategy};
+use sha2::Sha256;
+
+const SECRET: &[u8] = b"server secret";
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    let strategy = TokenStrategy::SignedDoubleSubmit {
+        secret: SECRET.to_vec(),
+    };
+
+    rocket::build()
+        .attach(Fairing::new(CsrfConfig::default().with_strategy(strategy)))
+        .mount("/", routes![index, verify])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[post("/")]
+fn verify(csrf_token: CsrfToken, header: CsrfHeader<'_>) -> &'static str {
+    match csrf_token.verify(header.0) {
+        Ok(()) => "ok",
+        Err(CsrfError::Malformed) => "malformed",
+        Err(CsrfError::Mismatch) => "mismatch",
+        Err(_) => "other",
+    }
+}
+
+struct CsrfHeader<'r>(&'r str);
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for CsrfHeader<'r> {
+    type Error = ();
+
+    async fn from_request(
+        request: &'r rocket::Request<'_>,
+    ) -> rocket::request::Outcome<Self, Self::Error> {
+        rocket::request::Outcome::Success(CsrfHeader(
+            request
+                .headers()
+                .get_one("X-CSRF-Token")
+                .unwrap_or_default(),
+        ))
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+/// Signs `message` for `session` as an external implementation of the pattern would.
+fn sign(secret: &[u8], session: &str, message: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+    mac.update(&(session.len() as u64).to_be_bytes());
+    mac.update(session.as_bytes());
+    mac.update(message.as_bytes());
+
+    format!(
+        "{}.{}",
+        message,
+        general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+    )
+}
+
+fn submit(client: &rocket::local::blocking::Client, session: &str, token: &str) -> String {
+    client
+        .post("/")
+        .header(Header::new("X-CSRF-Token", token.to_string()))
+        .private_cookie(Cookie::new("csrf_token", session.to_string()))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn accept_valid_signed_pair() {
+    let client = client();
+    let session = session_token();
+    let minted = client
+        .get("/")
+        .private_cookie(Cookie::new("csrf_token", session.clone()))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(submit(&client, &session, &minted), "ok");
+
+    let signed = sign(SECRET, &session, "AAAAAAAAAAAAAAAAAAAAAA");
+    assert_eq!(submit(&client, &session, &signed), "ok");
+}
+
+#[test]
+fn reject_forged_pair() {
+    let client = client();
+    let session = session_token();
+
+    let foreign_secret = sign(b"another secret", &session, "AAAAAAAAAAAAAAAAAAAAAA");
+    assert_eq!(submit(&client, &session, &foreign_secret), "mismatch");
+
+    let (_, mac) = sign(SECRET, &session, "AAAAAAAAAAAAAAAAAAAAAA")
+        .split_once('.')
+        .map(|(message, mac)| (message.to_string(), mac.to_string()))
+        .unwrap();
+    let swapped = format!("BBBBBBBBBBBBBBBBBBBBBB.{}", mac);
+    assert_eq!(submit(&client, &session, &swapped), "mismatch");
+
+    let foreign_session = sign(SECRET, &session_token(), "AAAAAAAAAAAAAAAAAAAAAA");
+    assert_eq!(submit(&client, &session, &foreign_session), "mismatch");
+}
@@ -0,0 +1,111 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket_csrf_token::{Algorithm, CsrfConfig, CsrfError, CsrfToken, Fairing};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(algorithm: Algorithm) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::untracked(
+        rocket::build()
+            .attach(Fairing::new(
+                CsrfConfig::default().with_signing_algorithm(algorithm),
+            ))
+            .mount("/", routes![index, verify]),
+    )
+    .unwrap()
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(CsrfError::Malformed) => "malformed",
+        Err(CsrfError::Mismatch) => "mismatch",
+        Err(_) => "other",
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+fn mint(client: &rocket::local::blocking::Client, session: &str) -> String {
+    client
+        .get("/")
+        .private_cookie(Cookie::new("csrf_token", session.to_string()))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+fn check(client: &rocket::local::blocking::Client, session: &str, token: &str) -> String {
+    client
+        .get(format!(
+            "/verify?token={}",
+            token.replace('$', "%24").replace('/', "%2F")
+        ))
+        .private_cookie(Cookie::new("csrf_token", session.to_string()))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+fn hmac(key: &[u8]) -> Algorithm {
+    Algorithm::Hmac { key: key.to_vec() }
+}
+
+#[test]
+fn round_trip_hmac_tokens() {
+    let client = client(hmac(b"server key"));
+    let session = session_token();
+    let token = mint(&client, &session);
+
+    assert!(!token.starts_with("$2"));
+    assert_eq!(check(&client, &session, &token), "ok");
+}
+
+#[test]
+fn reject_token_signed_with_another_key() {
+    let signer = client(hmac(b"another key"));
+    let verifier = client(hmac(b"server key"));
+    let session = session_token();
+    let token = mint(&signer, &session);
+
+    assert_eq!(check(&verifier, &session, &token), "mismatch");
+}
+
+#[test]
+fn reject_hmac_token_of_another_session() {
+    let client = client(hmac(b"server key"));
+    let token = mint(&client, &session_token());
+
+    assert_eq!(check(&client, &session_token(), &token), "mismatch");
+}
+
+#[test]
+fn sign_with_bcrypt_by_default() {
+    let client = client(Algorithm::default());
+    let session = session_token();
+    let token = mint(&client, &session);
+
+    assert!(token.starts_with("$2b$"));
+    assert_eq!(check(&client, &session, &token), "ok");
+    assert_eq!(
+        CsrfConfig::default()
+            .with_signing_algorithm(hmac(b"server key"))
+            .summary()
+            .strategy,
+        "signed-double-submit"
+    );
+}
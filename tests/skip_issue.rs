@@ -0,0 +1,49 @@
+#[macro_use]
+extern crate rocket;
+
+use std::sync::Arc;
+
+use rocket::http::Header;
+use rocket_csrf_token::{CsrfConfig, Fairing};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    let config = CsrfConfig::default().with_skip_issue_when(Arc::new(|request| {
+        request
+            .headers()
+            .get_one("Authorization")
+            .is_some_and(|value| value.starts_with("Bearer "))
+    }));
+
+    rocket::build()
+        .attach(Fairing::new(config))
+        .mount("/", routes![index])
+}
+
+#[get("/")]
+fn index() -> &'static str {
+    "hello"
+}
+
+#[test]
+fn skip_cookie_for_matching_requests() {
+    let client = client();
+    let response = client
+        .get("/")
+        .header(Header::new("Authorization", "Bearer api-token"))
+        .dispatch();
+
+    assert!(response.cookies().get("csrf_token").is_none());
+    assert_eq!(response.into_string().unwrap(), "hello");
+}
+
+#[test]
+fn issue_cookie_for_other_requests() {
+    let client = client();
+    let response = client.get("/").dispatch();
+
+    assert!(response.cookies().get_private("csrf_token").is_some());
+}
@@ -0,0 +1,119 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket_csrf_token::{CsrfConfig, CsrfToken, TokenStrategy};
+#[cfg(feature = "snapshot")]
+use rocket_csrf_token::{CsrfError, CsrfSnapshot};
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(config)).unwrap()
+}
+
+fn rocket(config: CsrfConfig) -> rocket::Rocket<rocket::Build> {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![index, verify_restored]);
+    #[cfg(feature = "snapshot")]
+    let rocket = rocket.mount("/", routes![snapshot]);
+
+    rocket
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[cfg(feature = "snapshot")]
+#[get("/snapshot")]
+fn snapshot(csrf_token: CsrfToken) -> String {
+    serde_json::to_string(&csrf_token.snapshot().unwrap()).unwrap()
+}
+
+#[get("/verify-restored?<token>")]
+fn verify_restored(csrf_token: CsrfToken, token: &str) -> String {
+    match csrf_token.snapshot() {
+        Some(snapshot) => format!("{:?}", CsrfToken::from_snapshot(snapshot).verify(token)),
+        None => "unsupported".to_string(),
+    }
+}
+
+#[cfg(feature = "snapshot")]
+fn restore(client: &rocket::local::blocking::Client) -> CsrfToken {
+    let serialized = client.get("/snapshot").dispatch().into_string().unwrap();
+    let snapshot: CsrfSnapshot = serde_json::from_str(&serialized).unwrap();
+
+    CsrfToken::from_snapshot(snapshot)
+}
+
+fn verify_restored_token(config: CsrfConfig) -> String {
+    let client = client(config);
+    let token = client.get("/").dispatch().into_string().unwrap();
+
+    client
+        .get(format!(
+            "/verify-restored?token={}",
+            token.replace('$', "%24").replace('/', "%2F")
+        ))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn verify_tokens_with_snapshot_taken_in_request() {
+    assert_eq!(verify_restored_token(CsrfConfig::default()), "Ok(())");
+}
+
+#[test]
+fn refuse_snapshot_of_other_strategies() {
+    let split = TokenStrategy::Split {
+        secret: b"server secret".to_vec(),
+    };
+
+    assert_eq!(
+        verify_restored_token(CsrfConfig::default().with_strategy(split)),
+        "unsupported"
+    );
+}
+
+#[cfg(feature = "snapshot")]
+#[test]
+fn verify_tokens_with_restored_snapshot() {
+    let client = client(CsrfConfig::default());
+    let token = client.get("/").dispatch().into_string().unwrap();
+    let restored = restore(&client);
+
+    assert!(restored.verify(&token).is_ok());
+    assert_eq!(restored.cookie_name(), "csrf_token");
+
+    let other = bcrypt::hash("another session", 4).unwrap();
+    assert!(matches!(restored.verify(&other), Err(CsrfError::Mismatch)));
+}
+
+#[cfg(feature = "snapshot")]
+#[test]
+fn keep_verification_settings_in_snapshot() {
+    let client = client(CsrfConfig::default().with_token_checksum(true));
+    let token = client.get("/").dispatch().into_string().unwrap();
+    let restored = restore(&client);
+
+    assert!(restored.verify(&token).is_ok());
+    assert!(matches!(
+        restored.verify(&token[8..]),
+        Err(CsrfError::Malformed)
+    ));
+}
+
+#[cfg(feature = "snapshot")]
+#[test]
+fn redact_session_token_in_debug_output() {
+    let client = client(CsrfConfig::default());
+    client.get("/").dispatch();
+    let serialized = client.get("/snapshot").dispatch().into_string().unwrap();
+    let snapshot: CsrfSnapshot = serde_json::from_str(&serialized).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&serialized).unwrap();
+
+    let session = value["value"].as_str().unwrap();
+    assert!(!format!("{:?}", snapshot).contains(session));
+}
@@ -0,0 +1,120 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, Fairing, TokenStrategy};
+
+use base64::{engine::general_purpose, Engine as _};
+
+const SECRET: &[u8] = b"server secret";
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    let strategy = TokenStrategy::Split {
+        secret: SECRET.to_vec(),
+    };
+
+    rocket::build()
+        .attach(Fairing::new(CsrfConfig::default().with_strategy(strategy)))
+        .mount("/", routes![index, verify])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(CsrfError::Malformed) => "malformed",
+        Err(CsrfError::Mismatch) => "mismatch",
+        Err(_) => "other",
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+fn get(client: &rocket::local::blocking::Client, uri: String, session: &str) -> String {
+    client
+        .get(uri)
+        .private_cookie(Cookie::new("csrf_token", session.to_string()))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+/// Splits a token into its selector and validator.
+fn split(token: &str) -> (&str, &str) {
+    token.split_once('.').unwrap()
+}
+
+#[test]
+fn accept_valid_pair() {
+    let client = client();
+    let session = session_token();
+    let token = get(&client, "/".into(), &session);
+
+    let (selector, validator) = split(&token);
+    assert_eq!((selector.len(), validator.len()), (16, 43));
+    assert_eq!(
+        get(&client, format!("/verify?token={}", token), &session),
+        "ok"
+    );
+}
+
+#[test]
+fn reject_wrong_validator() {
+    let client = client();
+    let session = session_token();
+    let token = get(&client, "/".into(), &session);
+    let foreign = get(&client, "/".into(), &session_token());
+
+    let forged = format!("{}.{}", split(&token).0, split(&foreign).1);
+    assert_eq!(
+        get(&client, format!("/verify?token={}", forged), &session),
+        "mismatch"
+    );
+}
+
+#[test]
+fn reject_unknown_selector() {
+    let client = client();
+    let session = session_token();
+    let token = get(&client, "/".into(), &session);
+
+    let forged = format!("AAAAAAAAAAAAAAAA.{}", split(&token).1);
+    assert_eq!(
+        get(&client, format!("/verify?token={}", forged), &session),
+        "mismatch"
+    );
+    assert_eq!(
+        get(
+            &client,
+            format!("/verify?token={}", token),
+            &session_token()
+        ),
+        "mismatch"
+    );
+}
+
+#[test]
+fn reject_malformed_pair() {
+    let client = client();
+    let session = session_token();
+
+    assert_eq!(
+        get(&client, "/verify?token=no-separator".into(), &session),
+        "malformed"
+    );
+}
@@ -0,0 +1,123 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::time::Duration;
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, Fairing};
+
+fn client(window: Duration) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(window)).unwrap()
+}
+
+fn rocket(window: Duration) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(Fairing::new(CsrfConfig::default().with_step_up(window)))
+        .mount("/", routes![index, step_up, verify, verify_step_up])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/step-up")]
+fn step_up(csrf_token: CsrfToken) -> String {
+    csrf_token.step_up_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> &'static str {
+    describe(csrf_token.verify(token))
+}
+
+#[get("/verify-step-up?<token>")]
+fn verify_step_up(csrf_token: CsrfToken, token: &str) -> &'static str {
+    describe(csrf_token.verify_step_up(token))
+}
+
+fn describe(result: Result<(), CsrfError>) -> &'static str {
+    match result {
+        Ok(()) => "ok",
+        Err(CsrfError::Malformed) => "malformed",
+        Err(CsrfError::Mismatch) => "mismatch",
+        Err(CsrfError::Expired) => "expired",
+        Err(_) => "other",
+    }
+}
+
+fn get(client: &rocket::local::blocking::Client, uri: String) -> String {
+    client.get(uri).dispatch().into_string().unwrap()
+}
+
+#[test]
+fn reject_regular_token_in_step_up_check() {
+    let client = client(Duration::minutes(5));
+    let token = get(&client, "/".into());
+
+    assert_eq!(get(&client, format!("/verify?token={}", token)), "ok");
+    assert_eq!(
+        get(&client, format!("/verify-step-up?token={}", token)),
+        "malformed"
+    );
+
+    let now = rocket::time::OffsetDateTime::now_utc().unix_timestamp();
+    assert_eq!(
+        get(&client, format!("/verify-step-up?token={}.{}", now, token)),
+        "mismatch"
+    );
+}
+
+#[test]
+fn accept_step_up_token_within_window() {
+    let client = client(Duration::minutes(5));
+    get(&client, "/".into());
+    let token = get(&client, "/step-up".into());
+
+    assert_eq!(
+        get(&client, format!("/verify-step-up?token={}", token)),
+        "ok"
+    );
+}
+
+#[test]
+fn reject_step_up_token_in_regular_check() {
+    let client = client(Duration::minutes(5));
+    get(&client, "/".into());
+    let token = get(&client, "/step-up".into());
+    let (_, inner) = token.split_once('.').unwrap();
+
+    assert_eq!(get(&client, format!("/verify?token={}", inner)), "mismatch");
+}
+
+#[test]
+fn reject_step_up_token_after_window() {
+    let client = client(Duration::seconds(1));
+    get(&client, "/".into());
+    let token = get(&client, "/step-up".into());
+
+    std::thread::sleep(std::time::Duration::from_millis(2100));
+
+    assert_eq!(
+        get(&client, format!("/verify-step-up?token={}", token)),
+        "expired"
+    );
+}
+
+#[test]
+fn refuse_step_up_tokens_when_disabled() {
+    let client = rocket::local::blocking::Client::tracked(
+        rocket::build()
+            .attach(Fairing::default())
+            .mount("/", routes![step_up_unconfigured]),
+    )
+    .unwrap();
+
+    assert_eq!(get(&client, "/".into()), "unsupported");
+}
+
+#[get("/")]
+fn step_up_unconfigured(csrf_token: CsrfToken) -> &'static str {
+    match csrf_token.step_up_token() {
+        Ok(_) => "minted",
+        Err(_) => "unsupported",
+    }
+}
@@ -0,0 +1,95 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket::time::Duration;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing, TokenStrategy};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn time_window() -> TokenStrategy {
+    TokenStrategy::TimeWindow {
+        step: Duration::hours(1),
+        secret: b"server secret".to_vec(),
+    }
+}
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(Fairing::new(
+            CsrfConfig::default().with_strategies(vec![time_window(), TokenStrategy::Bcrypt]),
+        ))
+        .mount("/", routes![index, verify])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(_) => "rejected",
+    }
+}
+
+fn session() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+fn verify_token(session: &str, token: &str) -> String {
+    client()
+        .get(format!("/verify?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", session.to_string()))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn mint_with_first_strategy() {
+    let token = client().get("/").dispatch().into_string().unwrap();
+
+    // Unpadded URL-safe base64 of an HMAC-SHA256, rather than a bcrypt hash.
+    assert_eq!(token.len(), 43);
+    assert!(!CsrfToken::is_well_formed(&token));
+}
+
+#[test]
+fn verify_bcrypt_and_hmac_tokens_under_mixed_strategies() {
+    let session = session();
+    let client = client();
+    let hmac_token = client.get("/").dispatch().into_string().unwrap();
+    let bcrypt_token = bcrypt::hash(&session, 4).unwrap();
+
+    assert_eq!(verify_token(&session, &hmac_token), "ok");
+    assert_eq!(verify_token(&session, &bcrypt_token), "ok");
+    assert_eq!(
+        verify_token(&session, &bcrypt::hash(self::session(), 4).unwrap()),
+        "rejected"
+    );
+}
+
+#[test]
+fn report_fallback_strategies_in_summary() {
+    let summary = CsrfConfig::default()
+        .with_strategies(vec![time_window(), TokenStrategy::Bcrypt])
+        .summary();
+
+    assert_eq!(summary.strategy, "time-window");
+    assert_eq!(summary.fallback_strategies, vec!["bcrypt"]);
+    assert_eq!(
+        CsrfConfig::default().with_strategies(Vec::new()).summary(),
+        CsrfConfig::default().summary()
+    );
+}
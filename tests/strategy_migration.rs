@@ -0,0 +1,157 @@
+#[macro_use]
+extern crate rocket;
+
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket::local::blocking::Client;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing, TokenStrategy};
+use sha2::Sha256;
+
+const SECRET: &[u8] = b"server secret";
+const LEGACY_COOKIE_NAME: &str = "legacy_csrf";
+
+fn new_strategy() -> TokenStrategy {
+    TokenStrategy::SignedDoubleSubmit {
+        secret: SECRET.to_vec(),
+    }
+}
+
+/// Migrates from bcrypt tokens in `legacy_csrf` to signed double-submit tokens in `csrf_token`.
+fn client(migration_set: Vec<TokenStrategy>) -> Client {
+    let config = CsrfConfig::default()
+        .with_strategy(new_strategy())
+        .with_legacy_configs(vec![
+            CsrfConfig::default().with_cookie_name(LEGACY_COOKIE_NAME)
+        ])
+        .with_migration_set(migration_set);
+    let rocket = rocket::build()
+        .attach(Fairing::new(config))
+        .mount("/", routes![check]);
+
+    Client::tracked(rocket).unwrap()
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(_) => "rejected",
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+fn bcrypt_token(session: &str) -> String {
+    bcrypt::hash(session, 4).unwrap()
+}
+
+fn signed_token(session: &str) -> String {
+    let message = "AAAAAAAAAAAAAAAAAAAAAA";
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET).unwrap();
+    mac.update(&(session.len() as u64).to_be_bytes());
+    mac.update(session.as_bytes());
+    mac.update(message.as_bytes());
+
+    format!(
+        "{}.{}",
+        message,
+        general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+    )
+}
+
+fn submit(client: &Client, cookie_name: &'static str, session: &str, token: &str) -> String {
+    client
+        .get(format!(
+            "/check?token={}",
+            token.replace('$', "%24").replace('/', "%2F")
+        ))
+        .private_cookie(Cookie::new(cookie_name, session.to_string()))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+fn migration_set() -> Vec<TokenStrategy> {
+    vec![TokenStrategy::Bcrypt, new_strategy()]
+}
+
+#[test]
+fn accept_new_token_with_old_cookie() {
+    let session = session_token();
+
+    assert_eq!(
+        submit(
+            &client(migration_set()),
+            LEGACY_COOKIE_NAME,
+            &session,
+            &signed_token(&session)
+        ),
+        "ok"
+    );
+}
+
+#[test]
+fn accept_old_token_with_new_cookie() {
+    let session = session_token();
+
+    assert_eq!(
+        submit(
+            &client(migration_set()),
+            "csrf_token",
+            &session,
+            &bcrypt_token(&session)
+        ),
+        "ok"
+    );
+}
+
+#[test]
+fn reject_cross_strategy_combinations_outside_migration() {
+    let session = session_token();
+    let client = client(Vec::new());
+
+    assert_eq!(
+        submit(
+            &client,
+            LEGACY_COOKIE_NAME,
+            &session,
+            &signed_token(&session)
+        ),
+        "rejected"
+    );
+    assert_eq!(
+        submit(&client, "csrf_token", &session, &bcrypt_token(&session)),
+        "rejected"
+    );
+    // Matching combinations keep verifying.
+    assert_eq!(
+        submit(
+            &client,
+            LEGACY_COOKIE_NAME,
+            &session,
+            &bcrypt_token(&session)
+        ),
+        "ok"
+    );
+    assert_eq!(
+        submit(&client, "csrf_token", &session, &signed_token(&session)),
+        "ok"
+    );
+}
+
+#[test]
+fn report_migration_set_in_summary() {
+    let summary = CsrfConfig::default()
+        .with_migration_set(migration_set())
+        .summary();
+
+    assert_eq!(summary.migration_set, ["bcrypt", "signed-double-submit"]);
+    assert!(CsrfConfig::default().summary().migration_set.is_empty());
+}
@@ -0,0 +1,101 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::time::Duration;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing, TokenStrategy};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(config: CsrfConfig) -> Client {
+    let rocket = rocket::build()
+        .attach(Fairing::new(config))
+        .mount("/", routes![index, check]);
+
+    Client::tracked(rocket).unwrap()
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn time_window() -> TokenStrategy {
+    TokenStrategy::TimeWindow {
+        step: Duration::minutes(5),
+        secret: b"server secret".to_vec(),
+    }
+}
+
+fn split() -> TokenStrategy {
+    TokenStrategy::Split {
+        secret: b"server secret".to_vec(),
+    }
+}
+
+/// Issues a session token under `config`, checks that a token minted from it verifies, and
+/// returns the length of the session token in bytes.
+fn issued_token_len(config: CsrfConfig) -> usize {
+    let client = client(config);
+    let response = client.get("/").dispatch();
+    let session = response.cookies().get_private("csrf_token").unwrap();
+    let len = general_purpose::STANDARD
+        .decode(session.value())
+        .unwrap()
+        .len();
+    let token = response.into_string().unwrap();
+
+    let status = client
+        .get(format!(
+            "/check?token={}",
+            token.replace('$', "%24").replace('/', "%2F")
+        ))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+
+    len
+}
+
+#[test]
+fn issue_tokens_of_each_strategy_length() {
+    assert_eq!(issued_token_len(CsrfConfig::default()), 32);
+    assert_eq!(
+        issued_token_len(CsrfConfig::default().with_strategy(time_window())),
+        16
+    );
+    assert_eq!(
+        issued_token_len(CsrfConfig::default().with_strategy(split())),
+        32
+    );
+}
+
+#[test]
+fn prefer_length_set_for_primary_strategy() {
+    let config = CsrfConfig::default()
+        .with_cookie_len(48)
+        .with_token_len_for(&TokenStrategy::Bcrypt, 24)
+        .with_token_len_for(&split(), 64);
+
+    assert_eq!(issued_token_len(config.clone()), 24);
+    assert_eq!(issued_token_len(config.clone().with_strategy(split())), 64);
+    // The global length still applies to strategies without a length of their own.
+    assert_eq!(issued_token_len(config.with_strategy(time_window())), 48);
+}
+
+#[test]
+fn report_effective_length_in_summary() {
+    let config = CsrfConfig::default().with_token_len_for(&time_window(), 20);
+
+    assert_eq!(config.summary().cookie_len, 32);
+    assert_eq!(config.with_strategy(time_window()).summary().cookie_len, 20);
+}
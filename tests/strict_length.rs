@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate rocket;
+
+use bcrypt::hash;
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken};
+use sha2::{Digest, Sha256};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(strict: bool) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(strict)).unwrap()
+}
+
+fn rocket(strict: bool) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default()
+                .with_cookie_len(32)
+                .with_strict_length(strict),
+        ))
+        .mount("/", routes![index])
+}
+
+#[get("/?<token>")]
+fn index(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(CsrfError::LengthMismatch) => "length mismatch",
+        Err(_) => "other",
+    }
+}
+
+fn verify_with_longer_session(strict: bool) -> String {
+    // A session issued while `cookie_len` was still 64.
+    let mut raw = [0u8; 64];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    // Encoded session tokens past bcrypt's 72-byte limit are pre-hashed.
+    let secret = general_purpose::STANDARD.encode(Sha256::digest(&encoded));
+    let token = hash(&secret, 4).unwrap();
+
+    client(strict)
+        .get(format!("/?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn reject_mismatched_session_length_when_strict() {
+    assert_eq!(verify_with_longer_session(true), "length mismatch");
+}
+
+#[test]
+fn accept_longer_session_when_lenient() {
+    assert_eq!(verify_with_longer_session(false), "ok");
+}
@@ -0,0 +1,64 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::Status;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+const TEST_TOKEN: &str = "e2e-test-token";
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    let config = config.with_test_token(Some(TEST_TOKEN.to_string()));
+    let rocket = rocket::build()
+        .attach(Fairing::new(config))
+        .mount("/", routes![verify]);
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn status(config: CsrfConfig, token: &str) -> Status {
+    client(config)
+        .get(format!("/verify?token={}", token))
+        .dispatch()
+        .status()
+}
+
+#[test]
+fn accept_test_token_under_development_config() {
+    assert_eq!(status(CsrfConfig::development(), TEST_TOKEN), Status::Ok);
+    assert_eq!(
+        status(CsrfConfig::development(), "another-token"),
+        Status::Forbidden
+    );
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn accept_test_token_in_debug_builds() {
+    assert_eq!(status(CsrfConfig::default(), TEST_TOKEN), Status::Ok);
+    assert!(
+        CsrfConfig::default()
+            .with_test_token(Some(TEST_TOKEN.to_string()))
+            .summary()
+            .test_token
+    );
+}
+
+#[cfg(not(debug_assertions))]
+#[test]
+fn ignore_test_token_in_release_builds() {
+    assert_eq!(status(CsrfConfig::default(), TEST_TOKEN), Status::Forbidden);
+    assert!(
+        !CsrfConfig::default()
+            .with_test_token(Some(TEST_TOKEN.to_string()))
+            .summary()
+            .test_token
+    );
+}
@@ -0,0 +1,101 @@
+#[macro_use]
+extern crate rocket;
+
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use rocket::time::{Duration, OffsetDateTime};
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, Fairing, TokenStrategy};
+use sha2::Sha256;
+
+const SECRET: &[u8] = b"server secret";
+const STEP: i64 = 3600;
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    let strategy = TokenStrategy::TimeWindow {
+        step: Duration::seconds(STEP),
+        secret: SECRET.to_vec(),
+    };
+
+    rocket::build()
+        .attach(Fairing::new(CsrfConfig::default().with_strategy(strategy)))
+        .mount("/", routes![index, verify])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(CsrfError::Malformed) => "malformed",
+        Err(CsrfError::Mismatch) => "mismatch",
+        Err(_) => "other",
+    }
+}
+
+/// Mints a token for the time step `offset` steps away from the current one.
+fn token_at(offset: i64) -> String {
+    let counter = OffsetDateTime::now_utc().unix_timestamp() / STEP + offset;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET).unwrap();
+    mac.update(&counter.to_be_bytes());
+    general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+}
+
+fn verify_token(token: &str) -> String {
+    client()
+        .get(format!("/verify?token={}", token))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn accept_token_minted_in_current_step() {
+    let client = client();
+    let token = client.get("/").dispatch().into_string().unwrap();
+
+    assert_eq!(token, token_at(0));
+    assert_eq!(verify_token(&token), "ok");
+}
+
+#[test]
+fn accept_token_minted_in_adjacent_step() {
+    assert_eq!(verify_token(&token_at(-1)), "ok");
+    assert_eq!(verify_token(&token_at(1)), "ok");
+}
+
+#[test]
+fn reject_token_minted_in_expired_step() {
+    assert_eq!(verify_token(&token_at(-2)), "mismatch");
+}
+
+#[test]
+fn reject_bcrypt_tokens() {
+    let token = bcrypt::hash("some session token", 4).unwrap();
+
+    assert_eq!(verify_token(&token), "malformed");
+}
+
+#[test]
+fn report_strategy_in_summary() {
+    assert_eq!(
+        Fairing::new(
+            CsrfConfig::default().with_strategy(TokenStrategy::TimeWindow {
+                step: Duration::seconds(STEP),
+                secret: SECRET.to_vec(),
+            })
+        )
+        .summary()
+        .strategy,
+        "time-window"
+    );
+    assert_eq!(Fairing::default().summary().strategy, "bcrypt");
+}
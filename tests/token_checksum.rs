@@ -0,0 +1,102 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_token_checksum(true),
+        ))
+        .mount("/", routes![index, verify])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/verify?<token>")]
+fn verify(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(CsrfError::Malformed) => "malformed",
+        Err(CsrfError::Mismatch) => "mismatch",
+        Err(_) => "other",
+    }
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+fn mint(client: &rocket::local::blocking::Client, session: &str) -> String {
+    client
+        .get("/")
+        .private_cookie(Cookie::new("csrf_token", session.to_string()))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+fn verify_with(client: &rocket::local::blocking::Client, session: &str, token: &str) -> String {
+    client
+        .get(format!("/verify?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", session.to_string()))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn accept_token_with_valid_checksum() {
+    let client = client();
+    let session = session_token();
+    let token = mint(&client, &session);
+
+    assert_eq!(token.len(), 68);
+    assert_eq!(verify_with(&client, &session, &token), "ok");
+}
+
+#[test]
+fn reject_token_with_bad_checksum_as_malformed() {
+    let client = client();
+    let session = session_token();
+    let token = mint(&client, &session);
+    let flipped = if token.starts_with('0') { '1' } else { '0' };
+    let corrupted = format!("{}{}", flipped, &token[1..]);
+
+    assert_eq!(verify_with(&client, &session, &corrupted), "malformed");
+    // A bare bcrypt hash lacks the checksum altogether.
+    assert_eq!(verify_with(&client, &session, &token[8..]), "malformed");
+}
+
+#[test]
+fn verify_token_with_valid_checksum_in_full() {
+    let client = client();
+    let token = mint(&client, &session_token());
+
+    assert_eq!(verify_with(&client, &session_token(), &token), "mismatch");
+}
+
+#[test]
+fn report_token_checksum_in_summary() {
+    assert!(!CsrfConfig::default().summary().token_checksum);
+    assert!(
+        CsrfConfig::default()
+            .with_token_checksum(true)
+            .summary()
+            .token_checksum
+    );
+}
@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::error::ErrorKind;
+use rocket::http::{ContentType, Status};
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+fn client(endpoint: Option<&str>) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(endpoint)).unwrap()
+}
+
+fn rocket(endpoint: Option<&str>) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(Fairing::new(
+            CsrfConfig::default().with_token_endpoint(endpoint.map(String::from)),
+        ))
+        .mount("/", routes![check])
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+#[test]
+fn serve_verifiable_token_from_mounted_endpoint() {
+    let client = client(Some("/csrf-token"));
+    let response = client.get("/csrf-token").dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+    assert_eq!(
+        response.headers().get_one("Cache-Control"),
+        Some("no-store")
+    );
+
+    let body: serde_json::Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    assert_eq!(body["param"], "authenticity_token");
+    assert_eq!(body["header"], "X-CSRF-Token");
+
+    let status = client
+        .get(format!("/check?token={}", body["token"].as_str().unwrap()))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn mount_nothing_by_default() {
+    let client = client(None);
+
+    assert_eq!(
+        client.get("/csrf-token").dispatch().status(),
+        Status::NotFound
+    );
+}
+
+#[test]
+fn fail_ignition_on_invalid_endpoint_path() {
+    let err = rocket::local::blocking::Client::tracked(rocket(Some("no leading slash")))
+        .err()
+        .unwrap();
+
+    assert!(matches!(err.kind(), ErrorKind::FailedFairings(_)));
+}
+
+#[test]
+fn escape_configured_names() {
+    let rocket = rocket::build().attach(Fairing::new(
+        CsrfConfig::default()
+            .with_token_endpoint(Some("/csrf-token".into()))
+            .with_param_name(r#"form"field\1"#)
+            .with_header_name("X-\"Token\"\n"),
+    ));
+    let client = rocket::local::blocking::Client::tracked(rocket).unwrap();
+
+    let body = client.get("/csrf-token").dispatch().into_string().unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(body["param"], r#"form"field\1"#);
+    assert_eq!(body["header"], "X-\"Token\"\n");
+}
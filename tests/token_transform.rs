@@ -0,0 +1,53 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::Status;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+use std::sync::Arc;
+
+use base64::{engine::general_purpose, Engine as _};
+
+const VERSION_TAG: u8 = 0x01;
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    let config = CsrfConfig::default()
+        .with_token_transform(Arc::new(|bytes: &mut Vec<u8>| bytes.insert(0, VERSION_TAG)));
+
+    rocket::build()
+        .attach(Fairing::new(config))
+        .mount("/", routes![index, check])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+#[test]
+fn issue_and_verify_transformed_tokens() {
+    let client = client();
+    let response = client.get("/").dispatch();
+    let cookie = response.cookies().get_private("csrf_token").unwrap();
+    let session = general_purpose::STANDARD.decode(cookie.value()).unwrap();
+    assert_eq!(session.len(), 33);
+    assert_eq!(session[0], VERSION_TAG);
+
+    let token = response.into_string().unwrap();
+    let status = client
+        .get(format!("/check?token={}", token))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok);
+}
@@ -0,0 +1,113 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::form::Form;
+use rocket::http::{ContentType, Cookie, Status};
+use rocket::Request;
+use rocket_csrf_token::{CsrfConfig, CsrfRequestExt, VerifiedCsrf};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    let rocket = rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(config))
+        .mount("/", routes![create])
+        .register("/", catchers![forbidden]);
+
+    rocket::local::blocking::Client::tracked(rocket).unwrap()
+}
+
+#[derive(FromForm)]
+struct Comment<'r> {
+    body: &'r str,
+}
+
+#[post("/comments", data = "<form>")]
+fn create(csrf: VerifiedCsrf, form: Form<Comment<'_>>) -> String {
+    format!("{} ({:?})", form.body, csrf.source())
+}
+
+#[catch(403)]
+fn forbidden(request: &Request) -> String {
+    match request.csrf_error() {
+        Some(err) => err.to_string(),
+        None => "forbidden".to_string(),
+    }
+}
+
+fn new_session() -> (String, String) {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+    (encoded, token)
+}
+
+fn submit(config: CsrfConfig, session: String, body: String) -> (Status, String) {
+    let client = client(config);
+    let response = client
+        .post("/comments")
+        .header(ContentType::Form)
+        .private_cookie(Cookie::new("csrf_token", session))
+        .body(body)
+        .dispatch();
+
+    (response.status(), response.into_string().unwrap())
+}
+
+fn encode(token: &str) -> String {
+    token.replace('$', "%24").replace('/', "%2F")
+}
+
+#[test]
+fn accept_form_with_valid_token() {
+    let (session, token) = new_session();
+    let body = format!("authenticity_token={}&body=hello", encode(&token));
+
+    assert_eq!(
+        submit(CsrfConfig::default(), session, body),
+        (Status::Ok, "hello (Form)".to_string())
+    );
+}
+
+#[test]
+fn reject_form_with_invalid_token() {
+    let (session, _) = new_session();
+    let (_, stale) = new_session();
+    let body = format!("authenticity_token={}&body=hello", encode(&stale));
+
+    assert_eq!(
+        submit(CsrfConfig::default(), session, body),
+        (
+            Status::Forbidden,
+            "CSRF token verification failed!".to_string()
+        )
+    );
+}
+
+#[test]
+fn reject_form_without_token() {
+    let (session, _) = new_session();
+
+    assert_eq!(
+        submit(CsrfConfig::default(), session, "body=hello".to_string()),
+        (Status::Forbidden, "CSRF token is missing".to_string())
+    );
+}
+
+#[test]
+fn read_configured_param_name() {
+    let (session, token) = new_session();
+    let body = format!("_csrf={}&body=hello", encode(&token));
+
+    assert_eq!(
+        submit(
+            CsrfConfig::default().with_param_name("_csrf"),
+            session,
+            body
+        ),
+        (Status::Ok, "hello (Form)".to_string())
+    );
+}
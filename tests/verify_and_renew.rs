@@ -0,0 +1,63 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::{CookieJar, Status};
+use rocket_csrf_token::CsrfToken;
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![index, submit])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[post("/?<token>")]
+fn submit(csrf_token: CsrfToken, cookies: &CookieJar<'_>, token: &str) -> (Status, String) {
+    match csrf_token.verify_and_renew(token, cookies) {
+        Ok(next) => (Status::Ok, next),
+        Err(err) => (Status::Forbidden, err.to_string()),
+    }
+}
+
+#[test]
+fn issue_fresh_cookie_and_token_on_success() {
+    let client = client();
+    let token = client.get("/").dispatch().into_string().unwrap();
+    let session = client.cookies().get_private("csrf_token").unwrap();
+
+    let response = client.post(format!("/?token={}", token)).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let renewed = response.cookies().get_private("csrf_token").unwrap();
+    assert_ne!(renewed.value(), session.value());
+
+    let next = response.into_string().unwrap();
+    assert_ne!(next, token);
+    assert!(bcrypt::verify(renewed.value(), &next).unwrap());
+
+    // The submitted token was single-use, the renewed one is accepted.
+    let status = client
+        .post(format!("/?token={}", token))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Forbidden);
+    let status = client.post(format!("/?token={}", next)).dispatch().status();
+    assert_eq!(status, Status::Ok);
+}
+
+#[test]
+fn keep_session_token_on_failure() {
+    let client = client();
+    client.get("/").dispatch();
+
+    let response = client.post("/?token=garbage").dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    assert!(response.cookies().get_private("csrf_token").is_none());
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "json")]
+
+#[macro_use]
+extern crate rocket;
+
+use base64::{engine::general_purpose, Engine as _};
+use rocket::http::Header;
+use rocket::local::blocking::Client;
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, Fairing, TokenStrategy};
+
+fn client() -> Client {
+    Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    let strategy = TokenStrategy::Claims {
+        secret: b"server secret".to_vec(),
+    };
+
+    rocket::build()
+        .attach(Fairing::new(
+            CsrfConfig::default()
+                .with_strategy(strategy)
+                .with_same_origin_shortcut(true),
+        ))
+        .mount("/", routes![index, mint, check, post_check])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/mint?<scope>")]
+fn mint(csrf_token: CsrfToken, scope: &str) -> String {
+    csrf_token.authenticity_token_for(scope).unwrap()
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> String {
+    match csrf_token.verify_and_scope(token) {
+        Ok(scope) => scope,
+        Err(CsrfError::Malformed) => "malformed".into(),
+        Err(CsrfError::Mismatch) => "mismatch".into(),
+        Err(_) => "other".into(),
+    }
+}
+
+#[post("/check?<token>")]
+fn post_check(csrf_token: CsrfToken, token: &str) -> String {
+    check(csrf_token, token)
+}
+
+fn get(client: &Client, uri: String) -> String {
+    client.get(uri).dispatch().into_string().unwrap()
+}
+
+#[test]
+fn return_scope_of_minted_token() {
+    let client = client();
+    let token = get(&client, "/mint?scope=transfer".into());
+
+    assert_eq!(get(&client, format!("/check?token={}", token)), "transfer");
+}
+
+#[test]
+fn reject_tampered_scope() {
+    let client = client();
+    let token = get(&client, "/mint?scope=transfer".into());
+
+    let (claims, mac) = token.split_once('.').unwrap();
+    let mut claims: serde_json::Value =
+        serde_json::from_slice(&general_purpose::URL_SAFE_NO_PAD.decode(claims).unwrap()).unwrap();
+    claims["scope"] = "delete-account".into();
+    let tampered = format!(
+        "{}.{}",
+        general_purpose::URL_SAFE_NO_PAD.encode(claims.to_string()),
+        mac
+    );
+
+    assert_eq!(
+        get(&client, format!("/check?token={}", tampered)),
+        "mismatch"
+    );
+
+    // A same-origin request doesn't vouch for the scope of the token it carries.
+    let same_origin = client
+        .post(format!("/check?token={}", tampered))
+        .header(Header::new("Sec-Fetch-Site", "same-origin"))
+        .dispatch()
+        .into_string()
+        .unwrap();
+    assert_eq!(same_origin, "mismatch");
+}
+
+#[test]
+fn reject_token_without_scope() {
+    let client = client();
+    let token = get(&client, "/".into());
+
+    assert_eq!(get(&client, format!("/check?token={}", token)), "malformed");
+}
@@ -0,0 +1,70 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket_csrf_token::{CsrfError, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![batch])
+}
+
+#[get("/batch?<tokens>")]
+fn batch(csrf_token: CsrfToken, tokens: Vec<String>) -> String {
+    csrf_token
+        .verify_batch(&tokens)
+        .into_iter()
+        .map(|result| match result {
+            Ok(()) => "ok",
+            Err(CsrfError::Missing) => "missing",
+            Err(CsrfError::Malformed) => "malformed",
+            Err(CsrfError::Mismatch) => "mismatch",
+            Err(_) => "other",
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn session_token() -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    general_purpose::STANDARD.encode(raw)
+}
+
+#[test]
+fn report_result_of_each_token() {
+    let session = session_token();
+    let valid = bcrypt::hash(&session, 4).unwrap();
+    let foreign = bcrypt::hash(session_token(), 4).unwrap();
+
+    let client = client();
+    let response = client
+        .get(format!(
+            "/batch?tokens={}&tokens={}&tokens=garbage&tokens={}",
+            valid, foreign, valid
+        ))
+        .private_cookie(Cookie::new("csrf_token", session))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "ok,mismatch,malformed,ok");
+}
+
+#[test]
+fn return_no_results_for_empty_batch() {
+    let client = client();
+    let response = client
+        .get("/batch")
+        .private_cookie(Cookie::new("csrf_token", session_token()))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "");
+}
@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::Status;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing, TokenStrategy, VerifyFn};
+use std::sync::Arc;
+
+const SENTINEL: &str = "sentinel-token";
+
+fn accept_sentinel() -> VerifyFn {
+    Arc::new(|_secret: &str, submitted: &str| submitted == SENTINEL)
+}
+
+fn client(config: CsrfConfig) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(config)).unwrap()
+}
+
+fn rocket(config: CsrfConfig) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(Fairing::new(config))
+        .mount("/", routes![index, check])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> Result<String, Status> {
+    csrf_token
+        .authenticity_token()
+        .map_err(|_| Status::NotImplemented)
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn check_status(client: &rocket::local::blocking::Client, token: &str) -> Status {
+    client
+        .get(format!("/check?token={}", token))
+        .dispatch()
+        .status()
+}
+
+#[test]
+fn accept_sentinel_with_custom_verify_fn() {
+    let client = client(CsrfConfig::default().with_verify_fn(accept_sentinel()));
+
+    assert_eq!(check_status(&client, SENTINEL), Status::Ok);
+    assert_eq!(check_status(&client, "anything-else"), Status::Forbidden);
+    assert_eq!(client.get("/").dispatch().status(), Status::NotImplemented);
+}
+
+#[test]
+fn combine_custom_verify_fn_with_minting_strategy() {
+    let client = client(CsrfConfig::default().with_strategies(vec![
+        TokenStrategy::Bcrypt,
+        TokenStrategy::Custom(accept_sentinel()),
+    ]));
+    let token = client.get("/").dispatch().into_string().unwrap();
+
+    assert_eq!(check_status(&client, &token), Status::Ok);
+    assert_eq!(check_status(&client, SENTINEL), Status::Ok);
+}
@@ -0,0 +1,77 @@
+#[macro_use]
+extern crate rocket;
+
+use rocket::http::Status;
+use rocket_csrf_token::{CsrfConfig, CsrfToken, Fairing};
+
+fn client(fairing: Fairing) -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket(fairing)).unwrap()
+}
+
+fn rocket(fairing: Fairing) -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(fairing)
+        .mount("/", routes![index, check])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[get("/check?<token>")]
+fn check(csrf_token: CsrfToken, token: &str) -> Status {
+    match csrf_token.verify(token) {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Forbidden,
+    }
+}
+
+fn verify_several_times(fairing: Fairing) {
+    let client = client(fairing);
+    let token = client.get("/").dispatch().into_string().unwrap();
+
+    for _ in 0..3 {
+        let status = client
+            .get(format!("/check?token={}", token))
+            .dispatch()
+            .status();
+        assert_eq!(status, Status::Ok);
+    }
+    let status = client.get("/check?token=garbage").dispatch().status();
+    assert_eq!(status, Status::Forbidden);
+}
+
+#[test]
+fn record_verification_latencies_when_enabled() {
+    let fairing = Fairing::new(CsrfConfig::default().with_latency_recording(true));
+    let handle = fairing.clone();
+    verify_several_times(fairing);
+
+    let percentiles = handle.metrics().verify_latency_percentiles().unwrap();
+    assert_eq!(percentiles.samples, 4);
+    assert!(percentiles.p50 > std::time::Duration::ZERO);
+    assert!(percentiles.p50 <= percentiles.p90);
+    assert!(percentiles.p90 <= percentiles.p99);
+}
+
+#[test]
+fn skip_latencies_by_default() {
+    let fairing = Fairing::default();
+    let handle = fairing.clone();
+    verify_several_times(fairing);
+
+    assert_eq!(handle.metrics().verified, 3);
+    assert!(handle.metrics().verify_latency_percentiles().is_none());
+}
+
+#[test]
+fn report_latency_recording_in_summary() {
+    assert!(
+        CsrfConfig::default()
+            .with_latency_recording(true)
+            .summary()
+            .latency_recording
+    );
+    assert!(!CsrfConfig::default().summary().latency_recording);
+}
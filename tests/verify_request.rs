@@ -0,0 +1,159 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::{ContentType, Cookie, Header};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_csrf_token::{CsrfConfig, CsrfError, CsrfToken, TokenSource};
+
+use base64::{engine::general_purpose, Engine as _};
+
+struct Verified(Result<TokenSource, CsrfError>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Verified {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        request
+            .guard::<CsrfToken>()
+            .await
+            .map(|csrf_token| Verified(csrf_token.verify_request(request)))
+    }
+}
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::new(
+            CsrfConfig::default().with_query_tokens(true),
+        ))
+        .mount("/", routes![submit])
+}
+
+#[post("/", data = "<_body>")]
+fn submit(verified: Verified, _body: String) -> String {
+    format!("{:?}", verified.0)
+}
+
+fn new_session() -> (String, String) {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+    let token = bcrypt::hash(&encoded, 4).unwrap();
+    (encoded, token)
+}
+
+fn encode(token: &str) -> String {
+    token.replace('$', "%24").replace('/', "%2F")
+}
+
+#[test]
+fn report_header_source() {
+    let (session, token) = new_session();
+
+    let body = client()
+        .post("/")
+        .header(Header::new("X-CSRF-Token", token))
+        .private_cookie(Cookie::new("csrf_token", session))
+        .body("")
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(
+        body,
+        format!("{:?}", Ok::<_, CsrfError>(TokenSource::Header))
+    );
+}
+
+#[test]
+fn report_form_source() {
+    let (session, token) = new_session();
+
+    let body = client()
+        .post("/")
+        .header(ContentType::Form)
+        .private_cookie(Cookie::new("csrf_token", session))
+        .body(format!("authenticity_token={}&comment=hi", encode(&token)))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(body, format!("{:?}", Ok::<_, CsrfError>(TokenSource::Form)));
+}
+
+#[test]
+fn report_query_source() {
+    let (session, token) = new_session();
+
+    let body = client()
+        .post(format!("/?authenticity_token={}", encode(&token)))
+        .private_cookie(Cookie::new("csrf_token", session))
+        .body("")
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(
+        body,
+        format!("{:?}", Ok::<_, CsrfError>(TokenSource::Query))
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn report_json_source() {
+    let (session, token) = new_session();
+
+    let body = client()
+        .post("/")
+        .header(ContentType::JSON)
+        .private_cookie(Cookie::new("csrf_token", session))
+        .body(format!(r#"{{"authenticity_token":"{}"}}"#, token))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(body, format!("{:?}", Ok::<_, CsrfError>(TokenSource::Json)));
+}
+
+#[test]
+fn report_valid_source_when_another_holds_a_stale_token() {
+    let (session, token) = new_session();
+    let (_, stale) = new_session();
+
+    let body = client()
+        .post("/")
+        .header(ContentType::Form)
+        .header(Header::new("X-CSRF-Token", stale))
+        .private_cookie(Cookie::new("csrf_token", session))
+        .body(format!("authenticity_token={}", encode(&token)))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(body, format!("{:?}", Ok::<_, CsrfError>(TokenSource::Form)));
+}
+
+#[test]
+fn fail_without_any_token() {
+    let (session, _) = new_session();
+
+    let body = client()
+        .post("/")
+        .private_cookie(Cookie::new("csrf_token", session))
+        .body("")
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert_eq!(
+        body,
+        format!("{:?}", Err::<TokenSource, _>(CsrfError::Missing))
+    );
+}
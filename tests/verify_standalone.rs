@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use rocket::time::Duration;
+use rocket_csrf_token::{mint_standalone, verify_standalone, CsrfError, TokenStrategy};
+
+use base64::{engine::general_purpose, Engine as _};
+
+const SECRET: [u8; 32] = [3; 32];
+const OTHER_SECRET: [u8; 32] = [4; 32];
+const SERVER_SECRET: &[u8] = b"server secret";
+
+/// Mints a token for `SECRET` and checks that it verifies for it alone.
+fn round_trip(strategy: TokenStrategy) {
+    let token = mint_standalone(&SECRET, &strategy).unwrap();
+
+    assert!(verify_standalone(&SECRET, &token, &strategy).is_ok());
+    assert!(matches!(
+        verify_standalone(&SECRET, "", &strategy),
+        Err(CsrfError::Missing)
+    ));
+    assert!(matches!(
+        verify_standalone(&SECRET, "not a token", &strategy),
+        Err(CsrfError::Malformed)
+    ));
+}
+
+#[test]
+fn verify_bcrypt_tokens() {
+    round_trip(TokenStrategy::Bcrypt);
+
+    let token = mint_standalone(&SECRET, &TokenStrategy::Bcrypt).unwrap();
+    assert!(matches!(
+        verify_standalone(&OTHER_SECRET, &token, &TokenStrategy::Bcrypt),
+        Err(CsrfError::Mismatch)
+    ));
+}
+
+#[test]
+fn verify_bcrypt_tokens_hashed_elsewhere() {
+    let encoded = general_purpose::STANDARD.encode(SECRET);
+    let token = bcrypt::hash(encoded, 4).unwrap();
+
+    assert!(verify_standalone(&SECRET, &token, &TokenStrategy::Bcrypt).is_ok());
+}
+
+#[test]
+fn verify_time_window_tokens() {
+    let strategy = TokenStrategy::TimeWindow {
+        step: Duration::minutes(5),
+        secret: SERVER_SECRET.to_vec(),
+    };
+    round_trip(strategy.clone());
+
+    let other = TokenStrategy::TimeWindow {
+        step: Duration::minutes(5),
+        secret: b"another secret".to_vec(),
+    };
+    let token = mint_standalone(&SECRET, &strategy).unwrap();
+    assert!(matches!(
+        verify_standalone(&SECRET, &token, &other),
+        Err(CsrfError::Mismatch)
+    ));
+}
+
+#[test]
+fn verify_split_tokens() {
+    let strategy = TokenStrategy::Split {
+        secret: SERVER_SECRET.to_vec(),
+    };
+    round_trip(strategy.clone());
+
+    let token = mint_standalone(&SECRET, &strategy).unwrap();
+    assert!(matches!(
+        verify_standalone(&OTHER_SECRET, &token, &strategy),
+        Err(CsrfError::Mismatch)
+    ));
+}
+
+#[test]
+fn verify_signed_double_submit_tokens() {
+    let strategy = TokenStrategy::SignedDoubleSubmit {
+        secret: SERVER_SECRET.to_vec(),
+    };
+    round_trip(strategy.clone());
+
+    let token = mint_standalone(&SECRET, &strategy).unwrap();
+    assert!(matches!(
+        verify_standalone(&OTHER_SECRET, &token, &strategy),
+        Err(CsrfError::Mismatch)
+    ));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn verify_claims_tokens() {
+    let strategy = TokenStrategy::Claims {
+        secret: SERVER_SECRET.to_vec(),
+    };
+    round_trip(strategy.clone());
+
+    let token = mint_standalone(&SECRET, &strategy).unwrap();
+    assert!(matches!(
+        verify_standalone(&OTHER_SECRET, &token, &strategy),
+        Err(CsrfError::Mismatch)
+    ));
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn verify_ed25519_tokens() {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let strategy = TokenStrategy::Ed25519 {
+        public_key: key.verifying_key().to_bytes(),
+    };
+    let token = format!(
+        "{}.{}",
+        general_purpose::URL_SAFE_NO_PAD.encode(b"session 42"),
+        general_purpose::URL_SAFE_NO_PAD.encode(key.sign(b"session 42").to_bytes())
+    );
+
+    assert!(verify_standalone(&SECRET, &token, &strategy).is_ok());
+    assert!(matches!(
+        mint_standalone(&SECRET, &strategy),
+        Err(CsrfError::HashError(_))
+    ));
+}
+
+#[test]
+fn verify_custom_tokens() {
+    let expected = general_purpose::STANDARD.encode(SECRET);
+    let strategy = TokenStrategy::Custom(Arc::new(move |session, submitted| {
+        session == expected && submitted == "let me in"
+    }));
+
+    assert!(verify_standalone(&SECRET, "let me in", &strategy).is_ok());
+    assert!(matches!(
+        verify_standalone(&OTHER_SECRET, "let me in", &strategy),
+        Err(CsrfError::Mismatch)
+    ));
+    assert!(mint_standalone(&SECRET, &strategy).is_err());
+}
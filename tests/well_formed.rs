@@ -0,0 +1,72 @@
+#[macro_use]
+extern crate rocket;
+
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket_csrf_token::{CsrfError, CsrfToken};
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![index])
+}
+
+#[get("/?<token>")]
+fn index(csrf_token: CsrfToken, token: &str) -> &'static str {
+    match csrf_token.verify(token) {
+        Ok(()) => "ok",
+        Err(CsrfError::Missing) => "missing",
+        Err(CsrfError::Malformed) => "malformed",
+        Err(CsrfError::Mismatch) => "mismatch",
+        Err(_) => "other",
+    }
+}
+
+fn verify_with_session(token: &str) -> String {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+
+    client()
+        .get(format!("/?token={}", token))
+        .private_cookie(Cookie::new("csrf_token", encoded))
+        .dispatch()
+        .into_string()
+        .unwrap()
+}
+
+#[test]
+fn accept_bcrypt_shaped_tokens() {
+    let token = bcrypt::hash("some session token", 4).unwrap();
+    assert!(CsrfToken::is_well_formed(&token));
+}
+
+#[test]
+fn reject_tokens_with_wrong_shape() {
+    let token = bcrypt::hash("some session token", 4).unwrap();
+
+    assert!(!CsrfToken::is_well_formed(""));
+    assert!(!CsrfToken::is_well_formed("not-a-token"));
+    assert!(!CsrfToken::is_well_formed(&token[1..]));
+    assert!(!CsrfToken::is_well_formed(&token.replace("$2b$", "$3b$")));
+    assert!(!CsrfToken::is_well_formed(&format!(
+        "{}!",
+        &token[..token.len() - 1]
+    )));
+}
+
+#[test]
+fn distinguish_malformed_from_mismatched_tokens() {
+    let well_formed = bcrypt::hash("another session token", 4).unwrap();
+
+    assert_eq!(verify_with_session(""), "missing");
+    assert_eq!(verify_with_session("garbage"), "malformed");
+    assert_eq!(verify_with_session(&well_formed), "mismatch");
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "zeroize")]
+
+#[macro_use]
+extern crate rocket;
+
+use bcrypt::verify;
+use rand::RngCore;
+use rocket::http::Cookie;
+use rocket_csrf_token::CsrfToken;
+
+use base64::{engine::general_purpose, Engine as _};
+
+fn client() -> rocket::local::blocking::Client {
+    rocket::local::blocking::Client::tracked(rocket()).unwrap()
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .attach(rocket_csrf_token::Fairing::default())
+        .mount("/", routes![index])
+}
+
+#[get("/")]
+fn index(csrf_token: CsrfToken) -> String {
+    // Dropping a clone runs the zeroizing drop path while the original stays usable.
+    drop(csrf_token.clone());
+    csrf_token.authenticity_token().unwrap()
+}
+
+#[test]
+fn tokens_stay_usable_with_zeroize_enabled() {
+    let mut raw = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut raw);
+
+    let encoded = general_purpose::STANDARD.encode(raw);
+
+    let body = client()
+        .get("/")
+        .private_cookie(Cookie::new("csrf_token", encoded.clone()))
+        .dispatch()
+        .into_string()
+        .unwrap();
+
+    assert!(verify(&encoded, &body).unwrap());
+}